@@ -0,0 +1,160 @@
+//! RPC interface for the octopus appchain pallet.
+
+use std::sync::Arc;
+
+use codec::Codec;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use serde::Serialize;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_rpc::number::NumberOrHex;
+use sp_runtime::{
+	generic::BlockId,
+	traits::{Block as BlockT, NumberFor},
+};
+
+pub use pallet_octopus_appchain_runtime_api::OctopusAppchainApi as OctopusAppchainRuntimeApi;
+use pallet_octopus_appchain_runtime_api::{
+	NotificationRecord, NotificationStatus, PendingObservation,
+};
+
+/// The bridge state of the octopus appchain pallet.
+#[rpc(server)]
+pub trait OctopusAppchainApi<BlockHash, BlockNumber, AccountId> {
+	/// The observations which have not reached consensus yet, with the stake of their voters.
+	#[rpc(name = "octopus_pendingObservations")]
+	fn pending_observations(
+		&self,
+		at: Option<BlockHash>,
+	) -> Result<Vec<PendingObservation<AccountId>>>;
+
+	/// The status of the notification with the index.
+	#[rpc(name = "octopus_notificationStatus")]
+	fn notification_status(
+		&self,
+		index: u32,
+		at: Option<BlockHash>,
+	) -> Result<NotificationStatus<AccountId, BlockNumber>>;
+
+	/// The records of the latest notifications received by the account.
+	#[rpc(name = "octopus_notificationsOf")]
+	fn notifications_of(
+		&self,
+		receiver: AccountId,
+		at: Option<BlockHash>,
+	) -> Result<Vec<(u32, NotificationRecord<AccountId, BlockNumber>)>>;
+
+	/// The validators planned for the next era, with their stake.
+	#[rpc(name = "octopus_plannedValidators")]
+	fn planned_validators(&self, at: Option<BlockHash>) -> Result<Vec<(AccountId, NumberOrHex)>>;
+
+	/// The account id of the anchor contract on NEAR.
+	#[rpc(name = "octopus_anchorContract")]
+	fn anchor_contract(&self, at: Option<BlockHash>) -> Result<String>;
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The call to runtime failed.
+	RuntimeError,
+}
+
+impl From<Error> for i64 {
+	fn from(e: Error) -> i64 {
+		match e {
+			Error::RuntimeError => 1,
+		}
+	}
+}
+
+/// An implementation of the octopus appchain specific RPC methods.
+pub struct OctopusAppchain<C, B> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> OctopusAppchain<C, B> {
+	/// Create new `OctopusAppchain` with the given reference to the client.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+fn runtime_error(message: &str, error: impl std::fmt::Debug) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(Error::RuntimeError.into()),
+		message: message.into(),
+		data: Some(format!("{:?}", error).into()),
+	}
+}
+
+impl<C, Block, AccountId> OctopusAppchainApi<<Block as BlockT>::Hash, NumberFor<Block>, AccountId>
+	for OctopusAppchain<C, Block>
+where
+	Block: BlockT,
+	C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: OctopusAppchainRuntimeApi<Block, AccountId, NumberFor<Block>>,
+	AccountId: Codec + Serialize + Send + Sync + 'static,
+{
+	fn pending_observations(
+		&self,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<PendingObservation<AccountId>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.pending_observations(&at)
+			.map_err(|e| runtime_error("Unable to query pending observations.", e))
+	}
+
+	fn notification_status(
+		&self,
+		index: u32,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<NotificationStatus<AccountId, NumberFor<Block>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.notification_status(&at, index)
+			.map_err(|e| runtime_error("Unable to query notification status.", e))
+	}
+
+	fn notifications_of(
+		&self,
+		receiver: AccountId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<(u32, NotificationRecord<AccountId, NumberFor<Block>>)>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.notifications_of(&at, receiver)
+			.map_err(|e| runtime_error("Unable to query notifications of the receiver.", e))
+	}
+
+	fn planned_validators(
+		&self,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<(AccountId, NumberOrHex)>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		let validators = api
+			.planned_validators(&at)
+			.map_err(|e| runtime_error("Unable to query planned validators.", e))?;
+		Ok(validators
+			.into_iter()
+			.map(|(validator, stake)| (validator, stake.into()))
+			.collect())
+	}
+
+	fn anchor_contract(&self, at: Option<<Block as BlockT>::Hash>) -> Result<String> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		let anchor_contract = api
+			.anchor_contract(&at)
+			.map_err(|e| runtime_error("Unable to query anchor contract.", e))?;
+		Ok(String::from_utf8_lossy(&anchor_contract).into_owned())
+	}
+}
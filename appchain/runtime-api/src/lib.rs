@@ -0,0 +1,45 @@
+//! Runtime API definition for the octopus appchain pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use sp_std::vec::Vec;
+
+pub use pallet_octopus_appchain::{NotificationRecord, NotificationStatus, PendingObservation};
+
+sp_api::decl_runtime_apis! {
+	/// The bridge state of the octopus appchain pallet.
+	pub trait OctopusAppchainApi<AccountId, BlockNumber> where
+		AccountId: Codec,
+		BlockNumber: Codec,
+	{
+		/// The id of the next validator set to observe.
+		fn next_set_id() -> u32;
+
+		/// The id of the next notification to observe.
+		fn next_notification_id() -> u32;
+
+		/// The validators planned for the next era, with their stake.
+		fn planned_validators() -> Vec<(AccountId, u128)>;
+
+		/// The observations which have not reached consensus yet, with the stake of their voters.
+		fn pending_observations() -> Vec<PendingObservation<AccountId>>;
+
+		/// The records of the notifications from `start_id`, at most `limit` of them.
+		fn notification_history(
+			start_id: u32,
+			limit: u32,
+		) -> Vec<(u32, NotificationRecord<AccountId, BlockNumber>)>;
+
+		/// The status of the notification with the index.
+		fn notification_status(index: u32) -> NotificationStatus<AccountId, BlockNumber>;
+
+		/// The records of the latest notifications received by the account.
+		fn notifications_of(
+			receiver: AccountId,
+		) -> Vec<(u32, NotificationRecord<AccountId, BlockNumber>)>;
+
+		/// The account id of the anchor contract on NEAR.
+		fn anchor_contract() -> Vec<u8>;
+	}
+}
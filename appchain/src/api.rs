@@ -0,0 +1,117 @@
+//! Queries of the bridge state, served by the `OctopusAppchainApi` runtime api.
+
+use super::*;
+
+/// The maximum number of notifications returned by a single history query.
+pub const MAX_NOTIFICATION_HISTORY_QUERY: u32 = 100;
+
+/// An observation which has not reached consensus yet, with the stake of its voters.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize))]
+#[cfg_attr(feature = "std", serde(bound(serialize = "AccountId: Encode + Serialize")))]
+pub struct PendingObservation<AccountId> {
+	pub observation_type: ObservationType,
+	pub observation_id: u32,
+	pub observation: Observation<AccountId>,
+	pub voters: Vec<AccountId>,
+	/// The active stake of the voters.
+	#[cfg_attr(feature = "std", serde(serialize_with = "serialize_to_str"))]
+	pub stake: u128,
+	/// The share of the active stake behind the observation.
+	pub stake_percentage: Perbill,
+}
+
+/// The status of a notification from the mainchain.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize))]
+pub enum NotificationStatus<AccountId, BlockNumber> {
+	/// The notification has not reached consensus yet.
+	Pending,
+	/// The notification has been executed, with its record.
+	Executed(NotificationRecord<AccountId, BlockNumber>),
+	/// The history of the notification has been pruned.
+	Pruned,
+}
+
+impl<T: Config> Pallet<T> {
+	/// The account id of the anchor contract on NEAR.
+	pub fn anchor_contract() -> Vec<u8> {
+		<AnchorContract<T>>::get()
+	}
+
+	/// The types and ids of the observations accepted by `check_observation`.
+	pub(crate) fn pending_observation_keys() -> Vec<(ObservationType, u32)> {
+		let next_notification_id = NextNotificationId::<T>::get();
+		let notification_ids =
+			next_notification_id..next_notification_id.saturating_add(T::RequestEventLimit::get());
+
+		let mut keys = vec![
+			(ObservationType::UpdateValidatorSet, NextSetId::<T>::get()),
+			(ObservationType::UpdateAppchainState, NextStateChangeId::<T>::get()),
+		];
+		for observation_type in [
+			ObservationType::Burn,
+			ObservationType::LockAsset,
+			ObservationType::BurnNativeAsset,
+			ObservationType::LockNft,
+			ObservationType::BurnNft,
+			ObservationType::GovernanceCall,
+		] {
+			keys.extend(notification_ids.clone().map(|id| (observation_type, id)));
+		}
+		keys
+	}
+
+	/// The observations which have not reached consensus yet.
+	///
+	/// Only the observations with the ids accepted by `check_observation` are read.
+	pub fn pending_observations() -> Vec<PendingObservation<T::AccountId>> {
+		let total_stake = T::LposInterface::active_total_stake().unwrap_or(0);
+
+		Self::pending_observation_keys()
+			.into_iter()
+			.flat_map(|(observation_type, observation_id)| {
+				<Observations<T>>::get(observation_type, observation_id)
+					.into_iter()
+					.map(move |observation| (observation_type, observation_id, observation))
+			})
+			.map(|(observation_type, observation_id, observation)| {
+				let hash = Self::observation_hash(&observation);
+				let stake = Self::observation_stake(&hash);
+				PendingObservation {
+					observation_type,
+					observation_id,
+					observation,
+					voters: Self::voters_of(&hash),
+					stake,
+					stake_percentage: Perbill::from_rational(stake, total_stake.max(1)),
+				}
+			})
+			.collect()
+	}
+
+	/// The records of the notifications from `start_id`, at most
+	/// `MAX_NOTIFICATION_HISTORY_QUERY` of them.
+	pub fn notification_history(
+		start_id: u32,
+		limit: u32,
+	) -> Vec<(u32, NotificationRecord<T::AccountId, T::BlockNumber>)> {
+		let end_id = start_id
+			.saturating_add(limit.min(MAX_NOTIFICATION_HISTORY_QUERY))
+			.min(NextNotificationId::<T>::get());
+		(start_id..end_id)
+			.filter_map(|id| <NotificationHistory<T>>::get(id).map(|record| (id, record)))
+			.collect()
+	}
+
+	/// The status of the notification with the index.
+	pub fn notification_status(index: u32) -> NotificationStatus<T::AccountId, T::BlockNumber> {
+		if index >= NextNotificationId::<T>::get() {
+			return NotificationStatus::Pending;
+		}
+		match <NotificationHistory<T>>::get(index) {
+			Some(record) => NotificationStatus::Executed(record),
+			None => NotificationStatus::Pruned,
+		}
+	}
+}
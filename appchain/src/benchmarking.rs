@@ -1,11 +1,14 @@
 use super::*;
-use frame_benchmarking::{benchmarks, whitelisted_caller};
+use frame_benchmarking::{account, benchmarks, whitelisted_caller};
 use frame_system::RawOrigin;
+use sp_runtime::traits::SaturatedConversion;
 // use rand::Rng;
 
 #[allow(unused_imports)]
 use crate::Pallet as OctopusAppchain;
 
+const SEED: u32 = 0;
+
 fn get_public<T: Config>() -> (T::Public, T::Signature) {
 	let key = <T::AuthorityId as AppCrypto<
 		<T as SigningTypes>::Public,
@@ -52,7 +55,37 @@ fn create_payload_and_signature<T: Config>(
 	(obs_payload, msig)
 }
 
+fn insert_failed_burn_notification<T: Config>(notification_id: u32) {
+	let min = T::Currency::minimum_balance();
+	let pallet_account = OctopusAppchain::<T>::pallet_account();
+	T::Currency::make_free_balance_be(&pallet_account, min * 10u32.into());
+
+	let observation = Observation::Burn(BurnEvent {
+		index: notification_id,
+		sender_id: "andy-pallet-test.testnet".to_string().as_bytes().to_vec(),
+		receiver: account("receiver", notification_id, SEED),
+		amount: min.saturated_into(),
+	});
+	let record =
+		NotificationRecord::new(&observation, Zero::zero(), NotificationResult::UnlockFailed);
+	<NotificationHistory<T>>::insert(notification_id, record.unwrap());
+	<FailedNotifications<T>>::insert(notification_id, (observation, 0));
+}
+
+/// Limit the transfers of the token and of all the tokens, so that their volumes are tracked.
+fn set_transfer_limits<T: Config>(direction: TransferDirection, token: BridgedToken<AssetIdOf<T>>) {
+	let limit = TransferLimit { max_amount: u128::MAX, window: 100u32.into() };
+	<TransferLimits<T>>::insert(direction, Some(token), limit);
+	<TransferLimits<T>>::insert(direction, None::<BridgedToken<AssetIdOf<T>>>, limit);
+}
+
 benchmarks! {
+	where_clause {
+	  where
+		T::Assets: fungibles::Create<T::AccountId>,
+		T::Uniques: nonfungibles::Create<T::AccountId>,
+	}
+
 	force_set_is_activated {
 	  let mut is_activated: bool = true;
 	  for i in 0 .. 100 {
@@ -87,29 +120,311 @@ benchmarks! {
 
 	lock {
 	  let account = OctopusAppchain::<T>::pallet_account();
+	  let min = T::Currency::minimum_balance();
+	  T::Currency::make_free_balance_be(&account, min * 100u32.into());
 	  let pallet_account = RawOrigin::Signed(account);
+	  <CurrentAppchainState<T>>::put(AppchainState::Active);
+	  set_transfer_limits::<T>(TransferDirection::Outbound, BridgedToken::Native);
+	  <BridgeFees<T>>::insert(
+		BridgedToken::Native, BridgeFee { flat: 0, basis_points: 100, min_amount: 0 });
+	}: lock(
+	  pallet_account, "test-account.testnet".to_string().as_bytes().to_vec(), min * 10u32.into())
+
+	set_mainchain_rpc_endpoints {
+	  let max_length = T::MaxRpcEndpointLength::get();
+	  let b in 0 .. T::MaxRpcEndpoints::get() * max_length;
+	  // `b` bytes of endpoints, each as long as possible.
+	  let mut endpoints: Vec<Vec<u8>> = Vec::new();
+	  let mut remaining = b;
+	  while remaining > 0 {
+		let length = remaining.min(max_length).max(8);
+		let mut endpoint = b"https://".to_vec();
+		endpoint.resize(length as usize, b'a');
+		endpoints.push(endpoint);
+		remaining = remaining.saturating_sub(length);
+	  }
+	}: set_mainchain_rpc_endpoints(RawOrigin::Root, endpoints)
+
+	submit_observations {
+	  let o in 1 .. T::RequestEventLimit::get();
+	  let v in 1 .. T::MaxValidators::get();
+	  // The genesis validators hold the active stake, so their votes reach the consensus.
+	  let validators: Vec<T::AccountId> =
+		<PlannedValidators<T>>::get().into_iter().map(|(id, _)| id).collect();
+	  let validator = validators.first().cloned().ok_or("No validator in the genesis")?;
+
+	  let min = T::Currency::minimum_balance();
+	  let pallet_account = OctopusAppchain::<T>::pallet_account();
+	  T::Currency::make_free_balance_be(&pallet_account, min * (o + 1).into());
+
+	  set_transfer_limits::<T>(TransferDirection::Inbound, BridgedToken::Native);
+
+	  let next_notification_id = NextNotificationId::<T>::get();
+	  let observations: Vec<Observation<T::AccountId>> = (0 .. o)
+		.map(|i| Observation::Burn(BurnEvent {
+		  index: next_notification_id + i,
+		  sender_id: "andy-pallet-test.testnet".to_string().as_bytes().to_vec(),
+		  receiver: account("receiver", i, SEED),
+		  amount: min.saturated_into(),
+		}))
+		.collect();
+	  // Every observation is voted by the other validators, and `v` more voters are spread
+	  // over the observations.
+	  for (i, observation) in observations.iter().enumerate() {
+		let mut voters: Vec<T::AccountId> = validators.iter().skip(1).cloned().collect();
+		voters.extend((0 .. v).filter(|j| *j % o == i as u32).map(|j| account("voter", j, SEED)));
+		// Leave room for the vote of the submitting validator.
+		voters.truncate(T::MaxValidators::get() as usize - 1);
+		let hash = OctopusAppchain::<T>::observation_hash(observation);
+		let time_slot = (ObservationType::Burn, next_notification_id + i as u32);
+		for voter in voters.iter() {
+		  <Observing<T>>::insert(hash, voter, ());
+		  <VotedObservations<T>>::insert(time_slot, voter, hash);
+		}
+		// The tally is outdated, so the vote reads the stake of all the voters.
+		<ObservationTallies<T>>::insert(hash, ObservationTally::untallied(voters.len() as u32));
+	  }
+	}: {
+	  for observation in observations {
+		OctopusAppchain::<T>::submit_observation(&validator, observation)?;
+	  }
+	}
+	verify {
+	  assert_eq!(NextNotificationId::<T>::get(), next_notification_id + o);
+	}
+
+	mint_asset {
+	  let asset_id: AssetIdOf<T> = 1000;
+	  let receiver: T::AccountId = account("receiver", 0, SEED);
+	  <T::Assets as fungibles::Create<T::AccountId>>::create(
+		asset_id, receiver.clone(), true, 1)?;
+	}: mint_asset(
+	  RawOrigin::Root,
+	  asset_id,
+	  "test-account.testnet".to_string().as_bytes().to_vec(),
+	  T::Lookup::unlookup(receiver.clone()),
+	  100000)
+	verify {
+	  assert_eq!(
+		<T::Assets as fungibles::Inspect<T::AccountId>>::balance(asset_id, &receiver), 100000);
+	}
+
+	burn_asset {
+	  let asset_id: AssetIdOf<T> = 1000;
+	  let caller: T::AccountId = whitelisted_caller();
+	  <T::Assets as fungibles::Create<T::AccountId>>::create(asset_id, caller.clone(), true, 1)?;
+	  <T::Assets as fungibles::Mutate<T::AccountId>>::mint_into(asset_id, &caller, 200000)?;
+	  OctopusAppchain::<T>::register_asset(
+		RawOrigin::Root.into(), "test-token.testnet".to_string().as_bytes().to_vec(), asset_id)?;
+	  <CurrentAppchainState<T>>::put(AppchainState::Active);
+	  set_transfer_limits::<T>(TransferDirection::Outbound, BridgedToken::Asset(asset_id));
+	  <BridgeFees<T>>::insert(
+		BridgedToken::Asset(asset_id), BridgeFee { flat: 0, basis_points: 100, min_amount: 0 });
+	}: burn_asset(
+	  RawOrigin::Signed(caller.clone()),
+	  asset_id,
+	  "test-account.testnet".to_string().as_bytes().to_vec(),
+	  100000)
+	verify {
+	  assert_eq!(
+		<T::Assets as fungibles::Inspect<T::AccountId>>::balance(asset_id, &caller), 100000);
+	}
+
+	register_asset {
+	  let token_id = "test-token.testnet".to_string().as_bytes().to_vec();
+	}: register_asset(RawOrigin::Root, token_id.clone(), 1000)
+	verify {
+	  assert_eq!(<TokenIdByAssetId<T>>::get(1000), Some(token_id));
+	}
+
+	update_asset {
+	  let token_id = "test-token.testnet".to_string().as_bytes().to_vec();
+	  OctopusAppchain::<T>::register_asset(RawOrigin::Root.into(), token_id.clone(), 1000)?;
+	}: update_asset(RawOrigin::Root, token_id.clone(), 1001)
+	verify {
+	  assert_eq!(<TokenIdByAssetId<T>>::get(1001), Some(token_id));
+	}
+
+	deregister_asset {
+	  let token_id = "test-token.testnet".to_string().as_bytes().to_vec();
+	  OctopusAppchain::<T>::register_asset(RawOrigin::Root.into(), token_id.clone(), 1000)?;
+	}: deregister_asset(RawOrigin::Root, token_id)
+	verify {
+	  assert_eq!(<TokenIdByAssetId<T>>::get(1000), None);
+	}
+
+	set_asset_decimals {
+	  OctopusAppchain::<T>::register_asset(
+		RawOrigin::Root.into(), "test-token.testnet".to_string().as_bytes().to_vec(), 1000)?;
+	  let decimals = TokenDecimals { near: 24, appchain: 18 };
+	}: set_asset_decimals(RawOrigin::Root, 1000, Some(decimals))
+	verify {
+	  assert_eq!(<AssetDecimals<T>>::get(1000), Some(decimals));
+	}
+
+	set_native_token_decimals {
+	  let decimals = TokenDecimals { near: 24, appchain: 18 };
+	}: set_native_token_decimals(RawOrigin::Root, Some(decimals))
+	verify {
+	  assert_eq!(<NativeTokenDecimals<T>>::get(), Some(decimals));
+	}
+
+	retry_notification {
+	  let caller: T::AccountId = whitelisted_caller();
+	  insert_failed_burn_notification::<T>(0);
+	  set_transfer_limits::<T>(TransferDirection::Inbound, BridgedToken::Native);
+	}: retry_notification(RawOrigin::Signed(caller), 0)
+	verify {
+	  assert!(!<FailedNotifications<T>>::contains_key(0));
+	}
+
+	redirect_notification {
+	  insert_failed_burn_notification::<T>(0);
+	  set_transfer_limits::<T>(TransferDirection::Inbound, BridgedToken::Native);
+	  let receiver: T::AccountId = account("redirected", 0, SEED);
+	}: redirect_notification(RawOrigin::Root, 0, T::Lookup::unlookup(receiver))
+	verify {
+	  assert!(!<FailedNotifications<T>>::contains_key(0));
+	}
+
+	refund_notification {
+	  insert_failed_burn_notification::<T>(0);
+	}: refund_notification(RawOrigin::Root, 0)
+	verify {
+	  assert!(!<FailedNotifications<T>>::contains_key(0));
+	}
+
+	set_transfer_limit {
+	  let limit = TransferLimit { max_amount: 1_000_000, window: 100u32.into() };
+	}: set_transfer_limit(
+	  RawOrigin::Root, TransferDirection::Inbound, Some(BridgedToken::Native), Some(limit))
+	verify {
+	  assert_eq!(
+		<TransferLimits<T>>::get(TransferDirection::Inbound, Some(BridgedToken::Native)),
+		Some(limit));
+	}
+
+	resume_transfers {
+	  set_transfer_limits::<T>(TransferDirection::Inbound, BridgedToken::Native);
+	  <TransferVolumes<T>>::insert(
+		TransferDirection::Inbound, Some(BridgedToken::Native), TransferVolume::default());
+	  <TransferVolumes<T>>::insert(
+		TransferDirection::Inbound, None::<BridgedToken<AssetIdOf<T>>>, TransferVolume::default());
+	  <TransfersPaused<T>>::insert(TransferDirection::Inbound, true);
+	}: resume_transfers(RawOrigin::Root, TransferDirection::Inbound)
+	verify {
+	  assert!(!<TransfersPaused<T>>::get(TransferDirection::Inbound));
+	}
+
+	set_bridge_fee {
+	  let fee = BridgeFee { flat: 1000, basis_points: 10, min_amount: 10000 };
+	}: set_bridge_fee(RawOrigin::Root, BridgedToken::Native, Some(fee))
+	verify {
+	  assert_eq!(<BridgeFees<T>>::get(BridgedToken::Native), Some(fee));
+	}
+
+	set_consensus_threshold {
+	  let threshold = Perbill::from_percent(75);
+	}: set_consensus_threshold(RawOrigin::Root, Some(threshold))
+	verify {
+	  assert_eq!(<ConsensusThreshold<T>>::get(), Some(threshold));
+	}
+
+	set_operation_paused {
+	}: set_operation_paused(RawOrigin::Root, BridgeOperation::Lock, true)
+	verify {
+	  assert!(<PausedOperations<T>>::get(BridgeOperation::Lock));
+	}
+
+	set_asset_paused {
+	}: set_asset_paused(RawOrigin::Root, 1000, true)
+	verify {
+	  assert!(<PausedAssets<T>>::get(1000));
+	}
+
+	lock_asset {
+	  let asset_id: AssetIdOf<T> = 1000;
+	  let caller: T::AccountId = whitelisted_caller();
+	  <T::Assets as fungibles::Create<T::AccountId>>::create(asset_id, caller.clone(), true, 1)?;
+	  <T::Assets as fungibles::Mutate<T::AccountId>>::mint_into(asset_id, &caller, 200000)?;
+	  <CurrentAppchainState<T>>::put(AppchainState::Active);
+	  set_transfer_limits::<T>(TransferDirection::Outbound, BridgedToken::Asset(asset_id));
+	  <BridgeFees<T>>::insert(
+		BridgedToken::Asset(asset_id), BridgeFee { flat: 0, basis_points: 100, min_amount: 0 });
+	}: lock_asset(
+	  RawOrigin::Signed(caller.clone()),
+	  asset_id,
+	  "test-account.testnet".to_string().as_bytes().to_vec(),
+	  100000)
+	verify {
+	  assert_eq!(
+		<T::Assets as fungibles::Inspect<T::AccountId>>::balance(asset_id, &caller), 100000);
+	}
+
+	register_nft_class {
+	  let contract_id = "test-nft.testnet".to_string().as_bytes().to_vec();
+	}: register_nft_class(RawOrigin::Root, contract_id.clone(), 1000)
+	verify {
+	  assert_eq!(<ContractIdByClassId<T>>::get(1000), Some(contract_id));
+	}
+
+	deregister_nft_class {
+	  let contract_id = "test-nft.testnet".to_string().as_bytes().to_vec();
+	  OctopusAppchain::<T>::register_nft_class(RawOrigin::Root.into(), contract_id.clone(), 1000)?;
+	}: deregister_nft_class(RawOrigin::Root, contract_id)
+	verify {
+	  assert_eq!(<ContractIdByClassId<T>>::get(1000), None);
+	}
+
+	burn_nft {
+	  let caller: T::AccountId = whitelisted_caller();
 	  let min = T::Currency::minimum_balance();
-	  <IsActivated<T>>::put(true);
-	}: lock(pallet_account, "test-account.testnet".to_string().as_bytes().to_vec(), min)
-
-	// //Note: need to config for this pallet first.
-	// submit_observations {
-	//   let (obs_payload, msig) = create_payload_and_signature::<T>();
-	// }: submit_observations(RawOrigin::None, obs_payload, msig)
-
-	// TODO:
-	// mint_asset {
-	//   let caller: T::AccountId = whitelisted_caller();
-	//   let receiver = T::Lookup::unlookup(caller.clone());
-	//   T::Currency::make_free_balance_be(&caller, T::Currency::minimum_balance());
-	// }: mint_asset(
-	//     RawOrigin::Root,
-	//     0,
-	//     "test-account.testnet".to_string().as_bytes().to_vec(),
-	//     receiver,
-	//     100000 )
-
-	//TODO: burn_asset
+	  T::Currency::make_free_balance_be(&caller, min * 1_000_000u32.into());
+	  let contract_id = "test-nft.testnet".to_string().as_bytes().to_vec();
+	  <T::Uniques as nonfungibles::Create<T::AccountId>>::create_class(&1000, &caller, &caller)?;
+	  OctopusAppchain::<T>::register_nft_class(RawOrigin::Root.into(), contract_id.clone(), 1000)?;
+	  OctopusAppchain::<T>::mint_nft_inner(
+		contract_id,
+		b"nft-0".to_vec(),
+		"test-account.testnet".to_string().as_bytes().to_vec(),
+		caller.clone(),
+		TokenMetadata::default())?;
+	  let instance_id = OctopusAppchain::<T>::nft_instance_id(b"nft-0");
+	  <CurrentAppchainState<T>>::put(AppchainState::Active);
+	}: burn_nft(
+	  RawOrigin::Signed(caller.clone()),
+	  1000,
+	  instance_id,
+	  "test-account.testnet".to_string().as_bytes().to_vec())
+	verify {
+	  assert_eq!(
+		<T::Uniques as nonfungibles::Inspect<T::AccountId>>::owner(&1000, &instance_id), None);
+	}
+
+	lock_nft {
+	  let caller: T::AccountId = whitelisted_caller();
+	  let min = T::Currency::minimum_balance();
+	  T::Currency::make_free_balance_be(&caller, min * 1_000_000u32.into());
+	  <T::Uniques as nonfungibles::Create<T::AccountId>>::create_class(&1000, &caller, &caller)?;
+	  <T::Uniques as nonfungibles::Mutate<T::AccountId>>::mint_into(&1000, &0, &caller)?;
+	  <CurrentAppchainState<T>>::put(AppchainState::Active);
+	}: lock_nft(
+	  RawOrigin::Signed(caller.clone()),
+	  1000,
+	  0,
+	  "test-account.testnet".to_string().as_bytes().to_vec())
+	verify {
+	  assert_eq!(
+		<T::Uniques as nonfungibles::Inspect<T::AccountId>>::owner(&1000, &0),
+		Some(OctopusAppchain::<T>::account_id()));
+	}
+
+	force_set_appchain_state {
+	  <CurrentAppchainState<T>>::put(AppchainState::Active);
+	}: force_set_appchain_state(RawOrigin::Root, AppchainState::Frozen)
+	verify {
+	  assert_eq!(<CurrentAppchainState<T>>::get(), AppchainState::Frozen);
+	}
 
 	impl_benchmark_test_suite!(OctopusAppchain, crate::mock::new_tester(), crate::mock::Test, );
 }
@@ -0,0 +1,89 @@
+//! Conversion of token amounts between the decimals on NEAR and the decimals on the appchain.
+
+use super::*;
+
+/// The decimals of a bridged token on NEAR and of its counterpart on the appchain.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct TokenDecimals {
+	pub near: u8,
+	pub appchain: u8,
+}
+
+impl TokenDecimals {
+	/// Whether amounts can be scaled between both decimals without overflowing the unit.
+	pub fn is_valid(&self) -> bool {
+		unit(self.near.max(self.appchain) - self.near.min(self.appchain)).is_some()
+	}
+
+	/// Convert an amount on NEAR into the amount on the appchain.
+	///
+	/// Fails if the amount can not be represented exactly with the decimals on the appchain, as
+	/// the whole amount has already been burnt or locked on NEAR.
+	pub fn to_appchain(&self, amount: u128) -> Option<u128> {
+		if self.appchain >= self.near {
+			amount.checked_mul(unit(self.appchain - self.near)?)
+		} else {
+			let unit = unit(self.near - self.appchain)?;
+			if amount % unit != 0 {
+				return None;
+			}
+			Some(amount / unit)
+		}
+	}
+
+	/// Convert an amount on the appchain into the amount on NEAR.
+	///
+	/// Fails if the amount can not be represented exactly with the decimals on NEAR.
+	pub fn to_near(&self, amount: u128) -> Option<u128> {
+		if self.near >= self.appchain {
+			amount.checked_mul(unit(self.near - self.appchain)?)
+		} else {
+			let unit = unit(self.appchain - self.near)?;
+			if amount % unit != 0 {
+				return None;
+			}
+			Some(amount / unit)
+		}
+	}
+
+	/// The largest amount on the appchain, up to `amount`, which can be converted exactly into
+	/// the amount on NEAR.
+	pub fn truncate(&self, amount: u128) -> u128 {
+		if self.appchain > self.near {
+			match unit(self.appchain - self.near) {
+				Some(unit) => amount - amount % unit,
+				None => 0,
+			}
+		} else {
+			amount
+		}
+	}
+}
+
+fn unit(decimals: u8) -> Option<u128> {
+	10u128.checked_pow(decimals as u32)
+}
+
+impl<T: Config> Pallet<T> {
+	/// Convert an amount on NEAR into the amount on the appchain, with the given decimals.
+	pub(crate) fn amount_from_near(
+		decimals: Option<TokenDecimals>,
+		amount: u128,
+	) -> Result<u128, Error<T>> {
+		match decimals {
+			Some(decimals) => decimals.to_appchain(amount).ok_or(Error::<T>::AmountNotConvertible),
+			None => Ok(amount),
+		}
+	}
+
+	/// Convert an amount on the appchain into the amount on NEAR, with the given decimals.
+	pub(crate) fn amount_to_near(
+		decimals: Option<TokenDecimals>,
+		amount: u128,
+	) -> Result<u128, Error<T>> {
+		match decimals {
+			Some(decimals) => decimals.to_near(amount).ok_or(Error::<T>::AmountNotConvertible),
+			None => Ok(amount),
+		}
+	}
+}
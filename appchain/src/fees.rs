@@ -0,0 +1,60 @@
+//! Fees of the outbound transfers.
+//!
+//! The fee of a transfer is charged in the transferred token and deducted from the transferred
+//! amount, together with the dust which can not be represented with the decimals of the token on
+//! NEAR. The fee is handed to `OnBridgeFee` for the native token or to `OnAssetBridgeFee` for
+//! the assets.
+
+use super::*;
+use sp_runtime::helpers_128bit::multiply_by_rational;
+
+/// The basis points of a whole amount.
+pub const MAX_BASIS_POINTS: u16 = 10_000;
+
+/// The fee schedule of the outbound transfers of a token, in its units on the appchain.
+#[derive(Encode, Decode, Clone, Copy, Default, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct BridgeFee {
+	/// The fee charged on every transfer.
+	pub flat: u128,
+	/// The fee charged in proportion of the transferred amount, in basis points.
+	pub basis_points: u16,
+	/// The minimum amount of a transfer, fee included.
+	pub min_amount: u128,
+}
+
+impl BridgeFee {
+	pub fn is_valid(&self) -> bool {
+		self.basis_points <= MAX_BASIS_POINTS
+	}
+
+	/// The fee of a transfer of `amount`, fee included.
+	pub fn fee_of(&self, amount: u128) -> u128 {
+		let proportional =
+			multiply_by_rational(amount, self.basis_points as u128, MAX_BASIS_POINTS as u128)
+				.unwrap_or(amount);
+		self.flat.saturating_add(proportional)
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Split the amount of an outbound transfer into the bridged amount and the fee.
+	///
+	/// The dust of the bridged amount which can not be represented on NEAR is charged with the
+	/// fee.
+	pub(crate) fn split_bridge_fee(
+		token: BridgedToken<AssetIdOf<T>>,
+		amount: u128,
+		decimals: Option<TokenDecimals>,
+	) -> Result<(u128, u128), Error<T>> {
+		let fee = match <BridgeFees<T>>::get(token) {
+			Some(fee) => fee,
+			None => return Ok((amount, 0)),
+		};
+		ensure!(amount >= fee.min_amount, Error::<T>::AmountTooLow);
+
+		let bridged = amount.saturating_sub(fee.fee_of(amount));
+		let bridged = decimals.map_or(bridged, |decimals| decimals.truncate(bridged));
+		ensure!(!bridged.is_zero(), Error::<T>::AmountTooLow);
+		Ok((bridged, amount - bridged))
+	}
+}
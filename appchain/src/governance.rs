@@ -0,0 +1,95 @@
+//! Calls from the anchor contract, dispatched with `MainchainOrigin` once their notification
+//! reaches consensus.
+//!
+//! Only the calls allowed by `MainchainCallFilter` are dispatched, so that the registry on NEAR
+//! can govern the parameters of the appchain without sudo. A call which fails is not retried,
+//! the registry sends a new one instead.
+
+use super::*;
+use codec::DecodeLimit;
+use frame_support::{
+	storage::{with_transaction, TransactionOutcome},
+	traits::EnsureOrigin,
+};
+use sp_std::marker::PhantomData;
+
+/// The maximum depth of nested calls in a call from the mainchain, the same as for an extrinsic
+/// (`sp_api::MAX_EXTRINSIC_DEPTH`).
+const MAX_MAINCHAIN_CALL_DEPTH: u32 = 256;
+
+/// The origin of the calls from the mainchain.
+#[derive(Clone, PartialEq, Eq, RuntimeDebug, Encode, Decode, TypeInfo)]
+pub enum MainchainOrigin {
+	/// A call from the anchor contract, observed by the validators.
+	Mainchain,
+}
+
+/// Ensure that the origin is the mainchain.
+///
+/// Use it in `EnsureOneOf` with `EnsureRoot` for the origins governed from NEAR.
+pub struct EnsureMainchain<AccountId>(PhantomData<AccountId>);
+
+impl<O: Into<Result<MainchainOrigin, O>> + From<MainchainOrigin>, AccountId> EnsureOrigin<O>
+	for EnsureMainchain<AccountId>
+{
+	type Success = ();
+
+	fn try_origin(o: O) -> Result<Self::Success, O> {
+		o.into().map(|_| ())
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn successful_origin() -> O {
+		O::from(MainchainOrigin::Mainchain)
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// The weight reserved for the calls from the mainchain among the observations.
+	pub(crate) fn mainchain_calls_weight(observations: &[Observation<T::AccountId>]) -> Weight {
+		let calls = observations
+			.iter()
+			.filter(|observation| matches!(observation, Observation::GovernanceCall(_)))
+			.count();
+		T::MaxMainchainCallWeight::get().saturating_mul(calls as Weight)
+	}
+
+	/// Decode and dispatch a call from the mainchain, if it is allowed.
+	pub(crate) fn dispatch_mainchain_call(
+		notification_id: u32,
+		encoded_call: &[u8],
+	) -> Result<(), NotificationResult> {
+		let call = match <T as Config>::Call::decode_all_with_depth_limit(
+			MAX_MAINCHAIN_CALL_DEPTH,
+			encoded_call,
+		) {
+			Ok(call) => call,
+			Err(error) => {
+				log!(warn, "failed to decode mainchain call {:?}: {:?}", notification_id, error);
+				Self::deposit_event(Event::MainchainCallRejected(notification_id));
+				return Err(NotificationResult::CallRejected);
+			},
+		};
+		if !T::MainchainCallFilter::contains(&call)
+			|| call.get_dispatch_info().weight > T::MaxMainchainCallWeight::get()
+		{
+			log!(warn, "mainchain call {:?} is not allowed: {:?}", notification_id, call);
+			Self::deposit_event(Event::MainchainCallRejected(notification_id));
+			return Err(NotificationResult::CallRejected);
+		}
+
+		let result = with_transaction(|| {
+			let result = call.dispatch(MainchainOrigin::Mainchain.into());
+			match result {
+				Ok(_) => TransactionOutcome::Commit(result),
+				Err(_) => TransactionOutcome::Rollback(result),
+			}
+		});
+		log!(info, "mainchain call {:?} dispatched: {:?}", notification_id, result);
+		Self::deposit_event(Event::MainchainCallDispatched(
+			notification_id,
+			result.map(|_| ()).map_err(|e| e.error),
+		));
+		result.map(|_| ()).map_err(|_| NotificationResult::CallFailed)
+	}
+}
@@ -0,0 +1,137 @@
+//! Health of the mainchain rpc endpoints, recorded by the offchain worker of each validator.
+//!
+//! A failing endpoint cools down for 30 seconds after its first consecutive failure, and the
+//! period doubles with each further failure up to 30 minutes. The other endpoints are ranked by
+//! their success rate, smoothed with Laplace's rule `(successes + 1) / (queries + 2)` so that
+//! a new endpoint starts at 50% instead of being ranked on a handful of queries.
+
+use super::*;
+
+/// Cool-down period of an endpoint after its first consecutive failure, in milliseconds.
+const COOL_DOWN_BASE: u64 = 30_000;
+
+/// Upper bound of the cool-down period of a failing endpoint, in milliseconds.
+const MAX_COOL_DOWN: u64 = 30 * 60_000;
+
+/// The statistics are halved once this number of queries is recorded, so that the recent
+/// behaviour of an endpoint weighs more than its history.
+const MAX_RECORDED_QUERIES: u32 = 100;
+
+/// Health statistics of a mainchain rpc endpoint, kept in the offchain persistent storage.
+#[derive(Encode, Decode, Clone, Default, PartialEq, Eq, RuntimeDebug)]
+pub struct EndpointHealth {
+	/// Number of successful queries.
+	pub successes: u32,
+	/// Number of failed queries.
+	pub failures: u32,
+	/// Number of failed queries since the last successful one.
+	pub consecutive_failures: u32,
+	/// Moving average of the latency of successful queries, in milliseconds.
+	pub average_latency: u64,
+	/// The endpoint should not be used before this unix timestamp, in milliseconds.
+	pub cool_down_until: u64,
+}
+
+impl EndpointHealth {
+	/// The success rate in per mill. An endpoint without any record is rated 500.
+	pub fn success_rate(&self) -> u64 {
+		let successes = self.successes as u64;
+		let total = successes + self.failures as u64;
+		(successes + 1) * 1000 / (total + 2)
+	}
+
+	pub fn is_cooling_down(&self, now: u64) -> bool {
+		now < self.cool_down_until
+	}
+
+	fn on_success(&mut self, latency: u64) {
+		self.average_latency = if self.successes == 0 {
+			latency
+		} else {
+			(self.average_latency.saturating_mul(3).saturating_add(latency)) / 4
+		};
+		self.successes = self.successes.saturating_add(1);
+		self.consecutive_failures = 0;
+		self.cool_down_until = 0;
+		self.decay();
+	}
+
+	fn on_failure(&mut self, now: u64) {
+		self.failures = self.failures.saturating_add(1);
+		self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+		// The cool-down period doubles with each consecutive failure.
+		let exponent = self.consecutive_failures.saturating_sub(1).min(16);
+		let cool_down = COOL_DOWN_BASE.saturating_mul(1u64 << exponent).min(MAX_COOL_DOWN);
+		self.cool_down_until = now.saturating_add(cool_down);
+		self.decay();
+	}
+
+	fn decay(&mut self) {
+		if self.successes.saturating_add(self.failures) > MAX_RECORDED_QUERIES {
+			self.successes /= 2;
+			self.failures /= 2;
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	fn endpoint_health_key(endpoint: &str) -> Vec<u8> {
+		let mut key = b"octopus_appchain::endpoint_health::".to_vec();
+		key.extend(endpoint.as_bytes());
+		key
+	}
+
+	/// Get the health statistics of the endpoint recorded by this node.
+	pub(crate) fn endpoint_health(endpoint: &str) -> EndpointHealth {
+		StorageValueRef::persistent(&Self::endpoint_health_key(endpoint))
+			.get::<EndpointHealth>()
+			.ok()
+			.flatten()
+			.unwrap_or_default()
+	}
+
+	fn update_endpoint_health(endpoint: &str, f: impl FnOnce(&mut EndpointHealth)) {
+		let key = Self::endpoint_health_key(endpoint);
+		let storage = StorageValueRef::persistent(&key);
+		let mut health = storage.get::<EndpointHealth>().ok().flatten().unwrap_or_default();
+		f(&mut health);
+		log!(debug, "health of {:?}: {:?}", endpoint, health);
+		storage.set(&health);
+	}
+
+	/// Sort the endpoints from the healthiest to the least healthy one.
+	///
+	/// Endpoints which are cooling down come last, ordered by the end of their cool-down.
+	/// The others are ordered by success rate, then by latency. Ties keep the configured order.
+	pub(crate) fn sort_endpoints_by_health(endpoints: &[String]) -> Vec<String> {
+		let now = sp_io::offchain::timestamp().unix_millis();
+		let mut endpoints = endpoints
+			.iter()
+			.map(|endpoint| (endpoint.clone(), Self::endpoint_health(endpoint)))
+			.collect::<Vec<(String, EndpointHealth)>>();
+
+		endpoints.sort_by(|(_, a), (_, b)| {
+			match (a.is_cooling_down(now), b.is_cooling_down(now)) {
+				(false, true) => sp_std::cmp::Ordering::Less,
+				(true, false) => sp_std::cmp::Ordering::Greater,
+				(true, true) => a.cool_down_until.cmp(&b.cool_down_until),
+				(false, false) => b
+					.success_rate()
+					.cmp(&a.success_rate())
+					.then_with(|| a.average_latency.cmp(&b.average_latency)),
+			}
+		});
+
+		endpoints.into_iter().map(|(endpoint, _)| endpoint).collect()
+	}
+
+	/// Record a successful round of queries to the endpoint, which took `latency` milliseconds.
+	pub(crate) fn record_endpoint_success(endpoint: &str, latency: u64) {
+		Self::update_endpoint_health(endpoint, |health| health.on_success(latency));
+	}
+
+	/// Record a failed round of queries to the endpoint, at the unix timestamp `now`.
+	pub(crate) fn record_endpoint_failure(endpoint: &str, now: u64) {
+		Self::update_endpoint_health(endpoint, |health| health.on_failure(now));
+	}
+}
@@ -0,0 +1,200 @@
+//! Records of the processed notifications, indexed by their receivers.
+//!
+//! The index of a receiver keeps its latest `MaxNotificationsPerReceiver` notifications, and
+//! loses a notification once its record is pruned.
+
+use super::*;
+
+/// The transfer of a notification from the mainchain.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize))]
+pub struct NotificationTransfer<AccountId> {
+	/// The sender of the transfer on NEAR.
+	pub sender_id: Vec<u8>,
+	/// The receiver of the transfer on the appchain.
+	pub receiver: AccountId,
+	/// The token id on NEAR of the transferred asset, `None` for the native token.
+	pub token_id: Option<Vec<u8>>,
+	/// The appchain-native asset unlocked from the escrow, `None` for the other transfers.
+	pub asset_id: Option<AssetId>,
+	/// The NEP-171 token id of the transferred NFT, `None` for the other transfers.
+	pub nft_token_id: Option<Vec<u8>>,
+	/// The appchain-native NFT unlocked from the escrow, `None` for the other transfers.
+	pub nft_instance: Option<(ClassId, InstanceId)>,
+	/// The transferred amount, in the units of the token on NEAR, 1 for an NFT.
+	#[cfg_attr(feature = "std", serde(serialize_with = "serialize_to_str"))]
+	pub amount: u128,
+}
+
+impl<AccountId: Clone> NotificationTransfer<AccountId> {
+	/// The transfer of an observation, `None` for a validator set, a governance call or a state
+	/// of the appchain.
+	pub fn new(observation: &Observation<AccountId>) -> Option<Self> {
+		match observation {
+			Observation::Burn(event) => Some(Self {
+				sender_id: event.sender_id.clone(),
+				receiver: event.receiver.clone(),
+				token_id: None,
+				asset_id: None,
+				nft_token_id: None,
+				nft_instance: None,
+				amount: event.amount,
+			}),
+			Observation::LockAsset(event) => Some(Self {
+				sender_id: event.sender_id.clone(),
+				receiver: event.receiver.clone(),
+				token_id: Some(event.token_id.clone()),
+				asset_id: None,
+				nft_token_id: None,
+				nft_instance: None,
+				amount: event.amount,
+			}),
+			Observation::BurnNativeAsset(event) => Some(Self {
+				sender_id: event.sender_id.clone(),
+				receiver: event.receiver.clone(),
+				token_id: None,
+				asset_id: Some(event.asset_id),
+				nft_token_id: None,
+				nft_instance: None,
+				amount: event.amount,
+			}),
+			Observation::LockNft(event) => Some(Self {
+				sender_id: event.sender_id.clone(),
+				receiver: event.receiver.clone(),
+				token_id: Some(event.contract_id.clone()),
+				asset_id: None,
+				nft_token_id: Some(event.token_id.clone()),
+				nft_instance: None,
+				amount: 1,
+			}),
+			Observation::BurnNft(event) => Some(Self {
+				sender_id: event.sender_id.clone(),
+				receiver: event.receiver.clone(),
+				token_id: None,
+				asset_id: None,
+				nft_token_id: None,
+				nft_instance: Some((event.class, event.instance)),
+				amount: 1,
+			}),
+			Observation::UpdateValidatorSet(_)
+			| Observation::GovernanceCall(_)
+			| Observation::UpdateAppchainState(_) => None,
+		}
+	}
+}
+
+/// A notification from the mainchain which has been processed.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize))]
+pub struct NotificationRecord<AccountId, BlockNumber> {
+	/// The transfer of the notification, `None` for a notification recorded by the migration to
+	/// the storage version 3 after its observations had been pruned.
+	pub transfer: Option<NotificationTransfer<AccountId>>,
+	/// The block in which the notification was last executed, `None` for a notification
+	/// recorded by the migration to the storage version 3.
+	pub block_number: Option<BlockNumber>,
+	pub result: NotificationResult,
+}
+
+impl<AccountId: Clone, BlockNumber> NotificationRecord<AccountId, BlockNumber> {
+	/// The record of the transfer of an observation executed in the block `block_number`, `None`
+	/// for a validator set, a governance call or a state of the appchain.
+	pub fn new(
+		observation: &Observation<AccountId>,
+		block_number: BlockNumber,
+		result: NotificationResult,
+	) -> Option<Self> {
+		NotificationTransfer::new(observation).map(|transfer| Self {
+			transfer: Some(transfer),
+			block_number: Some(block_number),
+			result,
+		})
+	}
+
+	/// The receiver of the transfer, if it is known.
+	pub fn receiver(&self) -> Option<&AccountId> {
+		self.transfer.as_ref().map(|transfer| &transfer.receiver)
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Record the result of a notification executed in the current block, and index it by its
+	/// receiver.
+	pub(crate) fn record_notification(
+		notification_id: u32,
+		observation: &Observation<T::AccountId>,
+		result: NotificationResult,
+	) {
+		let block_number = <frame_system::Pallet<T>>::block_number();
+		let record = match NotificationRecord::new(observation, block_number, result) {
+			Some(record) => record,
+			None => return,
+		};
+
+		// The receiver of a failed notification can be redirected by governance.
+		if let Some(previous) = <NotificationHistory<T>>::get(notification_id) {
+			if let Some(receiver) = previous.receiver().filter(|r| Some(*r) != record.receiver()) {
+				Self::unindex_notification(receiver, notification_id);
+			}
+		}
+		if let Some(receiver) = record.receiver() {
+			Self::index_notification(receiver, notification_id);
+		}
+		log!(debug, "save notification record {:?}: {:?}", notification_id, record);
+		<NotificationHistory<T>>::insert(notification_id, record);
+	}
+
+	/// Remove the record of a notification and its index. Returns the number of reads and
+	/// writes.
+	pub(crate) fn remove_notification_record(notification_id: u32) -> (Weight, Weight) {
+		// The results older than the records migrated to the storage version 3 can not be
+		// decoded as records, and are removed all the same.
+		let record = <NotificationHistory<T>>::get(notification_id);
+		<NotificationHistory<T>>::remove(notification_id);
+		match record.as_ref().and_then(|record| record.receiver()) {
+			Some(receiver) => {
+				Self::unindex_notification(receiver, notification_id);
+				(2, 2)
+			},
+			None => (1, 1),
+		}
+	}
+
+	/// The records of the latest notifications received by the account.
+	pub fn notifications_of(
+		receiver: &T::AccountId,
+	) -> Vec<(u32, NotificationRecord<T::AccountId, T::BlockNumber>)> {
+		<NotificationsByReceiver<T>>::get(receiver)
+			.into_iter()
+			.filter_map(|id| <NotificationHistory<T>>::get(id).map(|record| (id, record)))
+			.collect()
+	}
+
+	pub(crate) fn index_notification(receiver: &T::AccountId, notification_id: u32) {
+		let max_notifications = T::MaxNotificationsPerReceiver::get() as usize;
+		if max_notifications == 0 {
+			return;
+		}
+		<NotificationsByReceiver<T>>::mutate(receiver, |ids| {
+			if ids.contains(&notification_id) {
+				return;
+			}
+			// The oldest notification is dropped from a full index.
+			if ids.len() >= max_notifications {
+				ids.remove(0);
+			}
+			ids.push(notification_id);
+		});
+	}
+
+	fn unindex_notification(receiver: &T::AccountId, notification_id: u32) {
+		<NotificationsByReceiver<T>>::mutate_exists(receiver, |ids| {
+			if let Some(list) = ids {
+				list.retain(|id| *id != notification_id);
+				if list.is_empty() {
+					*ids = None;
+				}
+			}
+		});
+	}
+}
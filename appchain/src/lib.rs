@@ -9,13 +9,17 @@ use alloc::string::{String, ToString};
 use borsh::BorshSerialize;
 use codec::{Decode, Encode};
 use frame_support::{
+	dispatch::{Dispatchable, GetDispatchInfo, PostDispatchInfo},
 	traits::{
-		tokens::fungibles,
-		Currency,
+		tokens::{fungibles, nonfungibles},
+		Contains, Currency,
 		ExistenceRequirement::{AllowDeath, KeepAlive},
-		OneSessionHandler, StorageVersion,
+		GetStorageVersion, OnUnbalanced, OneSessionHandler, StorageVersion,
+		ValidatorSetWithIdentification, WithdrawReasons,
 	},
-	transactional, PalletId,
+	transactional,
+	weights::Weight,
+	PalletId,
 };
 use frame_system::offchain::{
 	AppCrypto, CreateSignedTransaction, SendUnsignedTransaction, SignedPayload, Signer,
@@ -23,30 +27,64 @@ use frame_system::offchain::{
 };
 use pallet_octopus_support::{
 	log,
-	traits::{AppchainInterface, LposInterface, UpwardMessagesInterface, ValidatorsProvider},
-	types::{BurnAssetPayload, LockPayload, PayloadType},
+	traits::{
+		AppchainInterface, LposInterface, ObserversProvider, UpwardMessagesInterface,
+		ValidatorsProvider,
+	},
+	types::{
+		BurnAssetPayload, BurnNftPayload, LockAssetPayload, LockNftPayload, LockPayload,
+		NearAccountId, PayloadType, TokenMetadata,
+	},
 };
 use scale_info::TypeInfo;
 use serde::{de, Deserialize, Deserializer};
+#[cfg(feature = "std")]
+use serde::{Serialize, Serializer};
 use sp_core::crypto::KeyTypeId;
 use sp_runtime::RuntimeAppPublic;
 use sp_runtime::{
 	offchain::{
 		http,
 		storage::{MutateStorageError, StorageRetrievalError, StorageValueRef},
-		Duration,
+		Duration, Timestamp,
 	},
-	traits::{AccountIdConversion, CheckedConversion, IdentifyAccount, StaticLookup},
-	RuntimeDebug,
+	traits::{AccountIdConversion, CheckedConversion, IdentifyAccount, StaticLookup, Zero},
+	Perbill, RuntimeDebug,
 };
+use sp_staking::offence::ReportOffence;
 use sp_std::prelude::*;
 
 pub use pallet::*;
 
 pub(crate) const LOG_TARGET: &'static str = "runtime::octopus-appchain";
 
+mod api;
+mod decimals;
+mod fees;
+mod governance;
+mod health;
+mod history;
+mod lifecycle;
 mod mainchain;
+pub mod migrations;
+mod nft;
+mod offences;
+mod pause;
+mod retry;
+mod tally;
+mod transfer_limits;
 pub mod weights;
+pub use api::{NotificationStatus, PendingObservation, MAX_NOTIFICATION_HISTORY_QUERY};
+pub use decimals::TokenDecimals;
+pub use fees::BridgeFee;
+pub use governance::{EnsureMainchain, MainchainOrigin};
+pub use history::{NotificationRecord, NotificationTransfer};
+pub use lifecycle::{AppchainState, AppchainStateChange};
+pub use mainchain::{AnchorStatus, IndexRange, MainchainClient, NearMainchainClient};
+pub use offences::{ConflictingObservationOffence, IdentificationTuple};
+pub use pause::BridgeOperation;
+pub use tally::ObservationTally;
+pub use transfer_limits::{BridgedToken, TransferDirection, TransferLimit, TransferVolume};
 pub use weights::WeightInfo;
 
 #[cfg(test)]
@@ -81,39 +119,61 @@ pub type AuthorityId = crypto::Public;
 
 type AssetId = u32;
 type AssetBalance = u128;
+type ClassId = u128;
+type InstanceId = u128;
 
 type BalanceOf<T> =
 	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
 
+type NegativeImbalanceOf<T> = <<T as Config>::Currency as Currency<
+	<T as frame_system::Config>::AccountId,
+>>::NegativeImbalance;
+
+type AssetCreditOf<T> =
+	fungibles::CreditOf<<T as frame_system::Config>::AccountId, <T as Config>::Assets>;
+
 type AssetBalanceOf<T> =
 	<<T as Config>::Assets as fungibles::Inspect<<T as frame_system::Config>::AccountId>>::Balance;
 
 type AssetIdOf<T> =
 	<<T as Config>::Assets as fungibles::Inspect<<T as frame_system::Config>::AccountId>>::AssetId;
 
+type ClassIdOf<T> = <<T as Config>::Uniques as nonfungibles::Inspect<
+	<T as frame_system::Config>::AccountId,
+>>::ClassId;
+
+type InstanceIdOf<T> = <<T as Config>::Uniques as nonfungibles::Inspect<
+	<T as frame_system::Config>::AccountId,
+>>::InstanceId;
+
 /// Validator of appchain.
 #[derive(Deserialize, Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize))]
 pub struct Validator<AccountId> {
 	/// The validator's id.
 	#[serde(deserialize_with = "deserialize_from_hex_str")]
-	#[serde(bound(deserialize = "AccountId: Decode"))]
+	#[serde(serialize_with = "serialize_to_hex_str")]
+	#[serde(bound(serialize = "AccountId: Encode", deserialize = "AccountId: Decode"))]
 	validator_id_in_appchain: AccountId,
 	/// The total stake of this validator in mainchain's staking system.
 	#[serde(deserialize_with = "deserialize_from_str")]
+	#[serde(serialize_with = "serialize_to_str")]
 	total_stake: u128,
 }
 
 #[derive(Deserialize, Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize))]
 pub struct ValidatorSet<AccountId> {
 	/// The anchor era that this set belongs to.
 	set_id: u32,
 	/// Validators in this set.
-	#[serde(bound(deserialize = "AccountId: Decode"))]
+	#[serde(bound(serialize = "AccountId: Encode", deserialize = "AccountId: Decode"))]
 	validators: Vec<Validator<AccountId>>,
 }
 
 /// Appchain token burn event.
 #[derive(Deserialize, Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize))]
 pub struct BurnEvent<AccountId> {
 	#[serde(default)]
 	index: u32,
@@ -122,14 +182,17 @@ pub struct BurnEvent<AccountId> {
 	sender_id: Vec<u8>,
 	#[serde(rename = "receiver_id_in_appchain")]
 	#[serde(deserialize_with = "deserialize_from_hex_str")]
-	#[serde(bound(deserialize = "AccountId: Decode"))]
+	#[serde(serialize_with = "serialize_to_hex_str")]
+	#[serde(bound(serialize = "AccountId: Encode", deserialize = "AccountId: Decode"))]
 	receiver: AccountId,
 	#[serde(deserialize_with = "deserialize_from_str")]
+	#[serde(serialize_with = "serialize_to_str")]
 	amount: u128,
 }
 
 /// Token locked event.
 #[derive(Deserialize, Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize))]
 pub struct LockAssetEvent<AccountId> {
 	#[serde(default)]
 	index: u32,
@@ -141,12 +204,95 @@ pub struct LockAssetEvent<AccountId> {
 	sender_id: Vec<u8>,
 	#[serde(rename = "receiver_id_in_appchain")]
 	#[serde(deserialize_with = "deserialize_from_hex_str")]
-	#[serde(bound(deserialize = "AccountId: Decode"))]
+	#[serde(serialize_with = "serialize_to_hex_str")]
+	#[serde(bound(serialize = "AccountId: Encode", deserialize = "AccountId: Decode"))]
+	receiver: AccountId,
+	#[serde(deserialize_with = "deserialize_from_str")]
+	#[serde(serialize_with = "serialize_to_str")]
+	amount: u128,
+}
+
+/// Wrapped appchain asset burnt event, unlocking the asset from the escrow.
+#[derive(Deserialize, Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize))]
+pub struct BurnNativeAssetEvent<AccountId> {
+	#[serde(default)]
+	index: u32,
+	#[serde(rename = "asset_id_in_appchain")]
+	#[serde(deserialize_with = "deserialize_from_str")]
+	#[serde(serialize_with = "serialize_to_str")]
+	asset_id: AssetId,
+	#[serde(rename = "sender_id_in_near")]
+	#[serde(with = "serde_bytes")]
+	sender_id: Vec<u8>,
+	#[serde(rename = "receiver_id_in_appchain")]
+	#[serde(deserialize_with = "deserialize_from_hex_str")]
+	#[serde(serialize_with = "serialize_to_hex_str")]
+	#[serde(bound(serialize = "AccountId: Encode", deserialize = "AccountId: Decode"))]
 	receiver: AccountId,
 	#[serde(deserialize_with = "deserialize_from_str")]
+	#[serde(serialize_with = "serialize_to_str")]
 	amount: u128,
 }
 
+/// NEP-171 token locked event.
+#[derive(Deserialize, Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize))]
+pub struct LockNftEvent<AccountId> {
+	#[serde(default)]
+	index: u32,
+	#[serde(rename = "contract_account")]
+	#[serde(with = "serde_bytes")]
+	contract_id: Vec<u8>,
+	#[serde(with = "serde_bytes")]
+	token_id: Vec<u8>,
+	#[serde(rename = "sender_id_in_near")]
+	#[serde(with = "serde_bytes")]
+	sender_id: Vec<u8>,
+	#[serde(rename = "receiver_id_in_appchain")]
+	#[serde(deserialize_with = "deserialize_from_hex_str")]
+	#[serde(serialize_with = "serialize_to_hex_str")]
+	#[serde(bound(serialize = "AccountId: Encode", deserialize = "AccountId: Decode"))]
+	receiver: AccountId,
+	#[serde(rename = "token_metadata")]
+	metadata: TokenMetadata,
+}
+
+/// Wrapped appchain NFT burnt event, unlocking the NFT from the escrow.
+#[derive(Deserialize, Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize))]
+pub struct BurnNftEvent<AccountId> {
+	#[serde(default)]
+	index: u32,
+	#[serde(rename = "class_id")]
+	#[serde(deserialize_with = "deserialize_from_str")]
+	#[serde(serialize_with = "serialize_to_str")]
+	class: ClassId,
+	#[serde(rename = "instance_id")]
+	#[serde(deserialize_with = "deserialize_from_str")]
+	#[serde(serialize_with = "serialize_to_str")]
+	instance: InstanceId,
+	#[serde(rename = "sender_id_in_near")]
+	#[serde(with = "serde_bytes")]
+	sender_id: Vec<u8>,
+	#[serde(rename = "receiver_id_in_appchain")]
+	#[serde(deserialize_with = "deserialize_from_hex_str")]
+	#[serde(serialize_with = "serialize_to_hex_str")]
+	#[serde(bound(serialize = "AccountId: Encode", deserialize = "AccountId: Decode"))]
+	receiver: AccountId,
+}
+
+/// Governance call event, carrying a SCALE-encoded call of the appchain runtime.
+#[derive(Deserialize, Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize))]
+pub struct GovernanceCallEvent {
+	#[serde(default)]
+	index: u32,
+	#[serde(deserialize_with = "deserialize_bytes_from_hex_str")]
+	#[serde(serialize_with = "serialize_bytes_to_hex_str")]
+	call: Vec<u8>,
+}
+
 #[derive(Deserialize, Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
 pub enum AppchainNotification<AccountId> {
 	#[serde(rename = "NearFungibleTokenLocked")]
@@ -156,14 +302,46 @@ pub enum AppchainNotification<AccountId> {
 	#[serde(rename = "WrappedAppchainTokenBurnt")]
 	#[serde(bound(deserialize = "AccountId: Decode"))]
 	Burn(BurnEvent<AccountId>),
+
+	#[serde(rename = "WrappedAppchainAssetBurnt")]
+	#[serde(bound(deserialize = "AccountId: Decode"))]
+	BurnNativeAsset(BurnNativeAssetEvent<AccountId>),
+
+	#[serde(rename = "NearNonFungibleTokenLocked")]
+	#[serde(bound(deserialize = "AccountId: Decode"))]
+	LockNft(LockNftEvent<AccountId>),
+
+	#[serde(rename = "WrappedAppchainNftBurnt")]
+	#[serde(bound(deserialize = "AccountId: Decode"))]
+	BurnNft(BurnNftEvent<AccountId>),
+
+	#[serde(rename = "AppchainGovernanceCall")]
+	GovernanceCall(GovernanceCallEvent),
 }
 
-#[derive(PartialEq, Encode, Decode, Clone, RuntimeDebug, TypeInfo)]
+#[derive(PartialEq, Eq, Encode, Decode, Clone, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize))]
 pub enum NotificationResult {
 	Success,
 	UnlockFailed,
 	AssetMintFailed,
 	AssetGetFailed,
+	/// The transfer exceeded a limit, or the inbound transfers are paused.
+	TransferLimited,
+	/// The operation or the asset of the transfer is paused.
+	OperationPaused,
+	/// The appchain-native asset could not be unlocked from the escrow.
+	AssetUnlockFailed,
+	/// The NFT could not be minted for the NEP-171 token.
+	NftMintFailed,
+	/// The appchain-native NFT could not be unlocked from the escrow.
+	NftUnlockFailed,
+	/// The call from the mainchain could not be decoded, or is not allowed.
+	CallRejected,
+	/// The call from the mainchain failed when dispatched.
+	CallFailed,
+	/// The transfer failed, and its funds have been sent back to the sender on NEAR.
+	Refunded,
 }
 
 impl Default for NotificationResult {
@@ -183,6 +361,15 @@ where
 	S::decode(&mut &account_id_hex[..]).map_err(|e| de::Error::custom(e.to_string()))
 }
 
+fn deserialize_bytes_from_hex_str<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
+where
+	D: Deserializer<'de>,
+{
+	let bytes_str: String = Deserialize::deserialize(deserializer)?;
+	let bytes_str = bytes_str.strip_prefix("0x").unwrap_or(&bytes_str);
+	hex::decode(bytes_str).map_err(|e| de::Error::custom(e.to_string()))
+}
+
 pub fn deserialize_from_str<'de, S, D>(deserializer: D) -> Result<S, D::Error>
 where
 	S: sp_std::str::FromStr,
@@ -193,21 +380,62 @@ where
 	amount_str.parse::<S>().map_err(|e| de::Error::custom(e.to_string()))
 }
 
+#[cfg(feature = "std")]
+fn serialize_to_hex_str<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+	T: Encode,
+	S: Serializer,
+{
+	serializer.serialize_str(&format!("0x{}", hex::encode(value.encode())))
+}
+
+#[cfg(feature = "std")]
+fn serialize_bytes_to_hex_str<S>(value: &[u8], serializer: S) -> Result<S::Ok, S::Error>
+where
+	S: Serializer,
+{
+	serializer.serialize_str(&format!("0x{}", hex::encode(value)))
+}
+
+#[cfg(feature = "std")]
+pub fn serialize_to_str<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+	T: ToString,
+	S: Serializer,
+{
+	serializer.serialize_str(&value.to_string())
+}
+
 #[derive(Deserialize, Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize))]
 pub enum Observation<AccountId> {
-	#[serde(bound(deserialize = "AccountId: Decode"))]
+	#[serde(bound(serialize = "AccountId: Encode", deserialize = "AccountId: Decode"))]
 	UpdateValidatorSet(ValidatorSet<AccountId>),
-	#[serde(bound(deserialize = "AccountId: Decode"))]
+	#[serde(bound(serialize = "AccountId: Encode", deserialize = "AccountId: Decode"))]
 	LockAsset(LockAssetEvent<AccountId>),
-	#[serde(bound(deserialize = "AccountId: Decode"))]
+	#[serde(bound(serialize = "AccountId: Encode", deserialize = "AccountId: Decode"))]
 	Burn(BurnEvent<AccountId>),
+	#[serde(bound(serialize = "AccountId: Encode", deserialize = "AccountId: Decode"))]
+	BurnNativeAsset(BurnNativeAssetEvent<AccountId>),
+	#[serde(bound(serialize = "AccountId: Encode", deserialize = "AccountId: Decode"))]
+	LockNft(LockNftEvent<AccountId>),
+	#[serde(bound(serialize = "AccountId: Encode", deserialize = "AccountId: Decode"))]
+	BurnNft(BurnNftEvent<AccountId>),
+	GovernanceCall(GovernanceCallEvent),
+	UpdateAppchainState(AppchainStateChange),
 }
 
-#[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+#[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize))]
 pub enum ObservationType {
 	UpdateValidatorSet,
 	Burn,
 	LockAsset,
+	BurnNativeAsset,
+	LockNft,
+	BurnNft,
+	GovernanceCall,
+	UpdateAppchainState,
 }
 
 impl<AccountId> Observation<AccountId> {
@@ -216,6 +444,11 @@ impl<AccountId> Observation<AccountId> {
 			Observation::UpdateValidatorSet(set) => set.set_id,
 			Observation::LockAsset(event) => event.index,
 			Observation::Burn(event) => event.index,
+			Observation::BurnNativeAsset(event) => event.index,
+			Observation::LockNft(event) => event.index,
+			Observation::BurnNft(event) => event.index,
+			Observation::GovernanceCall(event) => event.index,
+			Observation::UpdateAppchainState(change) => change.index,
 		}
 	}
 }
@@ -237,7 +470,7 @@ impl<T: SigningTypes> SignedPayload<T>
 
 impl<T: Config> AppchainInterface for Pallet<T> {
 	fn is_activated() -> bool {
-		IsActivated::<T>::get()
+		Self::is_active()
 	}
 
 	fn next_set_id() -> u32 {
@@ -246,7 +479,7 @@ impl<T: Config> AppchainInterface for Pallet<T> {
 }
 
 /// The current storage version.
-const STORAGE_VERSION: StorageVersion = StorageVersion::new(0);
+const STORAGE_VERSION: StorageVersion = StorageVersion::new(5);
 
 #[frame_support::pallet]
 pub mod pallet {
@@ -263,22 +496,71 @@ pub mod pallet {
 		/// The overarching event type.
 		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
 
+		/// The overarching origin type, including the origin of the calls from the mainchain.
+		type Origin: From<MainchainOrigin>;
+
 		/// The overarching dispatch call type.
-		type Call: From<Call<Self>>;
+		type Call: From<Call<Self>>
+			+ Parameter
+			+ Dispatchable<Origin = <Self as Config>::Origin, PostInfo = PostDispatchInfo>
+			+ GetDispatchInfo;
 
 		type PalletId: Get<PalletId>;
 
 		type Currency: Currency<Self::AccountId>;
 
 		type Assets: fungibles::Mutate<
-			<Self as frame_system::Config>::AccountId,
-			AssetId = AssetId,
-			Balance = AssetBalance,
-		>;
+				<Self as frame_system::Config>::AccountId,
+				AssetId = AssetId,
+				Balance = AssetBalance,
+			> + fungibles::Balanced<<Self as frame_system::Config>::AccountId>
+			+ fungibles::Transfer<<Self as frame_system::Config>::AccountId>;
+
+		/// The NFTs minted for the bridged NEP-171 tokens, and the appchain-native NFTs.
+		type Uniques: nonfungibles::Mutate<
+				<Self as frame_system::Config>::AccountId,
+				ClassId = ClassId,
+				InstanceId = InstanceId,
+			> + nonfungibles::Transfer<<Self as frame_system::Config>::AccountId>;
 
 		type LposInterface: LposInterface<Self::AccountId>;
 		type UpwardMessagesInterface: UpwardMessagesInterface<Self::AccountId>;
 
+		/// The origin which may update the governance-managed parameters of this pallet.
+		///
+		/// Include `EnsureMainchain` to let the registry on NEAR update them.
+		type GovernanceOrigin: EnsureOrigin<<Self as frame_system::Config>::Origin>;
+
+		/// The origin which may pause and resume the bridge operations and assets.
+		type PauseOrigin: EnsureOrigin<<Self as frame_system::Config>::Origin>;
+
+		/// Handler for the fees of the outbound transfers of the native token.
+		type OnBridgeFee: OnUnbalanced<NegativeImbalanceOf<Self>>;
+
+		/// Handler for the fees of the outbound transfers of the bridged assets.
+		type OnAssetBridgeFee: OnUnbalanced<AssetCreditOf<Self>>;
+
+		/// The validators of the current session, with their full identification.
+		type ValidatorSet: ValidatorSetWithIdentification<Self::AccountId>;
+
+		/// The calls from the mainchain which may be dispatched with `MainchainOrigin`.
+		type MainchainCallFilter: Contains<<Self as Config>::Call>;
+
+		/// The handler of the offences of the validators voting for conflicting observations.
+		///
+		/// Use `pallet_octopus_lpos::FilterHistoricalOffences` to discard the offences from
+		/// before the bonding period.
+		type ReportConflictingObservation: ReportOffence<
+			Self::AccountId,
+			IdentificationTuple<Self>,
+			ConflictingObservationOffence<IdentificationTuple<Self>>,
+		>;
+
+		/// The client of the anchor contract used by the offchain worker.
+		///
+		/// Use `NearMainchainClient` for an anchor contract deployed on NEAR.
+		type MainchainClient: MainchainClient<Self::AccountId>;
+
 		// Configuration parameters
 
 		/// A grace period after we send transaction.
@@ -299,6 +581,64 @@ pub mod pallet {
 		#[pallet::constant]
 		type RequestEventLimit: Get<u32>;
 
+		/// Number of notification histories to keep.
+		///
+		/// The records of notifications with an index lower than
+		/// `NextNotificationId - NotificationHistoryDepth` will be pruned.
+		#[pallet::constant]
+		type NotificationHistoryDepth: Get<u32>;
+
+		/// Number of notifications whose observations are kept.
+		///
+		/// The observations of notifications with an index lower than
+		/// `NextNotificationId - ObservationHistoryDepth` will be pruned.
+		#[pallet::constant]
+		type ObservationHistoryDepth: Get<u32>;
+
+		/// Number of blocks after an observation reaches consensus in which the votes for it are
+		/// still credited to their voters.
+		#[pallet::constant]
+		type LateVotePeriod: Get<Self::BlockNumber>;
+
+		/// The maximum number of notifications indexed for a receiver.
+		#[pallet::constant]
+		type MaxNotificationsPerReceiver: Get<u32>;
+
+		/// The maximum number of observation ids pruned in a single block.
+		#[pallet::constant]
+		type MaxPrunedPerBlock: Get<u32>;
+
+		/// The maximum number of mainchain rpc endpoints stored on chain.
+		#[pallet::constant]
+		type MaxRpcEndpoints: Get<u32>;
+
+		/// The maximum length in bytes of a mainchain rpc endpoint.
+		#[pallet::constant]
+		type MaxRpcEndpointLength: Get<u32>;
+
+		/// The maximum number of validators of the appchain.
+		///
+		/// It bounds the number of voters of an observation, whose stakes are tallied and used
+		/// to weigh `submit_observations` before dispatch.
+		#[pallet::constant]
+		type MaxValidators: Get<u32>;
+
+		/// The maximum number of distinct observations with the same observation id.
+		#[pallet::constant]
+		type MaxObservationsPerId: Get<u32>;
+
+		/// The number of automatic retries of a failed notification.
+		///
+		/// After them, the notification is only retried on demand.
+		#[pallet::constant]
+		type MaxNotificationRetries: Get<u32>;
+
+		/// The maximum weight of a call from the mainchain.
+		///
+		/// It is reserved by `submit_observations` for each governance call observed.
+		#[pallet::constant]
+		type MaxMainchainCallWeight: Get<Weight>;
+
 		type WeightInfo: WeightInfo;
 	}
 
@@ -307,6 +647,10 @@ pub mod pallet {
 	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
+	/// The origin of the calls from the mainchain.
+	#[pallet::origin]
+	pub type Origin = MainchainOrigin;
+
 	#[pallet::type_value]
 	pub(super) fn DefaultForAnchorContract() -> Vec<u8> {
 		Vec::new()
@@ -317,11 +661,22 @@ pub mod pallet {
 	pub(super) type AnchorContract<T: Config> =
 		StorageValue<_, Vec<u8>, ValueQuery, DefaultForAnchorContract>;
 
-	/// Whether the appchain is activated.
+	/// The state of the appchain, following the state of its anchor contract.
 	///
 	/// Only an active appchain will communicate with the mainchain and pay block rewards.
 	#[pallet::storage]
-	pub(super) type IsActivated<T: Config> = StorageValue<_, bool, ValueQuery>;
+	#[pallet::getter(fn appchain_state)]
+	pub(super) type CurrentAppchainState<T: Config> = StorageValue<_, AppchainState, ValueQuery>;
+
+	/// The state of the anchor contract which the validators last agreed on.
+	///
+	/// It differs from `CurrentAppchainState` while governance overrides the state.
+	#[pallet::storage]
+	pub(super) type AnchorAppchainState<T: Config> = StorageValue<_, AppchainState, ValueQuery>;
+
+	/// The index of the next change of the state of the appchain to observe.
+	#[pallet::storage]
+	pub type NextStateChangeId<T: Config> = StorageValue<_, u32, ValueQuery>;
 
 	#[pallet::storage]
 	pub type NextSetId<T: Config> = StorageValue<_, u32, ValueQuery>;
@@ -343,21 +698,208 @@ pub mod pallet {
 		ValueQuery,
 	>;
 
+	/// The voters of each observation, keyed by the hash of the observation.
 	#[pallet::storage]
 	pub type Observing<T: Config> =
-		StorageMap<_, Twox64Concat, Observation<T::AccountId>, Vec<T::AccountId>, ValueQuery>;
+		StorageDoubleMap<_, Twox64Concat, T::Hash, Twox64Concat, T::AccountId, (), OptionQuery>;
+
+	/// The number of voters of each observation and their stake, keyed by the hash of the
+	/// observation.
+	#[pallet::storage]
+	pub type ObservationTallies<T: Config> =
+		StorageMap<_, Twox64Concat, T::Hash, ObservationTally, ValueQuery>;
+
+	/// The block and the era in which an observation reached consensus, keyed by the hash of the
+	/// observation.
+	#[pallet::storage]
+	pub type ConsensusReachedAt<T: Config> =
+		StorageMap<_, Twox64Concat, T::Hash, (T::BlockNumber, u32), OptionQuery>;
 
 	#[pallet::storage]
 	pub type AssetIdByName<T: Config> =
 		StorageMap<_, Twox64Concat, Vec<u8>, AssetIdOf<T>, ValueQuery>;
 
+	/// The token id of each bridged asset, the reverse of `AssetIdByName`.
+	#[pallet::storage]
+	pub type TokenIdByAssetId<T: Config> =
+		StorageMap<_, Twox64Concat, AssetIdOf<T>, Vec<u8>, OptionQuery>;
+
+	/// The decimals of the bridged assets whose decimals differ between NEAR and the appchain.
+	#[pallet::storage]
+	pub type AssetDecimals<T: Config> =
+		StorageMap<_, Twox64Concat, AssetIdOf<T>, TokenDecimals, OptionQuery>;
+
+	/// The decimals of the native token, if they differ between NEAR and the appchain.
+	#[pallet::storage]
+	pub type NativeTokenDecimals<T: Config> = StorageValue<_, TokenDecimals, OptionQuery>;
+
+	/// The class of the NFTs minted for each bridged NEP-171 contract.
+	#[pallet::storage]
+	pub type ClassIdByContractId<T: Config> =
+		StorageMap<_, Twox64Concat, Vec<u8>, ClassIdOf<T>, OptionQuery>;
+
+	/// The NEP-171 contract of each bridged class, the reverse of `ClassIdByContractId`.
+	#[pallet::storage]
+	pub type ContractIdByClassId<T: Config> =
+		StorageMap<_, Twox64Concat, ClassIdOf<T>, Vec<u8>, OptionQuery>;
+
+	/// The NEP-171 token id of each NFT minted for a bridged token.
+	#[pallet::storage]
+	pub type NftTokenIds<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		ClassIdOf<T>,
+		Twox64Concat,
+		InstanceIdOf<T>,
+		Vec<u8>,
+		OptionQuery,
+	>;
+
+	/// The metadata carried from NEAR of each NFT minted for a bridged token.
+	#[pallet::storage]
+	pub type NftMetadata<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		ClassIdOf<T>,
+		Twox64Concat,
+		InstanceIdOf<T>,
+		TokenMetadata,
+		OptionQuery,
+	>;
+
 	#[pallet::storage]
 	#[pallet::getter(fn pallet_account)]
 	pub type PalletAccount<T: Config> = StorageValue<_, T::AccountId, ValueQuery>;
 
+	/// The records of the processed notifications.
+	#[pallet::storage]
+	pub type NotificationHistory<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		u32,
+		NotificationRecord<T::AccountId, T::BlockNumber>,
+		OptionQuery,
+	>;
+
+	/// The ids of the latest notifications received by an account.
+	#[pallet::storage]
+	pub type NotificationsByReceiver<T: Config> =
+		StorageMap<_, Twox64Concat, T::AccountId, Vec<u32>, ValueQuery>;
+
+	/// The share of the active stake which the voters of an observation must exceed to reach
+	/// the consensus.
+	///
+	/// More than 2/3 of the active stake is needed if it is not set.
+	#[pallet::storage]
+	pub type ConsensusThreshold<T: Config> = StorageValue<_, Perbill, OptionQuery>;
+
+	/// The hash of the observation each validator voted for, keyed by the type and the id of the
+	/// observation.
+	#[pallet::storage]
+	pub type VotedObservations<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		(ObservationType, u32),
+		Twox64Concat,
+		T::AccountId,
+		T::Hash,
+		OptionQuery,
+	>;
+
+	/// The hashes of the conflicting observations voted by a validator, keyed by the type and the
+	/// id of the observations.
+	#[pallet::storage]
+	pub type ConflictingObservations<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		(ObservationType, u32),
+		Twox64Concat,
+		T::AccountId,
+		(T::Hash, T::Hash),
+		OptionQuery,
+	>;
+
+	/// The number of observations which reached consensus in each era.
+	#[pallet::storage]
+	pub type EraObservations<T: Config> = StorageMap<_, Twox64Concat, u32, u32, ValueQuery>;
+
+	/// The number of observations which reached consensus in each era that a validator voted
+	/// for, before the consensus or within `LateVotePeriod` blocks after it.
+	#[pallet::storage]
+	pub type ObservationParticipants<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, u32, Twox64Concat, T::AccountId, u32, ValueQuery>;
+
+	/// The notifications whose inbound transfer failed, with the number of automatic retries.
+	#[pallet::storage]
+	pub type FailedNotifications<T: Config> =
+		StorageMap<_, Twox64Concat, u32, (Observation<T::AccountId>, u32), OptionQuery>;
+
+	/// The raw key of the last failed notification visited by the automatic retries, where they
+	/// resume in the next block.
+	#[pallet::storage]
+	pub type FailedNotificationsCursor<T: Config> = StorageValue<_, Vec<u8>, OptionQuery>;
+
+	/// The limits of the transfers in a direction, per token or for all the tokens (`None`).
+	#[pallet::storage]
+	pub type TransferLimits<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		TransferDirection,
+		Twox64Concat,
+		Option<BridgedToken<AssetIdOf<T>>>,
+		TransferLimit<T::BlockNumber>,
+		OptionQuery,
+	>;
+
+	/// The amounts transferred in a direction, for the tokens of `TransferLimits`.
+	#[pallet::storage]
+	pub type TransferVolumes<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		TransferDirection,
+		Twox64Concat,
+		Option<BridgedToken<AssetIdOf<T>>>,
+		TransferVolume<T::BlockNumber>,
+		ValueQuery,
+	>;
+
+	/// The directions whose transfers are paused after exceeding a limit.
+	#[pallet::storage]
+	pub type TransfersPaused<T: Config> =
+		StorageMap<_, Twox64Concat, TransferDirection, bool, ValueQuery>;
+
+	/// The fee schedules of the outbound transfers, per token.
+	#[pallet::storage]
+	pub type BridgeFees<T: Config> =
+		StorageMap<_, Twox64Concat, BridgedToken<AssetIdOf<T>>, BridgeFee, OptionQuery>;
+
+	/// The bridge operations which are paused.
+	#[pallet::storage]
+	pub type PausedOperations<T: Config> =
+		StorageMap<_, Twox64Concat, BridgeOperation, bool, ValueQuery>;
+
+	/// The bridged assets whose transfers are paused.
+	#[pallet::storage]
+	pub type PausedAssets<T: Config> = StorageMap<_, Twox64Concat, AssetIdOf<T>, bool, ValueQuery>;
+
+	/// The lowest notification index whose history has not been pruned yet.
+	#[pallet::storage]
+	pub type NextPruneNotificationId<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	/// The lowest notification index whose observations have not been pruned yet.
+	#[pallet::storage]
+	pub type NextPruneObservationId<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	/// The lowest validator set id whose observations have not been pruned yet.
+	#[pallet::storage]
+	pub type NextPruneSetId<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	/// The mainchain rpc endpoints used by the offchain worker.
+	///
+	/// The first one is the primary endpoint, the others are failovers.
 	#[pallet::storage]
-	pub type NotificationHistory<T: Config> =
-		StorageMap<_, Twox64Concat, u32, NotificationResult, ValueQuery>;
+	#[pallet::getter(fn mainchain_rpc_endpoints)]
+	pub type MainchainRpcEndpoints<T: Config> = StorageValue<_, Vec<Vec<u8>>, ValueQuery>;
 
 	#[pallet::genesis_config]
 	pub struct GenesisConfig<T: Config> {
@@ -399,6 +941,7 @@ pub mod pallet {
 
 			for (token_id, id) in self.asset_id_by_name.iter() {
 				<AssetIdByName<T>>::insert(token_id.as_bytes(), id);
+				<TokenIdByAssetId<T>>::insert(id, token_id.as_bytes());
 			}
 		}
 	}
@@ -406,13 +949,97 @@ pub mod pallet {
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
-		Locked(T::AccountId, Vec<u8>, BalanceOf<T>),
+		/// Native tokens have been locked to be minted on NEAR.
+		/// \[sender, receiver_id, amount, fee\]
+		Locked(T::AccountId, Vec<u8>, BalanceOf<T>, BalanceOf<T>),
 		Unlocked(Vec<u8>, T::AccountId, BalanceOf<T>),
 		AssetMinted(AssetIdOf<T>, Vec<u8>, T::AccountId, AssetBalanceOf<T>),
-		AssetBurned(AssetIdOf<T>, T::AccountId, Vec<u8>, AssetBalanceOf<T>),
+		/// Assets have been burnt to be unlocked on NEAR.
+		/// \[asset_id, sender, receiver_id, amount, fee\]
+		AssetBurned(AssetIdOf<T>, T::AccountId, Vec<u8>, AssetBalanceOf<T>, AssetBalanceOf<T>),
 		UnlockFailed(Vec<u8>, T::AccountId, BalanceOf<T>),
 		AssetMintFailed(AssetIdOf<T>, Vec<u8>, T::AccountId, AssetBalanceOf<T>),
 		AssetIdGetFailed(Vec<u8>, Vec<u8>, T::AccountId, AssetBalanceOf<T>),
+		/// The mainchain rpc endpoints have been updated. \[endpoints\]
+		MainchainRpcEndpointsUpdated(Vec<Vec<u8>>),
+		/// A bridged token has been registered. \[token_id, asset_id\]
+		AssetRegistered(Vec<u8>, AssetIdOf<T>),
+		/// The asset of a bridged token has been updated. \[token_id, old_asset_id, asset_id\]
+		AssetUpdated(Vec<u8>, AssetIdOf<T>, AssetIdOf<T>),
+		/// A bridged token has been deregistered. \[token_id, asset_id\]
+		AssetDeregistered(Vec<u8>, AssetIdOf<T>),
+		/// The decimals of a bridged asset have been updated. \[asset_id, decimals\]
+		AssetDecimalsUpdated(AssetIdOf<T>, Option<TokenDecimals>),
+		/// The decimals of the native token have been updated. \[decimals\]
+		NativeTokenDecimalsUpdated(Option<TokenDecimals>),
+		/// The transfer of a failed notification has succeeded. \[notification_id\]
+		NotificationRetried(u32),
+		/// A failed notification has been redirected to another receiver.
+		/// \[notification_id, receiver\]
+		NotificationRedirected(u32, T::AccountId),
+		/// The funds of a failed notification have been sent back to NEAR. \[notification_id\]
+		NotificationRefunded(u32),
+		/// A transfer limit has been exceeded and its direction paused. \[direction, token\]
+		TransferLimitExceeded(TransferDirection, BridgedToken<AssetIdOf<T>>),
+		/// A transfer limit has been updated. \[direction, token, limit\]
+		TransferLimitUpdated(
+			TransferDirection,
+			Option<BridgedToken<AssetIdOf<T>>>,
+			Option<TransferLimit<T::BlockNumber>>,
+		),
+		/// The paused transfers of a direction have been resumed. \[direction\]
+		TransfersResumed(TransferDirection),
+		/// A bridge operation has been paused or resumed. \[operation, paused\]
+		OperationPauseUpdated(BridgeOperation, bool),
+		/// The transfers of a bridged asset have been paused or resumed. \[asset_id, paused\]
+		AssetPauseUpdated(AssetIdOf<T>, bool),
+		/// The fee schedule of a token has been updated. \[token, fee\]
+		BridgeFeeUpdated(BridgedToken<AssetIdOf<T>>, Option<BridgeFee>),
+		/// The consensus threshold has been updated. \[threshold\]
+		ConsensusThresholdUpdated(Option<Perbill>),
+		/// A validator has voted for conflicting observations.
+		/// \[validator, observation_type, obs_id\]
+		ConflictingObservation(T::AccountId, ObservationType, u32),
+		/// Appchain-native assets have been locked to be minted on NEAR.
+		/// \[asset_id, sender, receiver_id, amount, fee\]
+		AssetLocked(AssetIdOf<T>, T::AccountId, Vec<u8>, AssetBalanceOf<T>, AssetBalanceOf<T>),
+		/// Appchain-native assets burnt on NEAR have been unlocked.
+		/// \[asset_id, sender_id, receiver, amount\]
+		AssetUnlocked(AssetIdOf<T>, Vec<u8>, T::AccountId, AssetBalanceOf<T>),
+		/// Appchain-native assets burnt on NEAR could not be unlocked.
+		/// \[asset_id, sender_id, receiver, amount\]
+		AssetUnlockFailed(AssetIdOf<T>, Vec<u8>, T::AccountId, AssetBalanceOf<T>),
+		/// A bridged NEP-171 contract has been registered. \[contract_id, class_id\]
+		NftClassRegistered(Vec<u8>, ClassIdOf<T>),
+		/// A bridged NEP-171 contract has been deregistered. \[contract_id, class_id\]
+		NftClassDeregistered(Vec<u8>, ClassIdOf<T>),
+		/// An NFT has been minted for a NEP-171 token locked on NEAR.
+		/// \[class_id, instance_id, sender_id, receiver\]
+		NftMinted(ClassIdOf<T>, InstanceIdOf<T>, Vec<u8>, T::AccountId),
+		/// No NFT could be minted for a NEP-171 token locked on NEAR.
+		/// \[contract_id, token_id, sender_id, receiver\]
+		NftMintFailed(Vec<u8>, Vec<u8>, Vec<u8>, T::AccountId),
+		/// An NFT has been burnt to unlock its NEP-171 token on NEAR.
+		/// \[class_id, instance_id, sender, receiver_id\]
+		NftBurned(ClassIdOf<T>, InstanceIdOf<T>, T::AccountId, Vec<u8>),
+		/// An appchain-native NFT has been locked to be minted on NEAR.
+		/// \[class_id, instance_id, sender, receiver_id\]
+		NftLocked(ClassIdOf<T>, InstanceIdOf<T>, T::AccountId, Vec<u8>),
+		/// An appchain-native NFT burnt on NEAR has been unlocked.
+		/// \[class_id, instance_id, sender_id, receiver\]
+		NftUnlocked(ClassIdOf<T>, InstanceIdOf<T>, Vec<u8>, T::AccountId),
+		/// An appchain-native NFT burnt on NEAR could not be unlocked.
+		/// \[class_id, instance_id, sender_id, receiver\]
+		NftUnlockFailed(ClassIdOf<T>, InstanceIdOf<T>, Vec<u8>, T::AccountId),
+		/// A call from the mainchain has been dispatched. \[notification_id, result\]
+		MainchainCallDispatched(u32, DispatchResult),
+		/// A call from the mainchain could not be decoded, or is not allowed.
+		/// \[notification_id\]
+		MainchainCallRejected(u32),
+		/// The state of the appchain has changed. \[previous, state\]
+		AppchainStateChanged(AppchainState, AppchainState),
+		/// A failed notification has used up its automatic retries. \[notification_id\]
+		NotificationRetriesExhausted(u32),
 	}
 
 	// Errors inform users that something went wrong.
@@ -442,10 +1069,86 @@ pub mod pallet {
 		NextSetIdOverflow,
 		/// Observations exceeded limit.
 		ObservationsExceededLimit,
+		/// Too many mainchain rpc endpoints.
+		TooManyRpcEndpoints,
+		/// Rpc endpoint is not a valid http(s) url.
+		InvalidRpcEndpoint,
+		/// The token is already registered.
+		TokenIdAlreadyRegistered,
+		/// The token is not registered.
+		TokenIdNotRegistered,
+		/// The asset is already registered for another token.
+		AssetIdAlreadyRegistered,
+		/// The decimals differ too much to scale the amounts.
+		InvalidDecimals,
+		/// The amount can not be represented exactly with the decimals on the other side.
+		AmountNotConvertible,
+		/// The notification is not in the retry queue.
+		NotificationNotFailed,
+		/// The transfer of the failed notification has failed again.
+		NotificationRetryFailed,
+		/// The transfers in this direction are paused.
+		TransfersPaused,
+		/// The transfer exceeds a transfer limit.
+		TransferLimitExceeded,
+		/// The window of a transfer limit must not be empty.
+		InvalidTransferLimit,
+		/// The bridge operation is paused.
+		OperationPaused,
+		/// The transfers of the asset are paused.
+		AssetPaused,
+		/// The amount does not cover the minimum amount or the fee of the transfer.
+		AmountTooLow,
+		/// The basis points of the fee exceed the whole amount.
+		InvalidBridgeFee,
+		/// The consensus threshold must be at least 1/2 and less than the whole stake.
+		InvalidConsensusThreshold,
+		/// The observation has reached the maximum number of voters.
+		TooManyVoters,
+		/// ReceiverId is not a valid NEAR account id.
+		InvalidNearAccountId,
+		/// The asset is minted for a bridged NEP-141 token, and can not be locked.
+		NotNativeAsset,
+		/// The NEP-171 contract is already registered.
+		ContractIdAlreadyRegistered,
+		/// The NEP-171 contract is not registered.
+		ContractIdNotRegistered,
+		/// The class is already registered for another NEP-171 contract.
+		ClassIdAlreadyRegistered,
+		/// The class is not registered for a NEP-171 contract.
+		WrongClassId,
+		/// The NFT is not minted for a bridged NEP-171 token.
+		UnknownNftInstance,
+		/// The NFT is minted for a bridged NEP-171 token, and can not be locked.
+		NotNativeNft,
+		/// The sender does not own the NFT.
+		NotNftOwner,
+		/// Next state change Id overflow.
+		NextStateChangeIdOverflow,
+		/// Wrong state change Id.
+		WrongStateChangeId,
+		/// Rpc endpoint is longer than `MaxRpcEndpointLength`.
+		RpcEndpointTooLong,
+		/// The class still has NFTs minted for bridged NEP-171 tokens.
+		NftClassNotEmpty,
 	}
 
 	#[pallet::hooks]
 	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		/// Prune the outdated observations and notification histories.
+		fn on_initialize(_n: BlockNumberFor<T>) -> Weight {
+			Self::prune_old_histories(T::MaxPrunedPerBlock::get())
+		}
+
+		/// Retry the failed notifications with the remaining weight of the block.
+		fn on_idle(_n: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+			Self::retry_failed_notifications(remaining_weight)
+		}
+
+		fn on_runtime_upgrade() -> Weight {
+			migrations::migrate::<T>()
+		}
+
 		/// Offchain Worker entry point.
 		///
 		/// By implementing `fn offchain_worker` you declare a new offchain worker.
@@ -457,10 +1160,7 @@ pub mod pallet {
 		/// You can use `Local Storage` API to coordinate runs of the worker.
 		fn offchain_worker(block_number: T::BlockNumber) {
 			let anchor_contract = Self::anchor_contract();
-			if !sp_io::offchain::is_validator()
-				|| !IsActivated::<T>::get()
-				|| anchor_contract.is_empty()
-			{
+			if !sp_io::offchain::is_validator() || anchor_contract.is_empty() {
 				return;
 			}
 
@@ -476,14 +1176,14 @@ pub mod pallet {
 				Some((public, validator_id)) => {
 					log!(debug, "public: {:?}, validator_id: {:?}", public, validator_id);
 
-					let mainchain_rpc_endpoint = Self::get_mainchain_rpc_endpoint(
+					let mainchain_rpc_endpoints = Self::get_mainchain_rpc_endpoints(
 						anchor_contract[anchor_contract.len() - 1] == 116,
 					); // last byte is 't'
-					log!(debug, "current mainchain_rpc_endpoint {:?}", mainchain_rpc_endpoint);
+					log!(debug, "current mainchain_rpc_endpoints {:?}", mainchain_rpc_endpoints);
 
 					if let Err(e) = Self::observing_mainchain(
 						block_number,
-						&mainchain_rpc_endpoint,
+						&mainchain_rpc_endpoints,
 						anchor_contract,
 						public,
 						validator_id,
@@ -531,7 +1231,13 @@ pub mod pallet {
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
 		/// Submit observations.
-		#[pallet::weight(0)]
+		///
+		/// The weight is refunded according to the number of stakes actually tallied. The
+		/// weight of the governance calls observed is reserved in full.
+		#[pallet::weight(<T as Config>::WeightInfo::submit_observations(
+			payload.observations.len() as u32,
+			(payload.observations.len() as u32).saturating_mul(T::MaxValidators::get()),
+		).saturating_add(Pallet::<T>::mainchain_calls_weight(&payload.observations)))]
 		pub fn submit_observations(
 			origin: OriginFor<T>,
 			payload: ObservationsPayload<
@@ -563,19 +1269,38 @@ pub mod pallet {
 			log!(debug, "️️️observations: {:#?},\nwho: {:?}", payload.observations, who);
 			//
 
+			let mut voters: u32 = 0;
 			for observation in payload.observations.iter() {
-				if let Err(e) = Self::submit_observation(&val_id, observation.clone()) {
-					log!(warn, "OCTOPUS-ALERT-DISCORD submit_observation: Error: {:?}", e);
+				match Self::submit_observation(&val_id, observation.clone()) {
+					Ok(count) => voters = voters.saturating_add(count),
+					Err(e) => {
+						log!(warn, "OCTOPUS-ALERT-DISCORD submit_observation: Error: {:?}", e);
+					},
 				}
 			}
 
-			Ok(().into())
+			Ok(Some(
+				<T as Config>::WeightInfo::submit_observations(
+					payload.observations.len() as u32,
+					voters,
+				)
+				.saturating_add(Self::mainchain_calls_weight(&payload.observations)),
+			)
+			.into())
 		}
 
+		/// Activate the appchain, or halt it, until the state of its anchor contract changes.
+		///
+		/// An appchain which has not started yet keeps booting when deactivated, otherwise it is
+		/// frozen rather than booting again.
 		#[pallet::weight(<T as Config>::WeightInfo::force_set_is_activated())]
 		pub fn force_set_is_activated(origin: OriginFor<T>, is_activated: bool) -> DispatchResult {
-			ensure_root(origin)?;
-			<IsActivated<T>>::put(is_activated);
+			T::GovernanceOrigin::ensure_origin(origin)?;
+			Self::set_appchain_state(match (is_activated, <CurrentAppchainState<T>>::get()) {
+				(true, _) => AppchainState::Active,
+				(false, AppchainState::Booting) => AppchainState::Booting,
+				(false, _) => AppchainState::Frozen,
+			});
 			Ok(())
 		}
 
@@ -583,6 +1308,8 @@ pub mod pallet {
 		pub fn force_set_next_set_id(origin: OriginFor<T>, next_set_id: u32) -> DispatchResult {
 			ensure_root(origin)?;
 			<NextSetId<T>>::put(next_set_id);
+			// The observations of the set ids above it will be submitted, and pruned, again.
+			<NextPruneSetId<T>>::mutate(|prune_id| *prune_id = (*prune_id).min(next_set_id));
 			Ok(())
 		}
 
@@ -597,6 +1324,39 @@ pub mod pallet {
 			Ok(())
 		}
 
+		/// Set the mainchain rpc endpoints used by the offchain workers.
+		///
+		/// The first endpoint is the primary one, the others are used as failovers.
+		#[pallet::weight(<T as Config>::WeightInfo::set_mainchain_rpc_endpoints(
+			endpoints.iter().map(|endpoint| endpoint.len() as u32).fold(0, u32::saturating_add)
+		))]
+		pub fn set_mainchain_rpc_endpoints(
+			origin: OriginFor<T>,
+			endpoints: Vec<Vec<u8>>,
+		) -> DispatchResult {
+			T::GovernanceOrigin::ensure_origin(origin)?;
+			ensure!(
+				endpoints.len() <= T::MaxRpcEndpoints::get() as usize,
+				Error::<T>::TooManyRpcEndpoints
+			);
+			for endpoint in endpoints.iter() {
+				ensure!(
+					endpoint.len() <= T::MaxRpcEndpointLength::get() as usize,
+					Error::<T>::RpcEndpointTooLong
+				);
+				let url =
+					sp_std::str::from_utf8(endpoint).map_err(|_| Error::<T>::InvalidRpcEndpoint)?;
+				ensure!(
+					url.starts_with("https://") || url.starts_with("http://"),
+					Error::<T>::InvalidRpcEndpoint
+				);
+			}
+
+			<MainchainRpcEndpoints<T>>::put(endpoints.clone());
+			Self::deposit_event(Event::MainchainRpcEndpointsUpdated(endpoints));
+			Ok(())
+		}
+
 		// cross chain transfer
 
 		// There are 2 kinds of assets:
@@ -607,6 +1367,18 @@ pub mod pallet {
 		// 2. NEP141 asset on mainchain
 		// mainchain:lock_asset()   -> appchain:mint_asset()
 		// mainchain:unlock_asset() <- appchain:burn_asset()
+		//
+		// 3. native asset on appchain
+		// mainchain:mint_asset() <- appchain:lock_asset()
+		// mainchain:burn_asset() -> appchain:unlock_asset()
+		//
+		// 4. NEP171 token on mainchain
+		// mainchain:lock_nft()   -> appchain:mint_nft()
+		// mainchain:unlock_nft() <- appchain:burn_nft()
+		//
+		// 5. native NFT on appchain
+		// mainchain:mint_nft() <- appchain:lock_nft()
+		// mainchain:burn_nft() -> appchain:unlock_nft()
 
 		#[pallet::weight(<T as Config>::WeightInfo::lock())]
 		#[transactional]
@@ -616,34 +1388,67 @@ pub mod pallet {
 			amount: BalanceOf<T>,
 		) -> DispatchResultWithPostInfo {
 			let who = ensure_signed(origin)?;
-			ensure!(IsActivated::<T>::get(), Error::<T>::NotActivated);
-
-			let receiver_id =
-				String::from_utf8(receiver_id).map_err(|_| Error::<T>::InvalidReceiverId)?;
-
-			let amount_wrapped: u128 = amount.checked_into().ok_or(Error::<T>::AmountOverflow)?;
+			Self::transfer_outbound(BridgedToken::Native, || {
+				ensure!(Self::is_active(), Error::<T>::NotActivated);
+				Self::ensure_not_paused(BridgeOperation::Lock, None)?;
+
+				let receiver_id =
+					String::from_utf8(receiver_id).map_err(|_| Error::<T>::InvalidReceiverId)?;
+				NearAccountId::validate(&receiver_id)
+					.map_err(|_| Error::<T>::InvalidNearAccountId)?;
+
+				let amount_wrapped: u128 =
+					amount.checked_into().ok_or(Error::<T>::AmountOverflow)?;
+				let decimals = <NativeTokenDecimals<T>>::get();
+				let (bridged, fee) =
+					Self::split_bridge_fee(BridgedToken::Native, amount_wrapped, decimals)?;
+				let amount_wrapped = Self::amount_to_near(decimals, bridged)?;
+
+				let amount: BalanceOf<T> =
+					bridged.checked_into().ok_or(Error::<T>::AmountOverflow)?;
+				let fee: BalanceOf<T> = fee.checked_into().ok_or(Error::<T>::AmountOverflow)?;
+				if !fee.is_zero() {
+					let imbalance =
+						T::Currency::withdraw(&who, fee, WithdrawReasons::FEE, AllowDeath)?;
+					T::OnBridgeFee::on_unbalanced(imbalance);
+				}
+				T::Currency::transfer(&who, &Self::account_id(), amount, AllowDeath)?;
+
+				let prefix = String::from("0x");
+				let hex_sender = prefix + &hex::encode(who.encode());
+				let message = LockPayload {
+					sender: hex_sender.clone(),
+					receiver_id: receiver_id.clone(),
+					amount: amount_wrapped,
+				};
+
+				T::UpwardMessagesInterface::submit(
+					&who,
+					PayloadType::Lock,
+					&message.try_to_vec().unwrap(),
+				)?;
+				Self::ensure_transfer_allowed(
+					TransferDirection::Outbound,
+					BridgedToken::Native,
+					amount_wrapped,
+				)?;
+				Self::record_transfer(
+					TransferDirection::Outbound,
+					BridgedToken::Native,
+					amount_wrapped,
+				);
+				Self::deposit_event(Event::Locked(
+					who,
+					receiver_id.as_bytes().to_vec(),
+					amount,
+					fee,
+				));
 
-			T::Currency::transfer(&who, &Self::account_id(), amount, AllowDeath)?;
+				Ok(().into())
+			})
+		}
 
-			let prefix = String::from("0x");
-			let hex_sender = prefix + &hex::encode(who.encode());
-			let message = LockPayload {
-				sender: hex_sender.clone(),
-				receiver_id: receiver_id.clone(),
-				amount: amount_wrapped,
-			};
-
-			T::UpwardMessagesInterface::submit(
-				&who,
-				PayloadType::Lock,
-				&message.try_to_vec().unwrap(),
-			)?;
-			Self::deposit_event(Event::Locked(who, receiver_id.as_bytes().to_vec(), amount));
-
-			Ok(().into())
-		}
-
-		#[pallet::weight(0)]
+		#[pallet::weight(<T as Config>::WeightInfo::mint_asset())]
 		#[transactional]
 		pub fn mint_asset(
 			origin: OriginFor<T>,
@@ -658,7 +1463,7 @@ pub mod pallet {
 			Self::mint_asset_inner(asset_id, sender_id, receiver, amount)
 		}
 
-		#[pallet::weight(0)]
+		#[pallet::weight(<T as Config>::WeightInfo::burn_asset())]
 		#[transactional]
 		pub fn burn_asset(
 			origin: OriginFor<T>,
@@ -667,58 +1472,577 @@ pub mod pallet {
 			amount: AssetBalanceOf<T>,
 		) -> DispatchResultWithPostInfo {
 			let sender = ensure_signed(origin)?;
-			ensure!(IsActivated::<T>::get(), Error::<T>::NotActivated);
+			Self::transfer_outbound(BridgedToken::Asset(asset_id), || {
+				ensure!(Self::is_active(), Error::<T>::NotActivated);
+				Self::ensure_not_paused(BridgeOperation::BurnAsset, Some(asset_id))?;
+
+				let receiver_id =
+					String::from_utf8(receiver_id).map_err(|_| Error::<T>::InvalidReceiverId)?;
+				NearAccountId::validate(&receiver_id)
+					.map_err(|_| Error::<T>::InvalidNearAccountId)?;
+
+				let token_id =
+					<TokenIdByAssetId<T>>::get(asset_id).ok_or(Error::<T>::WrongAssetId)?;
+
+				let token_id =
+					String::from_utf8(token_id).map_err(|_| Error::<T>::InvalidTokenId)?;
+				let decimals = <AssetDecimals<T>>::get(asset_id);
+				let (amount, fee) =
+					Self::split_bridge_fee(BridgedToken::Asset(asset_id), amount, decimals)?;
+				let amount_wrapped = Self::amount_to_near(decimals, amount)?;
+
+				if !fee.is_zero() {
+					let credit = <T::Assets as fungibles::Balanced<T::AccountId>>::withdraw(
+						asset_id, &sender, fee,
+					)?;
+					T::OnAssetBridgeFee::on_unbalanced(credit);
+				}
+				<T::Assets as fungibles::Mutate<T::AccountId>>::burn_from(
+					asset_id, &sender, amount,
+				)?;
+
+				let prefix = String::from("0x");
+				let hex_sender = prefix + &hex::encode(sender.encode());
+				let message = BurnAssetPayload {
+					token_id,
+					sender: hex_sender,
+					receiver_id: receiver_id.clone(),
+					amount: amount_wrapped,
+				};
+
+				T::UpwardMessagesInterface::submit(
+					&sender,
+					PayloadType::BurnAsset,
+					&message.try_to_vec().unwrap(),
+				)?;
+				Self::ensure_transfer_allowed(
+					TransferDirection::Outbound,
+					BridgedToken::Asset(asset_id),
+					amount_wrapped,
+				)?;
+				Self::record_transfer(
+					TransferDirection::Outbound,
+					BridgedToken::Asset(asset_id),
+					amount_wrapped,
+				);
+				Self::deposit_event(Event::AssetBurned(
+					asset_id,
+					sender,
+					receiver_id.as_bytes().to_vec(),
+					amount,
+					fee,
+				));
 
-			let receiver_id =
-				String::from_utf8(receiver_id).map_err(|_| Error::<T>::InvalidReceiverId)?;
+				Ok(().into())
+			})
+		}
+
+		/// Register the asset minted for a bridged NEP-141 token.
+		#[pallet::weight(<T as Config>::WeightInfo::register_asset())]
+		pub fn register_asset(
+			origin: OriginFor<T>,
+			token_id: Vec<u8>,
+			asset_id: AssetIdOf<T>,
+		) -> DispatchResult {
+			T::GovernanceOrigin::ensure_origin(origin)?;
+			ensure!(sp_std::str::from_utf8(&token_id).is_ok(), Error::<T>::InvalidTokenId);
+			ensure!(
+				!<AssetIdByName<T>>::contains_key(&token_id),
+				Error::<T>::TokenIdAlreadyRegistered
+			);
+			ensure!(
+				!<TokenIdByAssetId<T>>::contains_key(asset_id),
+				Error::<T>::AssetIdAlreadyRegistered
+			);
+
+			<AssetIdByName<T>>::insert(&token_id, asset_id);
+			<TokenIdByAssetId<T>>::insert(asset_id, &token_id);
+			Self::deposit_event(Event::AssetRegistered(token_id, asset_id));
+			Ok(())
+		}
+
+		/// Change the asset minted for a registered token.
+		#[pallet::weight(<T as Config>::WeightInfo::update_asset())]
+		pub fn update_asset(
+			origin: OriginFor<T>,
+			token_id: Vec<u8>,
+			asset_id: AssetIdOf<T>,
+		) -> DispatchResult {
+			T::GovernanceOrigin::ensure_origin(origin)?;
+			let old_asset_id = <AssetIdByName<T>>::try_get(&token_id)
+				.map_err(|_| Error::<T>::TokenIdNotRegistered)?;
+			ensure!(
+				!<TokenIdByAssetId<T>>::contains_key(asset_id),
+				Error::<T>::AssetIdAlreadyRegistered
+			);
+
+			<TokenIdByAssetId<T>>::remove(old_asset_id);
+			<AssetDecimals<T>>::remove(old_asset_id);
+			<AssetIdByName<T>>::insert(&token_id, asset_id);
+			<TokenIdByAssetId<T>>::insert(asset_id, &token_id);
+			Self::deposit_event(Event::AssetUpdated(token_id, old_asset_id, asset_id));
+			Ok(())
+		}
+
+		/// Deregister a bridged token.
+		#[pallet::weight(<T as Config>::WeightInfo::deregister_asset())]
+		pub fn deregister_asset(origin: OriginFor<T>, token_id: Vec<u8>) -> DispatchResult {
+			T::GovernanceOrigin::ensure_origin(origin)?;
+			let asset_id = <AssetIdByName<T>>::try_get(&token_id)
+				.map_err(|_| Error::<T>::TokenIdNotRegistered)?;
+
+			<AssetIdByName<T>>::remove(&token_id);
+			<TokenIdByAssetId<T>>::remove(asset_id);
+			<AssetDecimals<T>>::remove(asset_id);
+			Self::deposit_event(Event::AssetDeregistered(token_id, asset_id));
+			Ok(())
+		}
+
+		/// Set the decimals of a bridged asset, registered or appchain-native, on NEAR and on the
+		/// appchain.
+		///
+		/// `None` means that the asset has the same decimals on both sides.
+		#[pallet::weight(<T as Config>::WeightInfo::set_asset_decimals())]
+		pub fn set_asset_decimals(
+			origin: OriginFor<T>,
+			asset_id: AssetIdOf<T>,
+			decimals: Option<TokenDecimals>,
+		) -> DispatchResult {
+			T::GovernanceOrigin::ensure_origin(origin)?;
+			ensure!(decimals.map_or(true, |d| d.is_valid()), Error::<T>::InvalidDecimals);
+
+			<AssetDecimals<T>>::set(asset_id, decimals);
+			Self::deposit_event(Event::AssetDecimalsUpdated(asset_id, decimals));
+			Ok(())
+		}
+
+		/// Set the decimals of the native token on NEAR and on the appchain.
+		///
+		/// `None` means that the native token has the same decimals on both sides.
+		#[pallet::weight(<T as Config>::WeightInfo::set_native_token_decimals())]
+		pub fn set_native_token_decimals(
+			origin: OriginFor<T>,
+			decimals: Option<TokenDecimals>,
+		) -> DispatchResult {
+			T::GovernanceOrigin::ensure_origin(origin)?;
+			ensure!(decimals.map_or(true, |d| d.is_valid()), Error::<T>::InvalidDecimals);
+
+			<NativeTokenDecimals<T>>::set(decimals);
+			Self::deposit_event(Event::NativeTokenDecimalsUpdated(decimals));
+			Ok(())
+		}
+
+		/// Retry the transfer of a failed notification.
+		#[pallet::weight(<T as Config>::WeightInfo::retry_notification())]
+		#[transactional]
+		pub fn retry_notification(origin: OriginFor<T>, notification_id: u32) -> DispatchResult {
+			ensure_signed(origin)?;
+			let (observation, _) = <FailedNotifications<T>>::get(notification_id)
+				.ok_or(Error::<T>::NotificationNotFailed)?;
+
+			Self::retry_failed_notification(notification_id, &observation)
+				.map_err(|_| Error::<T>::NotificationRetryFailed)?;
+			Ok(())
+		}
+
+		/// Transfer the funds of a failed notification to another receiver.
+		#[pallet::weight(<T as Config>::WeightInfo::redirect_notification())]
+		#[transactional]
+		pub fn redirect_notification(
+			origin: OriginFor<T>,
+			notification_id: u32,
+			receiver: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResult {
+			T::GovernanceOrigin::ensure_origin(origin)?;
+			let receiver = T::Lookup::lookup(receiver)?;
+			let (mut observation, _) = <FailedNotifications<T>>::get(notification_id)
+				.ok_or(Error::<T>::NotificationNotFailed)?;
+
+			match observation {
+				Observation::Burn(ref mut event) => event.receiver = receiver.clone(),
+				Observation::LockAsset(ref mut event) => event.receiver = receiver.clone(),
+				Observation::BurnNativeAsset(ref mut event) => event.receiver = receiver.clone(),
+				Observation::LockNft(ref mut event) => event.receiver = receiver.clone(),
+				Observation::BurnNft(ref mut event) => event.receiver = receiver.clone(),
+				Observation::UpdateValidatorSet(_)
+				| Observation::GovernanceCall(_)
+				| Observation::UpdateAppchainState(_) => {},
+			}
+			Self::retry_failed_notification(notification_id, &observation)
+				.map_err(|_| Error::<T>::NotificationRetryFailed)?;
+			Self::deposit_event(Event::NotificationRedirected(notification_id, receiver));
+			Ok(())
+		}
+
+		/// Send the funds of a failed notification back to its sender on NEAR.
+		#[pallet::weight(<T as Config>::WeightInfo::refund_notification())]
+		#[transactional]
+		pub fn refund_notification(origin: OriginFor<T>, notification_id: u32) -> DispatchResult {
+			T::GovernanceOrigin::ensure_origin(origin)?;
+			let (observation, _) = <FailedNotifications<T>>::get(notification_id)
+				.ok_or(Error::<T>::NotificationNotFailed)?;
+
+			Self::refund_failed_notification(notification_id, observation)
+		}
+
+		/// Set or remove a limit of the transfers in a direction, and reset its volume.
+		///
+		/// The limit applies to a token, or to all the tokens if `token` is `None`. Its amount
+		/// is in the units of the tokens on NEAR.
+		#[pallet::weight(<T as Config>::WeightInfo::set_transfer_limit())]
+		pub fn set_transfer_limit(
+			origin: OriginFor<T>,
+			direction: TransferDirection,
+			token: Option<BridgedToken<AssetIdOf<T>>>,
+			limit: Option<TransferLimit<T::BlockNumber>>,
+		) -> DispatchResult {
+			T::GovernanceOrigin::ensure_origin(origin)?;
+			ensure!(limit.map_or(true, |l| !l.window.is_zero()), Error::<T>::InvalidTransferLimit);
+
+			<TransferLimits<T>>::set(direction, token, limit);
+			<TransferVolumes<T>>::remove(direction, token);
+			Self::deposit_event(Event::TransferLimitUpdated(direction, token, limit));
+			Ok(())
+		}
+
+		/// Resume the transfers in a direction paused by an exceeded limit, and reset its
+		/// volumes.
+		#[pallet::weight(<T as Config>::WeightInfo::resume_transfers())]
+		pub fn resume_transfers(
+			origin: OriginFor<T>,
+			direction: TransferDirection,
+		) -> DispatchResult {
+			T::GovernanceOrigin::ensure_origin(origin)?;
+
+			<TransfersPaused<T>>::remove(direction);
+			<TransferVolumes<T>>::remove_prefix(direction, None);
+			Self::deposit_event(Event::TransfersResumed(direction));
+			Ok(())
+		}
+
+		/// Set or remove the fee schedule of the outbound transfers of a token.
+		#[pallet::weight(<T as Config>::WeightInfo::set_bridge_fee())]
+		pub fn set_bridge_fee(
+			origin: OriginFor<T>,
+			token: BridgedToken<AssetIdOf<T>>,
+			fee: Option<BridgeFee>,
+		) -> DispatchResult {
+			T::GovernanceOrigin::ensure_origin(origin)?;
+			ensure!(fee.map_or(true, |f| f.is_valid()), Error::<T>::InvalidBridgeFee);
+
+			<BridgeFees<T>>::set(token, fee);
+			Self::deposit_event(Event::BridgeFeeUpdated(token, fee));
+			Ok(())
+		}
 
-			let token_id = <AssetIdByName<T>>::iter()
-				.find(|p| p.1 == asset_id)
-				.map(|p| p.0)
-				.ok_or(Error::<T>::WrongAssetId)?;
+		/// Set the share of the active stake which the voters of an observation must exceed.
+		///
+		/// `None` restores the default of 2/3.
+		#[pallet::weight(<T as Config>::WeightInfo::set_consensus_threshold())]
+		pub fn set_consensus_threshold(
+			origin: OriginFor<T>,
+			threshold: Option<Perbill>,
+		) -> DispatchResult {
+			T::GovernanceOrigin::ensure_origin(origin)?;
+			ensure!(
+				threshold.map_or(true, |t| t >= Perbill::from_percent(50) && t < Perbill::one()),
+				Error::<T>::InvalidConsensusThreshold
+			);
+
+			<ConsensusThreshold<T>>::set(threshold);
+			Self::deposit_event(Event::ConsensusThresholdUpdated(threshold));
+			Ok(())
+		}
+
+		/// Pause or resume a bridge operation.
+		///
+		/// Resuming the validator set updates applies the next validator set if it has reached
+		/// consensus while they were paused.
+		#[pallet::weight(<T as Config>::WeightInfo::set_operation_paused())]
+		pub fn set_operation_paused(
+			origin: OriginFor<T>,
+			operation: BridgeOperation,
+			paused: bool,
+		) -> DispatchResult {
+			T::PauseOrigin::ensure_origin(origin)?;
+
+			if paused {
+				<PausedOperations<T>>::insert(operation, true);
+			} else {
+				<PausedOperations<T>>::remove(operation);
+				if operation == BridgeOperation::UpdateValidatorSet {
+					Self::apply_paused_validator_set().map_err(|error| error.error)?;
+				}
+			}
+			Self::deposit_event(Event::OperationPauseUpdated(operation, paused));
+			Ok(())
+		}
+
+		/// Pause or resume the transfers of a bridged asset.
+		#[pallet::weight(<T as Config>::WeightInfo::set_asset_paused())]
+		pub fn set_asset_paused(
+			origin: OriginFor<T>,
+			asset_id: AssetIdOf<T>,
+			paused: bool,
+		) -> DispatchResult {
+			T::PauseOrigin::ensure_origin(origin)?;
+
+			if paused {
+				<PausedAssets<T>>::insert(asset_id, true);
+			} else {
+				<PausedAssets<T>>::remove(asset_id);
+			}
+			Self::deposit_event(Event::AssetPauseUpdated(asset_id, paused));
+			Ok(())
+		}
+
+		/// Lock an appchain-native asset in the pallet account, to be minted as a wrapped token
+		/// on NEAR.
+		#[pallet::weight(<T as Config>::WeightInfo::lock_asset())]
+		#[transactional]
+		pub fn lock_asset(
+			origin: OriginFor<T>,
+			asset_id: AssetIdOf<T>,
+			receiver_id: Vec<u8>,
+			amount: AssetBalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+			Self::transfer_outbound(BridgedToken::Asset(asset_id), || {
+				ensure!(Self::is_active(), Error::<T>::NotActivated);
+				Self::ensure_not_paused(BridgeOperation::LockAsset, Some(asset_id))?;
+
+				let receiver_id =
+					String::from_utf8(receiver_id).map_err(|_| Error::<T>::InvalidReceiverId)?;
+				NearAccountId::validate(&receiver_id)
+					.map_err(|_| Error::<T>::InvalidNearAccountId)?;
+
+				// The assets of the NEP-141 tokens are burnt instead.
+				ensure!(!<TokenIdByAssetId<T>>::contains_key(asset_id), Error::<T>::NotNativeAsset);
+
+				let decimals = <AssetDecimals<T>>::get(asset_id);
+				let (amount, fee) =
+					Self::split_bridge_fee(BridgedToken::Asset(asset_id), amount, decimals)?;
+				let amount_wrapped = Self::amount_to_near(decimals, amount)?;
+
+				if !fee.is_zero() {
+					let credit = <T::Assets as fungibles::Balanced<T::AccountId>>::withdraw(
+						asset_id, &sender, fee,
+					)?;
+					T::OnAssetBridgeFee::on_unbalanced(credit);
+				}
+				<T::Assets as fungibles::Transfer<T::AccountId>>::transfer(
+					asset_id,
+					&sender,
+					&Self::account_id(),
+					amount,
+					false,
+				)?;
+
+				let prefix = String::from("0x");
+				let hex_sender = prefix + &hex::encode(sender.encode());
+				let message = LockAssetPayload {
+					asset_id,
+					sender: hex_sender,
+					receiver_id: receiver_id.clone(),
+					amount: amount_wrapped,
+				};
+
+				T::UpwardMessagesInterface::submit(
+					&sender,
+					PayloadType::LockAsset,
+					&message.try_to_vec().unwrap(),
+				)?;
+				Self::ensure_transfer_allowed(
+					TransferDirection::Outbound,
+					BridgedToken::Asset(asset_id),
+					amount_wrapped,
+				)?;
+				Self::record_transfer(
+					TransferDirection::Outbound,
+					BridgedToken::Asset(asset_id),
+					amount_wrapped,
+				);
+				Self::deposit_event(Event::AssetLocked(
+					asset_id,
+					sender,
+					receiver_id.as_bytes().to_vec(),
+					amount,
+					fee,
+				));
 
+				Ok(().into())
+			})
+		}
+
+		/// Register the class of the NFTs minted for a bridged NEP-171 contract.
+		#[pallet::weight(<T as Config>::WeightInfo::register_nft_class())]
+		pub fn register_nft_class(
+			origin: OriginFor<T>,
+			contract_id: Vec<u8>,
+			class_id: ClassIdOf<T>,
+		) -> DispatchResult {
+			T::GovernanceOrigin::ensure_origin(origin)?;
+			ensure!(sp_std::str::from_utf8(&contract_id).is_ok(), Error::<T>::InvalidTokenId);
+			ensure!(
+				!<ClassIdByContractId<T>>::contains_key(&contract_id),
+				Error::<T>::ContractIdAlreadyRegistered
+			);
+			ensure!(
+				!<ContractIdByClassId<T>>::contains_key(class_id),
+				Error::<T>::ClassIdAlreadyRegistered
+			);
+
+			<ClassIdByContractId<T>>::insert(&contract_id, class_id);
+			<ContractIdByClassId<T>>::insert(class_id, &contract_id);
+			Self::deposit_event(Event::NftClassRegistered(contract_id, class_id));
+			Ok(())
+		}
+
+		/// Deregister a bridged NEP-171 contract, once all the NFTs minted for its tokens have
+		/// been burned.
+		#[pallet::weight(<T as Config>::WeightInfo::deregister_nft_class())]
+		pub fn deregister_nft_class(origin: OriginFor<T>, contract_id: Vec<u8>) -> DispatchResult {
+			T::GovernanceOrigin::ensure_origin(origin)?;
+			let class_id = <ClassIdByContractId<T>>::get(&contract_id)
+				.ok_or(Error::<T>::ContractIdNotRegistered)?;
+			// The NFTs minted for the class could not be burned back to NEAR anymore.
+			ensure!(
+				<NftTokenIds<T>>::iter_prefix(class_id).next().is_none(),
+				Error::<T>::NftClassNotEmpty
+			);
+
+			<ClassIdByContractId<T>>::remove(&contract_id);
+
+			<ContractIdByClassId<T>>::remove(class_id);
+			Self::deposit_event(Event::NftClassDeregistered(contract_id, class_id));
+			Ok(())
+		}
+
+		/// Burn an NFT minted for a NEP-171 token, to unlock the token on NEAR.
+		#[pallet::weight(<T as Config>::WeightInfo::burn_nft())]
+		#[transactional]
+		pub fn burn_nft(
+			origin: OriginFor<T>,
+			class_id: ClassIdOf<T>,
+			instance_id: InstanceIdOf<T>,
+			receiver_id: Vec<u8>,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+			ensure!(Self::is_active(), Error::<T>::NotActivated);
+			Self::ensure_not_paused(BridgeOperation::BurnNft, None)?;
+
+			let receiver_id =
+				String::from_utf8(receiver_id).map_err(|_| Error::<T>::InvalidReceiverId)?;
+			NearAccountId::validate(&receiver_id).map_err(|_| Error::<T>::InvalidNearAccountId)?;
+
+			let contract_id =
+				<ContractIdByClassId<T>>::get(class_id).ok_or(Error::<T>::WrongClassId)?;
+			let contract_id =
+				String::from_utf8(contract_id).map_err(|_| Error::<T>::InvalidTokenId)?;
+			let token_id = <NftTokenIds<T>>::get(class_id, instance_id)
+				.ok_or(Error::<T>::UnknownNftInstance)?;
 			let token_id = String::from_utf8(token_id).map_err(|_| Error::<T>::InvalidTokenId)?;
+			Self::ensure_nft_owner(class_id, instance_id, &sender)?;
 
-			<T::Assets as fungibles::Mutate<T::AccountId>>::burn_from(asset_id, &sender, amount)?;
+			<T::Uniques as nonfungibles::Mutate<T::AccountId>>::burn_from(&class_id, &instance_id)?;
+			<NftTokenIds<T>>::remove(class_id, instance_id);
+			<NftMetadata<T>>::remove(class_id, instance_id);
 
 			let prefix = String::from("0x");
 			let hex_sender = prefix + &hex::encode(sender.encode());
-			let message = BurnAssetPayload {
+			let message = BurnNftPayload {
+				contract_id,
 				token_id,
 				sender: hex_sender,
 				receiver_id: receiver_id.clone(),
-				amount,
 			};
 
 			T::UpwardMessagesInterface::submit(
 				&sender,
-				PayloadType::BurnAsset,
+				PayloadType::BurnNft,
 				&message.try_to_vec().unwrap(),
 			)?;
-			Self::deposit_event(Event::AssetBurned(
-				asset_id,
+			Self::deposit_event(Event::NftBurned(
+				class_id,
+				instance_id,
 				sender,
 				receiver_id.as_bytes().to_vec(),
-				amount,
 			));
 
 			Ok(().into())
 		}
+
+		/// Lock an appchain-native NFT in the pallet account, to be minted as a wrapped NFT on
+		/// NEAR with its metadata.
+		#[pallet::weight(<T as Config>::WeightInfo::lock_nft())]
+		#[transactional]
+		pub fn lock_nft(
+			origin: OriginFor<T>,
+			class_id: ClassIdOf<T>,
+			instance_id: InstanceIdOf<T>,
+			receiver_id: Vec<u8>,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+			ensure!(Self::is_active(), Error::<T>::NotActivated);
+			Self::ensure_not_paused(BridgeOperation::LockNft, None)?;
+
+			let receiver_id =
+				String::from_utf8(receiver_id).map_err(|_| Error::<T>::InvalidReceiverId)?;
+			NearAccountId::validate(&receiver_id).map_err(|_| Error::<T>::InvalidNearAccountId)?;
+
+			// The NFTs of the NEP-171 tokens are burnt instead.
+			ensure!(!<ContractIdByClassId<T>>::contains_key(class_id), Error::<T>::NotNativeNft);
+			Self::ensure_nft_owner(class_id, instance_id, &sender)?;
+
+			<T::Uniques as nonfungibles::Transfer<T::AccountId>>::transfer(
+				&class_id,
+				&instance_id,
+				&Self::account_id(),
+			)?;
+
+			let prefix = String::from("0x");
+			let hex_sender = prefix + &hex::encode(sender.encode());
+			let message = LockNftPayload {
+				class: class_id,
+				instance: instance_id,
+				sender: hex_sender,
+				receiver_id: receiver_id.clone(),
+				metadata: Self::native_nft_metadata(class_id, instance_id),
+			};
+
+			T::UpwardMessagesInterface::submit(
+				&sender,
+				PayloadType::LockNft,
+				&message.try_to_vec().unwrap(),
+			)?;
+			Self::deposit_event(Event::NftLocked(
+				class_id,
+				instance_id,
+				sender,
+				receiver_id.as_bytes().to_vec(),
+			));
+
+			Ok(().into())
+		}
+
+		/// Set the state of the appchain, overriding the state observed on the anchor contract
+		/// until it changes there.
+		#[pallet::weight(<T as Config>::WeightInfo::force_set_appchain_state())]
+		pub fn force_set_appchain_state(
+			origin: OriginFor<T>,
+			state: AppchainState,
+		) -> DispatchResult {
+			T::GovernanceOrigin::ensure_origin(origin)?;
+			Self::set_appchain_state(state);
+			Ok(())
+		}
 	}
 
 	impl<T: Config> Pallet<T> {
-		fn account_id() -> T::AccountId {
+		pub(crate) fn account_id() -> T::AccountId {
 			T::PalletId::get().into_account()
 		}
 
-		fn bsngate_rpc_endpoint(is_testnet: bool) -> String {
-			if is_testnet {
-				"https://ca.bsngate.com/api/8803b555a830c4d2ac680a7fdefc46aeb7738c4f6f0513f0aec328768ad71002/Near-Testnet/rpc".to_string()
-			} else {
-				"https://ca.bsngate.com/api/edc6aab2f13e1dc049fab8b4bcae29cdae53ce84df2d8b352f9497f290a697e2/Near-Mainnet/rpc".to_string()
-			}
-		}
-
 		fn default_rpc_endpoint(is_testnet: bool) -> String {
 			if is_testnet {
 				"https://rpc.testnet.near.org".to_string()
@@ -727,7 +2051,13 @@ pub mod pallet {
 			}
 		}
 
-		fn get_mainchain_rpc_endpoint(is_testnet: bool) -> String {
+		/// Get the mainchain rpc endpoints in the order they should be tried.
+		///
+		/// The endpoint configured in the local storage of this node comes first, then the
+		/// endpoints stored on chain. The public endpoint of NEAR is used if none is configured.
+		pub(crate) fn get_mainchain_rpc_endpoints(is_testnet: bool) -> Vec<String> {
+			let mut endpoints: Vec<String> = Vec::new();
+
 			let kind = sp_core::offchain::StorageKind::PERSISTENT;
 			if let Some(data) = sp_io::offchain::local_storage_get(
 				kind,
@@ -735,15 +2065,25 @@ pub mod pallet {
 			) {
 				if let Ok(rpc_url) = String::from_utf8(data) {
 					log!(debug, "The configure url is {:?} ", rpc_url.clone());
-					return rpc_url;
+					endpoints.push(rpc_url);
 				} else {
-					log!(warn, "Parse configure url error, return default rpc url");
-					return Self::default_rpc_endpoint(is_testnet);
+					log!(warn, "Parse configure url error, ignore it");
 				}
-			} else {
+			}
+
+			for endpoint in <MainchainRpcEndpoints<T>>::get() {
+				if let Ok(rpc_url) = String::from_utf8(endpoint) {
+					if !endpoints.contains(&rpc_url) {
+						endpoints.push(rpc_url);
+					}
+				}
+			}
+
+			if endpoints.is_empty() {
 				log!(debug, "No configuration for rpc, return default rpc url");
-				return Self::default_rpc_endpoint(is_testnet);
+				endpoints.push(Self::default_rpc_endpoint(is_testnet));
 			}
+			endpoints
 		}
 
 		fn should_send(block_number: T::BlockNumber) -> bool {
@@ -816,71 +2156,29 @@ pub mod pallet {
 
 		pub(crate) fn observing_mainchain(
 			block_number: T::BlockNumber,
-			mainchain_rpc_endpoint: &str,
+			mainchain_rpc_endpoints: &[String],
 			anchor_contract: Vec<u8>,
 			public: <T as SigningTypes>::Public,
 			_validator_id: T::AccountId,
 		) -> Result<(), &'static str> {
-			let mut obs: Vec<Observation<<T as frame_system::Config>::AccountId>>;
 			let next_notification_id = NextNotificationId::<T>::get();
 			log!(debug, "next_notification_id: {}", next_notification_id);
 			let next_set_id = NextSetId::<T>::get();
 			log!(debug, "next_set_id: {}", next_set_id);
 
-			// Make an external HTTP request to fetch the current price.
-			// Note this call will block until response is received.
-			let ret = Self::get_validator_list_of(
-				mainchain_rpc_endpoint,
-				anchor_contract.clone(),
+			// Fetch the state of the appchain, with the validator set and the notifications of
+			// an active appchain from the mainchain.
+			// Note these calls will block until the responses are received.
+			let (state, mut obs) = T::MainchainClient::observations(
+				mainchain_rpc_endpoints,
+				&anchor_contract,
 				next_set_id,
-			);
-
-			match ret {
-				Ok(observations) => {
-					obs = observations;
-				}
-				Err(_) => {
-					log!(debug, "retry with failsafe endpoint to get validators");
-					obs = Self::get_validator_list_of(
-						&Self::bsngate_rpc_endpoint(
-							anchor_contract[anchor_contract.len() - 1] == 116,
-						), // last byte is 't'
-						anchor_contract.clone(),
-						next_set_id,
-					)
-					.map_err(|_| "Failed to get_validator_list_of")?;
-				}
-			}
-
-			// check cross-chain transfers only if there isn't a validator_set update.
-			if obs.len() == 0 {
-				log!(debug, "No validat_set updates, try to get appchain notifications.");
-				// Make an external HTTP request to fetch the current price.
-				// Note this call will block until response is received.
-				let ret = Self::get_appchain_notification_histories(
-					mainchain_rpc_endpoint,
-					anchor_contract.clone(),
-					next_notification_id,
-					T::RequestEventLimit::get(),
-				);
-
-				match ret {
-					Ok(observations) => {
-						obs = observations;
-					}
-					Err(_) => {
-						log!(debug, "retry with failsafe endpoint to get notify");
-						obs = Self::get_appchain_notification_histories(
-							&Self::bsngate_rpc_endpoint(
-								anchor_contract[anchor_contract.len() - 1] == 116,
-							), // last byte is 't'
-							anchor_contract,
-							next_notification_id,
-							T::RequestEventLimit::get(),
-						)
-						.map_err(|_| "Failed to get_appchain_notification_histories")?;
-					}
-				}
+				next_notification_id,
+				T::RequestEventLimit::get(),
+			)?;
+			log!(debug, "appchain state on mainchain: {:?}", state);
+			if let Some(change) = state.and_then(Self::observed_state_change) {
+				obs.insert(0, Observation::UpdateAppchainState(change));
 			}
 
 			if obs.len() == 0 {
@@ -919,6 +2217,7 @@ pub mod pallet {
 			receiver: T::AccountId,
 			amount: u128,
 		) -> DispatchResultWithPostInfo {
+			let amount = Self::amount_from_near(<NativeTokenDecimals<T>>::get(), amount)?;
 			let amount_unwrapped = amount.checked_into().ok_or(Error::<T>::AmountOverflow)?;
 			// unlock native token
 			T::Currency::transfer(&Self::account_id(), &receiver, amount_unwrapped, KeepAlive)?;
@@ -927,6 +2226,210 @@ pub mod pallet {
 			Ok(().into())
 		}
 
+		/// Transfer the funds of an inbound notification to its receiver.
+		///
+		/// Returns the result to record for the notification if the transfer fails.
+		pub(crate) fn execute_notification(
+			observation: &Observation<T::AccountId>,
+		) -> Result<(), NotificationResult> {
+			// The transfers wait in the retry queue while the appchain is halted.
+			let is_transfer = !matches!(
+				observation,
+				Observation::UpdateValidatorSet(_)
+					| Observation::UpdateAppchainState(_)
+					| Observation::GovernanceCall(_)
+			);
+			if is_transfer && Self::is_halted() {
+				return Err(NotificationResult::OperationPaused);
+			}
+
+			match observation.clone() {
+				Observation::UpdateValidatorSet(_) | Observation::UpdateAppchainState(_) => {},
+				Observation::Burn(event) => {
+					Self::ensure_not_paused(BridgeOperation::Unlock, None)
+						.map_err(|_| NotificationResult::OperationPaused)?;
+					Self::ensure_inbound_transfer_allowed(BridgedToken::Native, event.amount)?;
+					if let Err(error) = Self::unlock_inner(
+						event.sender_id.clone(),
+						event.receiver.clone(),
+						event.amount,
+					) {
+						log!(info, "️️️failed to unlock native token: {:?}", error);
+						let min = T::Currency::minimum_balance();
+						let amount_unwrapped =
+							Self::amount_from_near(<NativeTokenDecimals<T>>::get(), event.amount)
+								.ok()
+								.and_then(|amount| amount.checked_into())
+								.unwrap_or(min); //Check: should not return error.
+						Self::deposit_event(Event::UnlockFailed(
+							event.sender_id,
+							event.receiver,
+							amount_unwrapped,
+						));
+						return Err(NotificationResult::UnlockFailed);
+					}
+					Self::record_transfer(
+						TransferDirection::Inbound,
+						BridgedToken::Native,
+						event.amount,
+					);
+				},
+				Observation::LockAsset(event) => {
+					let asset_id = match <AssetIdByName<T>>::try_get(&event.token_id) {
+						Ok(asset_id) => asset_id,
+						Err(_) => {
+							Self::deposit_event(Event::AssetIdGetFailed(
+								event.token_id,
+								event.sender_id,
+								event.receiver,
+								event.amount,
+							));
+							return Err(NotificationResult::AssetGetFailed);
+						},
+					};
+					Self::ensure_not_paused(BridgeOperation::MintAsset, Some(asset_id))
+						.map_err(|_| NotificationResult::OperationPaused)?;
+					Self::ensure_inbound_transfer_allowed(
+						BridgedToken::Asset(asset_id),
+						event.amount,
+					)?;
+					log!(
+						info,
+						"️️️mint asset:{:?}, sender_id:{:?}, receiver:{:?}, amount:{:?}",
+						asset_id,
+						event.sender_id,
+						event.receiver,
+						event.amount,
+					);
+					let minted =
+						Self::amount_from_near(<AssetDecimals<T>>::get(asset_id), event.amount)
+							.map_err(Into::into)
+							.and_then(|amount| {
+								Self::mint_asset_inner(
+									asset_id,
+									event.sender_id.clone(),
+									event.receiver.clone(),
+									amount,
+								)
+							});
+					if let Err(error) = minted {
+						log!(warn, "️️️failed to mint asset: {:?}", error);
+						Self::deposit_event(Event::AssetMintFailed(
+							asset_id,
+							event.sender_id,
+							event.receiver,
+							event.amount,
+						));
+						return Err(NotificationResult::AssetMintFailed);
+					}
+					Self::record_transfer(
+						TransferDirection::Inbound,
+						BridgedToken::Asset(asset_id),
+						event.amount,
+					);
+				},
+				Observation::BurnNativeAsset(event) => {
+					let asset_id = event.asset_id;
+					Self::ensure_not_paused(BridgeOperation::UnlockAsset, Some(asset_id))
+						.map_err(|_| NotificationResult::OperationPaused)?;
+					Self::ensure_inbound_transfer_allowed(
+						BridgedToken::Asset(asset_id),
+						event.amount,
+					)?;
+					if let Err(error) = Self::unlock_asset_inner(
+						asset_id,
+						event.sender_id.clone(),
+						event.receiver.clone(),
+						event.amount,
+					) {
+						log!(warn, "️️️failed to unlock asset: {:?}", error);
+						let amount =
+							Self::amount_from_near(<AssetDecimals<T>>::get(asset_id), event.amount)
+								.unwrap_or(event.amount);
+						Self::deposit_event(Event::AssetUnlockFailed(
+							asset_id,
+							event.sender_id,
+							event.receiver,
+							amount,
+						));
+						return Err(NotificationResult::AssetUnlockFailed);
+					}
+					Self::record_transfer(
+						TransferDirection::Inbound,
+						BridgedToken::Asset(asset_id),
+						event.amount,
+					);
+				},
+				Observation::LockNft(event) => {
+					Self::ensure_not_paused(BridgeOperation::MintNft, None)
+						.map_err(|_| NotificationResult::OperationPaused)?;
+					if let Err(error) = Self::mint_nft_inner(
+						event.contract_id.clone(),
+						event.token_id.clone(),
+						event.sender_id.clone(),
+						event.receiver.clone(),
+						event.metadata,
+					) {
+						log!(warn, "️️️failed to mint nft: {:?}", error);
+						Self::deposit_event(Event::NftMintFailed(
+							event.contract_id,
+							event.token_id,
+							event.sender_id,
+							event.receiver,
+						));
+						return Err(NotificationResult::NftMintFailed);
+					}
+				},
+				Observation::BurnNft(event) => {
+					Self::ensure_not_paused(BridgeOperation::UnlockNft, None)
+						.map_err(|_| NotificationResult::OperationPaused)?;
+					if let Err(error) = Self::unlock_nft_inner(
+						event.class,
+						event.instance,
+						event.sender_id.clone(),
+						event.receiver.clone(),
+					) {
+						log!(warn, "️️️failed to unlock nft: {:?}", error);
+						Self::deposit_event(Event::NftUnlockFailed(
+							event.class,
+							event.instance,
+							event.sender_id,
+							event.receiver,
+						));
+						return Err(NotificationResult::NftUnlockFailed);
+					}
+				},
+				Observation::GovernanceCall(event) => {
+					Self::ensure_not_paused(BridgeOperation::DispatchMainchainCall, None)
+						.map_err(|_| NotificationResult::OperationPaused)?;
+					Self::dispatch_mainchain_call(event.index, &event.call)?;
+				},
+			}
+
+			Ok(())
+		}
+
+		fn unlock_asset_inner(
+			asset_id: AssetIdOf<T>,
+			sender_id: Vec<u8>,
+			receiver: T::AccountId,
+			amount: u128,
+		) -> DispatchResultWithPostInfo {
+			// Only the escrow of an appchain-native asset can be unlocked.
+			ensure!(!<TokenIdByAssetId<T>>::contains_key(asset_id), Error::<T>::NotNativeAsset);
+			let amount = Self::amount_from_near(<AssetDecimals<T>>::get(asset_id), amount)?;
+			<T::Assets as fungibles::Transfer<T::AccountId>>::transfer(
+				asset_id,
+				&Self::account_id(),
+				&receiver,
+				amount,
+				false,
+			)?;
+			Self::deposit_event(Event::AssetUnlocked(asset_id, sender_id, receiver, amount));
+
+			Ok(().into())
+		}
+
 		fn mint_asset_inner(
 			asset_id: AssetIdOf<T>,
 			sender_id: Vec<u8>,
@@ -951,6 +2454,24 @@ pub mod pallet {
 			})
 		}
 
+		/// Plan the validator set which has reached consensus, and credit its voters.
+		pub(crate) fn apply_validator_set(
+			hash: &T::Hash,
+			val_set: &ValidatorSet<T::AccountId>,
+			voters: &[T::AccountId],
+		) -> DispatchResultWithPostInfo {
+			let validators: Vec<(T::AccountId, u128)> = val_set
+				.validators
+				.iter()
+				.map(|v| (v.validator_id_in_appchain.clone(), v.total_stake))
+				.collect();
+			<PlannedValidators<T>>::put(validators.clone());
+			log!(debug, "new PlannedValidators: {:?}", validators);
+			Self::increase_next_set_id()?;
+			Self::record_observers(hash, voters);
+			Ok(().into())
+		}
+
 		fn increase_next_set_id() -> DispatchResultWithPostInfo {
 			NextSetId::<T>::try_mutate(|next_id| -> DispatchResultWithPostInfo {
 				if let Some(v) = next_id.checked_add(1) {
@@ -963,6 +2484,7 @@ pub mod pallet {
 		}
 
 		fn check_observation(
+			observation: &Observation<T::AccountId>,
 			observation_type: ObservationType,
 			obs_id: u32,
 		) -> DispatchResultWithPostInfo {
@@ -979,6 +2501,18 @@ pub mod pallet {
 						return Err(Error::<T>::WrongSetId.into());
 					}
 				}
+				ObservationType::UpdateAppchainState => {
+					let next_state_change_id = NextStateChangeId::<T>::get();
+					if obs_id != next_state_change_id {
+						log!(
+							warn,
+							"wrong state change id: {:?}, expected: {:?}",
+							obs_id,
+							next_state_change_id
+						);
+						return Err(Error::<T>::WrongStateChangeId.into());
+					}
+				}
 				_ => {
 					let next_notification_id = NextNotificationId::<T>::get();
 					let limit = T::RequestEventLimit::get();
@@ -995,11 +2529,12 @@ pub mod pallet {
 				}
 			}
 
-			// The maximum number of observation for the same obs_id is the number of validators (100),
-			// that is, each validator submits a observation.
+			// A new observation is rejected once the maximum number of distinct observations for
+			// the same obs_id is reached.
 			let obs = <Observations<T>>::try_get(observation_type, obs_id);
 			if let Ok(obs) = obs {
-				if obs.len() > 100 {
+				if obs.len() as u32 >= T::MaxObservationsPerId::get() && !obs.contains(observation)
+				{
 					log!(
 						warn,
 						"the number of observations with ({:?}, {:?}) exceeded the upper limit",
@@ -1013,76 +2548,145 @@ pub mod pallet {
 			Ok(().into())
 		}
 
-		fn prune_old_histories() {
-			// let next_notification_id = NextNotificationId::<T>::get();
-			// if next_notification_id <= T::NotificationHistoryDepth::get() {
-			// 	return;
-			// }
-
-			// let prune_index = next_notification_id - T::NotificationHistoryDepth::get();
-
-			// // prune observations
-			// let prune_obs = <Observations<T>>::iter_prefix(observation_type)
-			// 	.filter(|(index, _)| *index < prune_index)
-			// 	.collect::<Vec<(u32, Vec<Observation<T::AccountId>>)>>();
-
-			// log!(debug, "will delete old observations: {:#?}", prune_obs.clone());
-			// let _ = prune_obs
-			// 	.iter()
-			// 	.map(|(index, obs)| {
-			// 		for o in obs.iter() {
-			// 			<Observing<T>>::remove(o);
-			// 		}
-			// 		<Observations<T>>::remove(observation_type, index);
-			// 	})
-			// 	.collect::<Vec<_>>();
-
-			// // prune records
-			// // TODO: use simple code
-			// let prune_records = <ObservationRecords<T>>::iter_prefix(record_type)
-			// 	.filter(|(index, _)| *index <= prune_index)
-			// 	.collect::<Vec<(u32, ObservationRecord<T::AccountId>)>>();
-
-			// log!(debug, "will delete old records: {:#?}", prune_records.clone());
-			// let _ = prune_records
-			// 	.iter()
-			// 	.map(|(index, _)| {
-			// 		<ObservationRecords<T>>::remove(record_type, index);
-			// 	})
-			// 	.collect::<Vec<_>>();
+		/// Prune at most `limit` observation ids whose observations and histories are outdated.
+		///
+		/// The observations of notifications are kept for `ObservationHistoryDepth` indexes and
+		/// their records for `NotificationHistoryDepth` indexes, while the observations of a
+		/// validator set are useless as soon as the set has been applied.
+		pub(crate) fn prune_old_histories(limit: u32) -> Weight {
+			// NextNotificationId, NextSetId, NextPruneObservationId, NextPruneNotificationId and
+			// NextPruneSetId.
+			let mut reads: Weight = 5;
+			let mut writes: Weight = 0;
+			let mut pruned: u32 = 0;
+
+			let next_notification_id = NextNotificationId::<T>::get();
+			let prune_end = next_notification_id.saturating_sub(T::ObservationHistoryDepth::get());
+			let start = NextPruneObservationId::<T>::get();
+			let mut index = start;
+			while index < prune_end && pruned < limit {
+				for observation_type in [
+					ObservationType::Burn,
+					ObservationType::LockAsset,
+					ObservationType::BurnNativeAsset,
+					ObservationType::LockNft,
+					ObservationType::BurnNft,
+					ObservationType::GovernanceCall,
+				]
+				.iter()
+				{
+					let (r, w) = Self::prune_observations(*observation_type, index);
+					reads = reads.saturating_add(r);
+					writes = writes.saturating_add(w);
+				}
+				index += 1;
+				pruned += 1;
+			}
+			if index != start {
+				log!(debug, "pruned notification observations in [{:?}, {:?})", start, index);
+				NextPruneObservationId::<T>::put(index);
+				writes = writes.saturating_add(1);
+			}
+
+			let prune_end = next_notification_id.saturating_sub(T::NotificationHistoryDepth::get());
+			let start = NextPruneNotificationId::<T>::get();
+			let mut index = start;
+			while index < prune_end && pruned < limit {
+				let (r, w) = Self::remove_notification_record(index);
+				reads = reads.saturating_add(r);
+				writes = writes.saturating_add(w);
+				index += 1;
+				pruned += 1;
+			}
+			if index != start {
+				log!(debug, "pruned notification histories in [{:?}, {:?})", start, index);
+				NextPruneNotificationId::<T>::put(index);
+				writes = writes.saturating_add(1);
+			}
+
+			let next_set_id = NextSetId::<T>::get();
+			let start = NextPruneSetId::<T>::get();
+			let mut set_id = start;
+			while set_id < next_set_id && pruned < limit {
+				let (r, w) = Self::prune_observations(ObservationType::UpdateValidatorSet, set_id);
+				reads = reads.saturating_add(r);
+				writes = writes.saturating_add(w);
+				set_id += 1;
+				pruned += 1;
+			}
+			if set_id != start {
+				log!(debug, "pruned validator set observations in [{:?}, {:?})", start, set_id);
+				NextPruneSetId::<T>::put(set_id);
+				writes = writes.saturating_add(1);
+			}
+
+			T::DbWeight::get().reads_writes(reads, writes)
 		}
 
-		/// If the observation already exists in the Observations, then the only thing
-		/// to do is vote for this observation.
+		/// Remove the observations with the given id, their voters, the tallies of their stake
+		/// and the conflicting votes for them.
+		///
+		/// Returns the number of storage reads and writes.
+		pub(crate) fn prune_observations(
+			observation_type: ObservationType,
+			obs_id: u32,
+		) -> (Weight, Weight) {
+			let observations = <Observations<T>>::take(observation_type, obs_id);
+			let voters = observations.iter().fold(0 as Weight, |voters, o| {
+				voters.saturating_add(Self::remove_votes(&Self::observation_hash(o)).into())
+			});
+			let removed = |result: sp_io::KillStorageResult| match result {
+				sp_io::KillStorageResult::AllRemoved(n)
+				| sp_io::KillStorageResult::SomeRemaining(n) => n as Weight,
+			};
+			let time_slot = (observation_type, obs_id);
+			let votes = removed(<VotedObservations<T>>::remove_prefix(time_slot, None));
+			let conflicts = removed(<ConflictingObservations<T>>::remove_prefix(time_slot, None));
+			(
+				1 + observations.len() as Weight + votes + conflicts,
+				1 + 3 * observations.len() as Weight + voters + votes + conflicts,
+			)
+		}
+
+		/// Vote for the observation, and apply it once the voters hold more than 2/3 of the
+		/// active stake. If the observation already exists in the Observations, then the only
+		/// thing to do is vote for this observation.
+		///
+		/// Returns the number of voters whose stake has been tallied by this vote.
 		#[transactional]
-		fn submit_observation(
+		pub(crate) fn submit_observation(
 			validator_id: &T::AccountId,
 			observation: Observation<T::AccountId>,
-		) -> DispatchResultWithPostInfo {
+		) -> Result<u32, frame_support::dispatch::DispatchErrorWithPostInfo> {
 			let observation_type = Self::get_observation_type(&observation);
 			let obs_id = observation.observation_index();
-			Self::check_observation(observation_type, obs_id)?;
+			let hash = Self::observation_hash(&observation);
+			let time_slot = (observation_type, obs_id);
+			if let Some(tallied) = Self::credit_late_vote(&hash, time_slot, validator_id)? {
+				return Ok(tallied);
+			}
+			Self::check_observation(&observation, observation_type, obs_id)?;
 
+			if let Some(first) = Self::conflicting_observation(validator_id, &hash, time_slot) {
+				// The state of the anchor contract may change between two observations, the
+				// latest vote of the validator replaces its previous one.
+				if observation_type == ObservationType::UpdateAppchainState {
+					Self::remove_vote(&first, validator_id);
+				} else {
+					Self::report_conflicting_observation(validator_id, first, hash, time_slot);
+					return Ok(0);
+				}
+			}
+
+			let (stake, tallied) = Self::vote(&hash, time_slot, validator_id)?;
 			<Observations<T>>::mutate(observation_type, obs_id, |obs| {
 				let found = obs.iter().any(|o| o == &observation);
 				if !found {
 					obs.push(observation.clone())
 				}
 			});
-			<Observing<T>>::mutate(&observation, |vals| {
-				let found = vals.iter().any(|id| id == validator_id);
-				if !found {
-					vals.push(validator_id.clone());
-				} else {
-					log!(warn, "{:?} submits a duplicate ocw tx", validator_id);
-				}
-			});
 			let total_stake: u128 = T::LposInterface::active_total_stake()
 				.ok_or(Error::<T>::InvalidActiveTotalStake)?;
-			let stake: u128 = <Observing<T>>::get(&observation)
-				.iter()
-				.map(|v| T::LposInterface::active_stake_of(v))
-				.sum();
 
 			//
 			log!(debug, "observations type: {:#?}", observation_type);
@@ -1091,99 +2695,73 @@ pub mod pallet {
 				"️️️observations content: {:#?}",
 				<Observations<T>>::get(observation_type, obs_id)
 			);
-			log!(debug, "️️️observer: {:#?}", <Observing<T>>::get(&observation));
 			log!(debug, "️️️total_stake: {:?}, stake: {:?}", total_stake, stake);
 			//
 
-			if 3 * stake > 2 * total_stake {
+			if Self::reaches_consensus(stake, total_stake) {
+				let voters = Self::voters_of(&hash);
+				log!(debug, "️️️observer: {:#?}", voters);
 				match observation.clone() {
 					Observation::UpdateValidatorSet(val_set) => {
-						let validators: Vec<(T::AccountId, u128)> = val_set
-							.validators
-							.iter()
-							.map(|v| (v.validator_id_in_appchain.clone(), v.total_stake))
-							.collect();
-						<PlannedValidators<T>>::put(validators.clone());
-						log!(debug, "new PlannedValidators: {:?}", validators);
-						Self::increase_next_set_id()?;
-					}
-					Observation::Burn(event) => {
-						Self::increase_next_notification_id()?;
-						let mut result = NotificationResult::Success;
-						if let Err(error) = Self::unlock_inner(
-							event.sender_id.clone(),
-							event.receiver.clone(),
-							event.amount,
-						) {
-							log!(info, "️️️failed to unlock native token: {:?}", error);
-							let min = T::Currency::minimum_balance();
-							let amount_unwrapped = event.amount.checked_into().unwrap_or(min); //Check: should not return error.
-							Self::deposit_event(Event::UnlockFailed(
-								event.sender_id,
-								event.receiver,
-								amount_unwrapped,
-							));
-							result = NotificationResult::UnlockFailed;
+						// Applied once resumed, see `apply_paused_validator_set`.
+						if Self::ensure_not_paused(BridgeOperation::UpdateValidatorSet, None)
+							.is_err()
+						{
+							log!(info, "validator set updates are paused");
+							return Ok(tallied);
 						}
-						NotificationHistory::<T>::insert(obs_id, result.clone());
-						log!(
-							debug,
-							"save notification result {:?}:{:?} to NotificationHistory ",
-							obs_id,
-							result
-						);
+						Self::apply_validator_set(&hash, &val_set, &voters)?;
+					}
+					Observation::UpdateAppchainState(change) => {
+						// The observations of the change are removed with their consensus, so
+						// the observers are recorded first.
+						Self::record_observers(&hash, &voters);
+						Self::apply_state_change(&change)?;
 					}
-					Observation::LockAsset(event) => {
+					_ => {
 						Self::increase_next_notification_id()?;
-						let mut result = NotificationResult::Success;
-						if let Ok(asset_id) = <AssetIdByName<T>>::try_get(&event.token_id) {
-							log!(
-								info,
-								"️️️mint asset:{:?}, sender_id:{:?}, receiver:{:?}, amount:{:?}",
-								asset_id,
-								event.sender_id,
-								event.receiver,
-								event.amount,
-							);
-							if let Err(error) = Self::mint_asset_inner(
-								asset_id,
-								event.sender_id.clone(),
-								event.receiver.clone(),
-								event.amount,
-							) {
-								log!(warn, "️️️failed to mint asset: {:?}", error);
-								Self::deposit_event(Event::AssetMintFailed(
-									asset_id,
-									event.sender_id,
-									event.receiver,
-									event.amount,
-								));
-								result = NotificationResult::AssetMintFailed;
-							}
-						} else {
-							Self::deposit_event(Event::AssetIdGetFailed(
-								event.token_id,
-								event.sender_id,
-								event.receiver,
-								event.amount,
-							));
-							result = NotificationResult::AssetGetFailed;
-						}
-
-						NotificationHistory::<T>::insert(obs_id, result.clone());
-						log!(
-							debug,
-							"save notification result {:?}:{:?} to NotificationHistory ",
-							obs_id,
-							result
-						);
+						Self::record_observers(&hash, &voters);
+						let result = match Self::execute_notification(&observation) {
+							Ok(()) => NotificationResult::Success,
+							// The registry sends a failed call again rather than retrying it.
+							Err(result) if observation_type == ObservationType::GovernanceCall => {
+								result
+							},
+							Err(result) => {
+								<FailedNotifications<T>>::insert(obs_id, (observation.clone(), 0));
+								result
+							},
+						};
+						Self::record_notification(obs_id, &observation, result);
 					}
 				}
+			}
+
+			Ok(tallied)
+		}
 
-				Self::prune_old_histories();
+		/// Record the voters of an observation which reached consensus in the active era.
+		fn record_observers(hash: &T::Hash, voters: &[T::AccountId]) {
+			let era_index = match T::LposInterface::active_era_index() {
+				Some(era_index) => era_index,
+				None => return,
+			};
+			let now = <frame_system::Pallet<T>>::block_number();
+			<ConsensusReachedAt<T>>::insert(hash, (now, era_index));
+			<EraObservations<T>>::mutate(era_index, |count| *count = count.saturating_add(1));
+			for voter in voters {
+				<ObservationParticipants<T>>::mutate(era_index, voter, |count| {
+					*count = count.saturating_add(1)
+				});
 			}
+		}
 
-			Ok(().into())
+		/// Whether the stake of the voters of an observation reaches the consensus.
+		pub(crate) fn reaches_consensus(stake: u128, total_stake: u128) -> bool {
+			match <ConsensusThreshold<T>>::get() {
+				Some(threshold) => stake > threshold.mul_floor(total_stake),
+				None => 3 * stake > 2 * total_stake,
+			}
 		}
 
 		fn get_observation_type(observation: &Observation<T::AccountId>) -> ObservationType {
@@ -1197,6 +2775,21 @@ pub mod pallet {
 				Observation::LockAsset(_) => {
 					return ObservationType::LockAsset;
 				}
+				Observation::BurnNativeAsset(_) => {
+					return ObservationType::BurnNativeAsset;
+				}
+				Observation::LockNft(_) => {
+					return ObservationType::LockNft;
+				}
+				Observation::BurnNft(_) => {
+					return ObservationType::BurnNft;
+				}
+				Observation::GovernanceCall(_) => {
+					return ObservationType::GovernanceCall;
+				}
+				Observation::UpdateAppchainState(_) => {
+					return ObservationType::UpdateAppchainState;
+				}
 			}
 		}
 
@@ -1272,4 +2865,18 @@ pub mod pallet {
 			<PlannedValidators<T>>::get()
 		}
 	}
+
+	impl<T: Config> ObserversProvider<T::AccountId> for Pallet<T> {
+		fn observers(era_index: u32) -> (u32, Vec<(T::AccountId, u32)>) {
+			(
+				<EraObservations<T>>::get(era_index),
+				<ObservationParticipants<T>>::iter_prefix(era_index).collect(),
+			)
+		}
+
+		fn clear_observers(era_index: u32) {
+			<EraObservations<T>>::remove(era_index);
+			<ObservationParticipants<T>>::remove_prefix(era_index, None);
+		}
+	}
 }
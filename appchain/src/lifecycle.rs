@@ -0,0 +1,85 @@
+//! Lifecycle of the appchain, following the state of its anchor contract on NEAR.
+//!
+//! The offchain worker observes the state of the anchor contract and submits it when it differs
+//! from `AnchorAppchainState`, the state the validators last agreed on. Only an active appchain
+//! sends tokens to NEAR and pays era rewards, and the tokens received by a frozen or broken
+//! appchain wait in the retry queue, so freezing the appchain on NEAR halts the bridge once the
+//! validators agree on it.
+//!
+//! Governance may override the state of the appchain, until the state of the anchor contract
+//! changes.
+
+use super::*;
+
+/// The state of the appchain in its anchor contract.
+#[derive(Deserialize, Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize))]
+pub enum AppchainState {
+	/// The validators are starting the appchain.
+	Booting,
+	/// The appchain runs and communicates with NEAR.
+	Active,
+	/// The appchain is halted by the registry, e.g. during an incident.
+	Frozen,
+	/// The appchain has been found broken and will not be resumed.
+	Broken,
+}
+
+impl Default for AppchainState {
+	fn default() -> Self {
+		AppchainState::Booting
+	}
+}
+
+/// A state of the appchain observed on the anchor contract.
+///
+/// `index` is the value of `NextStateChangeId` when the state has been observed.
+#[derive(Deserialize, Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize))]
+pub struct AppchainStateChange {
+	pub index: u32,
+	pub state: AppchainState,
+}
+
+impl<T: Config> Pallet<T> {
+	/// Whether the appchain is active, and may communicate with NEAR.
+	pub(crate) fn is_active() -> bool {
+		<CurrentAppchainState<T>>::get() == AppchainState::Active
+	}
+
+	/// Whether the appchain is frozen or broken on NEAR, which halts the inbound transfers.
+	pub(crate) fn is_halted() -> bool {
+		matches!(<CurrentAppchainState<T>>::get(), AppchainState::Frozen | AppchainState::Broken)
+	}
+
+	/// The change to submit if the state observed on the anchor contract is a new one.
+	pub(crate) fn observed_state_change(observed: AppchainState) -> Option<AppchainStateChange> {
+		if observed == <AnchorAppchainState<T>>::get() {
+			return None;
+		}
+		Some(AppchainStateChange { index: <NextStateChangeId<T>>::get(), state: observed })
+	}
+
+	/// Apply a state of the appchain, observed or forced by governance.
+	pub(crate) fn set_appchain_state(state: AppchainState) {
+		let previous = <CurrentAppchainState<T>>::get();
+		if previous == state {
+			return;
+		}
+		<CurrentAppchainState<T>>::put(state);
+		log!(info, "appchain state changed from {:?} to {:?}", previous, state);
+		Self::deposit_event(Event::AppchainStateChanged(previous, state));
+	}
+
+	/// Apply a change of state which reached consensus, and remove its observations.
+	pub(crate) fn apply_state_change(change: &AppchainStateChange) -> DispatchResult {
+		<NextStateChangeId<T>>::try_mutate(|next_id| -> DispatchResult {
+			*next_id = next_id.checked_add(1).ok_or(Error::<T>::NextStateChangeIdOverflow)?;
+			Ok(())
+		})?;
+		Self::prune_observations(ObservationType::UpdateAppchainState, change.index);
+		<AnchorAppchainState<T>>::put(change.state);
+		Self::set_appchain_state(change.state);
+		Ok(())
+	}
+}
@@ -1,4 +1,5 @@
 use super::*;
+use sp_std::marker::PhantomData;
 
 #[derive(Deserialize, RuntimeDebug)]
 struct Response {
@@ -25,133 +26,398 @@ pub struct AppchainNotificationHistory<AccountId> {
 	index: u32,
 }
 
-impl<T: Config> Pallet<T> {
-	/// Gets a validator set by the specified era number.
-	/// Returns an empty list if the validator set has not been generated.
-	pub(super) fn get_validator_list_of(
-		rpc_endpoint: &str,
-		anchor_contract: Vec<u8>,
+/// Index range of the histories kept by the anchor contract, both ends included.
+#[derive(Deserialize, Clone, Copy, Default, PartialEq, Eq, RuntimeDebug)]
+pub struct IndexRange {
+	#[serde(deserialize_with = "deserialize_from_str")]
+	pub start_index: u32,
+	#[serde(deserialize_with = "deserialize_from_str")]
+	pub end_index: u32,
+}
+
+/// Status of the anchor contract of the appchain.
+#[derive(Deserialize, Clone, Default, PartialEq, Eq, RuntimeDebug)]
+pub struct AnchorStatus {
+	#[serde(rename = "index_range_of_validator_set_history")]
+	pub validator_set_history: IndexRange,
+	#[serde(rename = "index_range_of_appchain_notification_history")]
+	pub notification_history: IndexRange,
+}
+
+/// Access to the anchor contract of the appchain on the mainchain, from the offchain worker.
+pub trait MainchainClient<AccountId> {
+	/// Fetch the validator set of `set_id`.
+	/// Returns `None` if the validator set has not been generated.
+	fn validator_set(
+		mainchain_rpc_endpoints: &[String],
+		anchor_contract: &[u8],
 		set_id: u32,
-	) -> Result<Vec<Observation<<T as frame_system::Config>::AccountId>>, http::Error> {
-		// We want to keep the offchain worker execution time reasonable, so we set a hard-coded
-		// deadline to 2s to complete the external call.
-		// You can also wait idefinitely for the response, however you may still get a timeout
-		// coming from the host machine.
-		let deadline = sp_io::offchain::timestamp().add(Duration::from_millis(2_000));
-		// Initiate an external HTTP GET request.
-		// This is using high-level wrappers from `sp_runtime`, for the low-level calls that
-		// you can find in `sp_io`. The API is trying to be similar to `reqwest`, but
-		// since we are running in a custom WASM execution environment we can't simply
-		// import the library here.
-		let args = Self::encode_get_validator_args(set_id).ok_or_else(|| {
-			log!(warn, "Encode get_validator_list_of args error");
-			http::Error::Unknown
-		})?;
+	) -> Result<Option<ValidatorSet<AccountId>>, &'static str>;
 
-		let mut body = br#"
-		{
-			"jsonrpc": "2.0",
-			"id": "dontcare",
-			"method": "query",
-			"params": {
-				"request_type": "call_function",
-				"finality": "final",
-				"account_id": ""#
-			.to_vec();
-		body.extend(&anchor_contract);
-		body.extend(
-			br#"",
-				"method_name": "get_validator_list_of",
-				"args_base64": ""#,
-		);
-		body.extend(&args);
-		body.extend(
-			br#""
-			}
-		}"#,
-		);
-		let request = http::Request::default()
-			.method(http::Method::Post)
-			.url(rpc_endpoint)
-			.body(vec![body])
-			.add_header("Content-Type", "application/json");
-		// We set the deadline for sending of the request, note that awaiting response can
-		// have a separate deadline. Next we send the request, before that it's also possible
-		// to alter request headers or stream body content in case of non-GET requests.
-		let pending = request.deadline(deadline).send().map_err(|_| http::Error::IoError)?;
+	/// Fetch at most `limit` notifications, from the one of `start_index`.
+	fn notification_histories(
+		mainchain_rpc_endpoints: &[String],
+		anchor_contract: &[u8],
+		start_index: u32,
+		limit: u32,
+	) -> Result<Vec<Observation<AccountId>>, &'static str>;
 
-		// The request is already being processed by the host, we are free to do anything
-		// else in the worker (we can send multiple concurrent requests too).
-		// At some point however we probably want to check the response though,
-		// so we can block current thread and wait for it to finish.
-		// Note that since the request is being driven by the host, we don't have to wait
-		// for the request to have it complete, we will just not read the response.
-		let response = pending.try_wait(deadline).map_err(|_| http::Error::DeadlineReached)??;
-		// Let's check the status code before we proceed to reading the response.
-		if response.code != 200 {
-			log!(warn, "Unexpected status code: {}", response.code);
-			return Err(http::Error::Unknown);
-		}
+	/// Fetch the status of the anchor contract.
+	fn anchor_status(
+		mainchain_rpc_endpoints: &[String],
+		anchor_contract: &[u8],
+	) -> Result<AnchorStatus, &'static str>;
 
-		// Next we want to fully read the response body and collect it to a vector of bytes.
-		// Note that the return object allows you to read the body in chunks as well
-		// with a way to control the deadline.
-		let body = response.body().collect::<Vec<u8>>();
-		log!(debug, "body: {:?}", body);
+	/// Fetch the state of the appchain in the anchor contract.
+	/// Returns `None` if the state is unknown to the appchain.
+	fn appchain_state(
+		mainchain_rpc_endpoints: &[String],
+		anchor_contract: &[u8],
+	) -> Result<Option<AppchainState>, &'static str>;
 
-		let json_response: Response = serde_json::from_slice(&body).map_err(|_| {
-			log::warn!("Failed to decode http body");
-			http::Error::Unknown
-		})?;
-		log!(debug, "{:?}", json_response);
+	/// Fetch the state of the appchain, and the observations to submit if it is active: the
+	/// validator set of `set_id` if it has been generated, the notifications from `start_index`
+	/// otherwise.
+	fn observations(
+		mainchain_rpc_endpoints: &[String],
+		anchor_contract: &[u8],
+		set_id: u32,
+		start_index: u32,
+		limit: u32,
+	) -> Result<(Option<AppchainState>, Vec<Observation<AccountId>>), &'static str> {
+		let state = Self::appchain_state(mainchain_rpc_endpoints, anchor_contract)?;
+		if state != Some(AppchainState::Active) {
+			return Ok((state, Vec::new()));
+		}
+		let observations =
+			match Self::validator_set(mainchain_rpc_endpoints, anchor_contract, set_id)? {
+				Some(val_set) => vec![Observation::UpdateValidatorSet(val_set)],
+				None => Self::notification_histories(
+					mainchain_rpc_endpoints,
+					anchor_contract,
+					start_index,
+					limit,
+				)?,
+			};
+		Ok((state, observations))
+	}
+}
 
-		let mut obs: Vec<Observation<<T as frame_system::Config>::AccountId>> = vec![];
-		let validators: Vec<Validator<<T as frame_system::Config>::AccountId>> =
-			serde_json::from_slice(&json_response.result.result).map_err(|_| {
-				log!(warn, "Failed to decode validators");
-				http::Error::Unknown
-			})?;
-		if validators.len() > 0 {
-			let val_set = ValidatorSet { set_id, validators };
-			obs.push(Observation::UpdateValidatorSet(val_set));
+/// The queries of the anchor contract made by the offchain worker.
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+enum Query {
+	ValidatorSet,
+	NotificationHistories,
+	AnchorStatus,
+	AppchainState,
+}
+
+impl Query {
+	fn method_name(&self) -> &'static [u8] {
+		match self {
+			Query::ValidatorSet => b"get_validator_list_of",
+			Query::NotificationHistories => b"get_appchain_notification_histories",
+			Query::AnchorStatus => b"get_anchor_status",
+			Query::AppchainState => b"get_appchain_state",
 		}
+	}
+}
 
-		log!(debug, "Got observations: {:?}", obs);
+/// Client of an anchor contract deployed on NEAR, through its JSON-RPC `call_function` query.
+///
+/// The endpoints are tried from the healthiest one, and their health is recorded.
+pub struct NearMainchainClient<T>(PhantomData<T>);
 
-		Ok(obs)
+impl<T: Config> MainchainClient<<T as frame_system::Config>::AccountId> for NearMainchainClient<T> {
+	fn validator_set(
+		mainchain_rpc_endpoints: &[String],
+		anchor_contract: &[u8],
+		set_id: u32,
+	) -> Result<Option<ValidatorSet<<T as frame_system::Config>::AccountId>>, &'static str> {
+		let args = Self::encode_get_validator_args(set_id)
+			.ok_or("Encode get_validator_list_of args error")?;
+		Self::query_with_failover(mainchain_rpc_endpoints, Query::ValidatorSet, |endpoint| {
+			let result =
+				Self::call_function(endpoint, anchor_contract, Query::ValidatorSet, &args)?;
+			Self::parse_validator_set(set_id, &result)
+		})
+		.ok_or("Failed to get_validator_list_of")
 	}
 
-	pub(crate) fn encode_get_validator_args(era: u32) -> Option<Vec<u8>> {
-		let a = String::from("{\"era_number\":\"");
-		let era = era.to_string();
-		let b = String::from("\"}");
-		let json = a + &era + &b;
-		let res = base64::encode(json).into_bytes();
-		Some(res)
+	fn notification_histories(
+		mainchain_rpc_endpoints: &[String],
+		anchor_contract: &[u8],
+		start_index: u32,
+		limit: u32,
+	) -> Result<Vec<Observation<<T as frame_system::Config>::AccountId>>, &'static str> {
+		let args = Self::encode_get_notification_args(start_index, limit)
+			.ok_or("Encode get_appchain_notification_histories args error")?;
+		Self::query_with_failover(
+			mainchain_rpc_endpoints,
+			Query::NotificationHistories,
+			|endpoint| {
+				let result = Self::call_function(
+					endpoint,
+					anchor_contract,
+					Query::NotificationHistories,
+					&args,
+				)?;
+				Self::parse_notification_histories(&result)
+			},
+		)
+		.ok_or("Failed to get_appchain_notification_histories")
 	}
 
-	/// Fetch the notifications from anchor contract.
-	pub(super) fn get_appchain_notification_histories(
-		rpc_endpoint: &str,
-		anchor_contract: Vec<u8>,
+	fn anchor_status(
+		mainchain_rpc_endpoints: &[String],
+		anchor_contract: &[u8],
+	) -> Result<AnchorStatus, &'static str> {
+		let args = base64::encode("{}").into_bytes();
+		Self::query_with_failover(mainchain_rpc_endpoints, Query::AnchorStatus, |endpoint| {
+			let result =
+				Self::call_function(endpoint, anchor_contract, Query::AnchorStatus, &args)?;
+			serde_json::from_slice(&result).map_err(|_| {
+				log!(warn, "Failed to decode anchor status");
+				http::Error::Unknown
+			})
+		})
+		.ok_or("Failed to get_anchor_status")
+	}
+
+	fn appchain_state(
+		mainchain_rpc_endpoints: &[String],
+		anchor_contract: &[u8],
+	) -> Result<Option<AppchainState>, &'static str> {
+		let args = base64::encode("{}").into_bytes();
+		Self::query_with_failover(mainchain_rpc_endpoints, Query::AppchainState, |endpoint| {
+			let result =
+				Self::call_function(endpoint, anchor_contract, Query::AppchainState, &args)?;
+			Self::parse_appchain_state(&result)
+		})
+		.ok_or("Failed to get_appchain_state")
+	}
+
+	fn observations(
+		mainchain_rpc_endpoints: &[String],
+		anchor_contract: &[u8],
+		set_id: u32,
+		start_index: u32,
+		limit: u32,
+	) -> Result<
+		(Option<AppchainState>, Vec<Observation<<T as frame_system::Config>::AccountId>>),
+		&'static str,
+	> {
+		Self::get_observations(mainchain_rpc_endpoints, anchor_contract, set_id, start_index, limit)
+	}
+}
+
+/// Offchain storage key of the id of the last validator set fetched by this node, which stays
+/// pending until the validators agree on it.
+const PENDING_VALIDATOR_SET_KEY: &[u8] = b"octopus_appchain::pending_validator_set";
+
+impl<T: Config> NearMainchainClient<T> {
+	/// Gets the state of the appchain and the observations from the anchor contract.
+	///
+	/// The state of the appchain, the validator set of `set_id` and the notification histories
+	/// from `index` are queried concurrently. The endpoints are tried from the healthiest one,
+	/// and the queries which failed on an endpoint are retried together on the next one. The
+	/// notifications are not queried while the validator set fetched by the previous run is
+	/// still pending.
+	///
+	/// Returns the state of the appchain, with the validator set if it has been generated, the
+	/// notifications otherwise. No observation is returned if the appchain is not active.
+	fn get_observations(
+		mainchain_rpc_endpoints: &[String],
+		anchor_contract: &[u8],
+		set_id: u32,
 		index: u32,
 		limit: u32,
-	) -> Result<Vec<Observation<<T as frame_system::Config>::AccountId>>, http::Error> {
+	) -> Result<
+		(Option<AppchainState>, Vec<Observation<<T as frame_system::Config>::AccountId>>),
+		&'static str,
+	> {
+		let state_args = base64::encode("{}").into_bytes();
+		let validator_args = Self::encode_get_validator_args(set_id)
+			.ok_or("Encode get_validator_list_of args error")?;
+		let notification_args = Self::encode_get_notification_args(index, limit)
+			.ok_or("Encode get_appchain_notification_histories args error")?;
+
+		let mut state: Option<Option<AppchainState>> = None;
+		let mut validator_set: Option<Vec<Observation<<T as frame_system::Config>::AccountId>>> =
+			None;
+		let mut notifications: Option<Vec<Observation<<T as frame_system::Config>::AccountId>>> =
+			None;
+		let pending_set = StorageValueRef::persistent(PENDING_VALIDATOR_SET_KEY);
+		let set_pending = pending_set.get::<u32>().ok().flatten() == Some(set_id);
+
+		for endpoint in Pallet::<T>::sort_endpoints_by_health(mainchain_rpc_endpoints).iter() {
+			let notifications_needed = match validator_set {
+				Some(ref obs) => obs.is_empty(),
+				None => !set_pending,
+			};
+			if state.is_some()
+				&& validator_set.is_some()
+				&& (!notifications_needed || notifications.is_some())
+			{
+				break;
+			}
+
+			// We want to keep the offchain worker execution time reasonable, so we set a
+			// hard-coded deadline to 2s to complete all the external calls to an endpoint.
+			let start = sp_io::offchain::timestamp();
+			let deadline = start.add(Duration::from_millis(2_000));
+
+			let mut queries = vec![];
+			if state.is_none() {
+				queries.push((Query::AppchainState, &state_args));
+			}
+			if validator_set.is_none() {
+				queries.push((Query::ValidatorSet, &validator_args));
+			}
+			if notifications_needed && notifications.is_none() {
+				queries.push((Query::NotificationHistories, &notification_args));
+			}
+
+			// All the requests are being processed by the host at the same time, then we wait
+			// for them to finish.
+			let mut sent = vec![];
+			let mut pending = vec![];
+			let mut failed = false;
+			for (query, args) in queries.into_iter() {
+				match Self::send_call_function(endpoint, anchor_contract, query, args, deadline) {
+					Ok(request) => {
+						sent.push(query);
+						pending.push(request);
+					},
+					Err(e) => {
+						log!(debug, "Failed to send {:?} to {:?}: {:?}", query, endpoint, e);
+						failed = true;
+					},
+				}
+			}
+			let responses = http::PendingRequest::try_wait_all(pending, deadline);
+
+			for (query, response) in sent.into_iter().zip(responses.into_iter()) {
+				let result = response
+					.map_err(|_| http::Error::DeadlineReached)
+					.and_then(|response| response)
+					.and_then(Self::read_call_function_result)
+					.and_then(|result| match query {
+						Query::AppchainState => Self::parse_appchain_state(&result)
+							.map(|appchain_state| state = Some(appchain_state)),
+						Query::ValidatorSet => {
+							Self::parse_validator_set(set_id, &result).map(|val_set| {
+								validator_set = Some(
+									val_set
+										.map(Observation::UpdateValidatorSet)
+										.into_iter()
+										.collect(),
+								)
+							})
+						},
+						_ => Self::parse_notification_histories(&result)
+							.map(|obs| notifications = Some(obs)),
+					});
+				if let Err(e) = result {
+					log!(debug, "Failed to query {:?} from {:?}: {:?}", query, endpoint, e);
+					failed = true;
+				}
+			}
+
+			let now = sp_io::offchain::timestamp().unix_millis();
+			if failed {
+				Pallet::<T>::record_endpoint_failure(endpoint, now);
+			} else {
+				Pallet::<T>::record_endpoint_success(
+					endpoint,
+					now.saturating_sub(start.unix_millis()),
+				);
+			}
+		}
+
+		let state = state.ok_or("Failed to get_appchain_state")?;
+		if state != Some(AppchainState::Active) {
+			return Ok((state, Vec::new()));
+		}
+		let validator_set = validator_set.ok_or("Failed to get_validator_list_of")?;
+		// check cross-chain transfers only if there isn't a validator_set update.
+		if !validator_set.is_empty() {
+			pending_set.set(&set_id);
+			return Ok((state, validator_set));
+		}
+		if set_pending {
+			pending_set.clear();
+		}
+		log!(debug, "No validat_set updates, try to get appchain notifications.");
+		let notifications = notifications.ok_or("Failed to get_appchain_notification_histories")?;
+		Ok((state, notifications))
+	}
+
+	/// Run the query against the endpoints from the healthiest one, until one of them succeeds.
+	///
+	/// The outcome and the latency of each attempt are recorded in the health statistics.
+	fn query_with_failover<R>(
+		mainchain_rpc_endpoints: &[String],
+		query: Query,
+		request: impl Fn(&str) -> Result<R, http::Error>,
+	) -> Option<R> {
+		for endpoint in Pallet::<T>::sort_endpoints_by_health(mainchain_rpc_endpoints).iter() {
+			let start = sp_io::offchain::timestamp().unix_millis();
+			let result = request(endpoint);
+			let now = sp_io::offchain::timestamp().unix_millis();
+			match result {
+				Ok(result) => {
+					Pallet::<T>::record_endpoint_success(endpoint, now.saturating_sub(start));
+					return Some(result);
+				},
+				Err(e) => {
+					log!(debug, "Failed to query {:?} from {:?}: {:?}", query, endpoint, e);
+					Pallet::<T>::record_endpoint_failure(endpoint, now);
+				},
+			}
+		}
+		None
+	}
+
+	/// Call a view function of the anchor contract and wait for its result.
+	fn call_function(
+		rpc_endpoint: &str,
+		anchor_contract: &[u8],
+		query: Query,
+		args: &[u8],
+	) -> Result<Vec<u8>, http::Error> {
 		// We want to keep the offchain worker execution time reasonable, so we set a hard-coded
 		// deadline to 2s to complete the external call.
 		// You can also wait idefinitely for the response, however you may still get a timeout
 		// coming from the host machine.
 		let deadline = sp_io::offchain::timestamp().add(Duration::from_millis(2_000));
-		// Initiate an external HTTP GET request.
+		let pending =
+			Self::send_call_function(rpc_endpoint, anchor_contract, query, args, deadline)?;
+
+		// The request is already being processed by the host, we are free to do anything
+		// else in the worker (we can send multiple concurrent requests too).
+		// At some point however we probably want to check the response though,
+		// so we can block current thread and wait for it to finish.
+		// Note that since the request is being driven by the host, we don't have to wait
+		// for the request to have it complete, we will just not read the response.
+		let response = pending.try_wait(deadline).map_err(|_| http::Error::DeadlineReached)??;
+		Self::read_call_function_result(response)
+	}
+
+	/// Send the request calling a view function of the anchor contract, without waiting for
+	/// its response.
+	fn send_call_function(
+		rpc_endpoint: &str,
+		anchor_contract: &[u8],
+		query: Query,
+		args: &[u8],
+		deadline: Timestamp,
+	) -> Result<http::PendingRequest, http::Error> {
+		// Initiate an external HTTP POST request.
 		// This is using high-level wrappers from `sp_runtime`, for the low-level calls that
 		// you can find in `sp_io`. The API is trying to be similar to `reqwest`, but
 		// since we are running in a custom WASM execution environment we can't simply
 		// import the library here.
-		let args = Self::encode_get_notification_args(index, limit).ok_or_else(|| {
-			log!(info, "Encode get_appchain_notification_histories args error");
-			http::Error::Unknown
-		})?;
-
 		let mut body = br#"
 		{
 			"jsonrpc": "2.0",
@@ -162,18 +428,30 @@ impl<T: Config> Pallet<T> {
 				"finality": "final",
 				"account_id": ""#
 			.to_vec();
-		body.extend(&anchor_contract);
+		body.extend(anchor_contract);
+		body.extend(
+			br#"",
+				"method_name": ""#,
+		);
+		body.extend(query.method_name());
 		body.extend(
 			br#"",
-				"method_name": "get_appchain_notification_histories",
 				"args_base64": ""#,
 		);
-		body.extend(&args);
+		body.extend(args);
 		body.extend(
 			br#""
 			}
 		}"#,
 		);
+		Self::send_request(rpc_endpoint, body, deadline)
+	}
+
+	fn send_request(
+		rpc_endpoint: &str,
+		body: Vec<u8>,
+		deadline: Timestamp,
+	) -> Result<http::PendingRequest, http::Error> {
 		let request = http::Request::default()
 			.method(http::Method::Post)
 			.url(rpc_endpoint)
@@ -182,15 +460,11 @@ impl<T: Config> Pallet<T> {
 		// We set the deadline for sending of the request, note that awaiting response can
 		// have a separate deadline. Next we send the request, before that it's also possible
 		// to alter request headers or stream body content in case of non-GET requests.
-		let pending = request.deadline(deadline).send().map_err(|_| http::Error::IoError)?;
+		request.deadline(deadline).send().map_err(|_| http::Error::IoError)
+	}
 
-		// The request is already being processed by the host, we are free to do anything
-		// else in the worker (we can send multiple concurrent requests too).
-		// At some point however we probably want to check the response though,
-		// so we can block current thread and wait for it to finish.
-		// Note that since the request is being driven by the host, we don't have to wait
-		// for the request to have it complete, we will just not read the response.
-		let response = pending.try_wait(deadline).map_err(|_| http::Error::DeadlineReached)??;
+	/// Read the result of a view function call from the response.
+	fn read_call_function_result(response: http::Response) -> Result<Vec<u8>, http::Error> {
 		// Let's check the status code before we proceed to reading the response.
 		if response.code != 200 {
 			log!(warn, "Unexpected status code: {}", response.code);
@@ -209,10 +483,60 @@ impl<T: Config> Pallet<T> {
 		})?;
 		log!(debug, "{:?}", json_response);
 
+		Ok(json_response.result.result)
+	}
+
+	/// Parse the state of the appchain in the anchor contract.
+	///
+	/// The states before booting are the booting one for the appchain, and a dead appchain is a
+	/// broken one. Any other state is unknown, and not a failure of the endpoint.
+	pub(crate) fn parse_appchain_state(
+		result: &[u8],
+	) -> Result<Option<AppchainState>, http::Error> {
+		let state: String = serde_json::from_slice(result).map_err(|_| {
+			log!(warn, "Failed to decode appchain state");
+			http::Error::Unknown
+		})?;
+		log!(debug, "Got appchain state: {:?}", state);
+
+		Ok(match state.as_str() {
+			"Registered" | "Auditing" | "InQueue" | "Staging" | "Booting" => {
+				Some(AppchainState::Booting)
+			},
+			"Active" => Some(AppchainState::Active),
+			"Frozen" => Some(AppchainState::Frozen),
+			"Broken" | "Dead" => Some(AppchainState::Broken),
+			_ => {
+				log!(warn, "Unknown appchain state: {:?}", state);
+				None
+			},
+		})
+	}
+
+	fn parse_validator_set(
+		set_id: u32,
+		result: &[u8],
+	) -> Result<Option<ValidatorSet<<T as frame_system::Config>::AccountId>>, http::Error> {
+		let validators: Vec<Validator<<T as frame_system::Config>::AccountId>> =
+			serde_json::from_slice(result).map_err(|_| {
+				log!(warn, "Failed to decode validators");
+				http::Error::Unknown
+			})?;
+		log!(debug, "Got validators: {:?}", validators);
+
+		if validators.is_empty() {
+			return Ok(None);
+		}
+		Ok(Some(ValidatorSet { set_id, validators }))
+	}
+
+	fn parse_notification_histories(
+		result: &[u8],
+	) -> Result<Vec<Observation<<T as frame_system::Config>::AccountId>>, http::Error> {
 		let mut obs: Vec<Observation<<T as frame_system::Config>::AccountId>> = vec![];
 		let notifications: Vec<
 			AppchainNotificationHistory<<T as frame_system::Config>::AccountId>,
-		> = serde_json::from_slice(&json_response.result.result).map_err(|_| {
+		> = serde_json::from_slice(result).map_err(|_| {
 			log!(warn, "Failed to decode appchain notification histories");
 			http::Error::Unknown
 		})?;
@@ -223,11 +547,27 @@ impl<T: Config> Pallet<T> {
 				AppchainNotification::Burn(mut event) => {
 					event.index = n.index;
 					obs.push(Observation::Burn(event));
-				}
+				},
 				AppchainNotification::LockAsset(mut event) => {
 					event.index = n.index;
 					obs.push(Observation::LockAsset(event));
-				}
+				},
+				AppchainNotification::BurnNativeAsset(mut event) => {
+					event.index = n.index;
+					obs.push(Observation::BurnNativeAsset(event));
+				},
+				AppchainNotification::LockNft(mut event) => {
+					event.index = n.index;
+					obs.push(Observation::LockNft(event));
+				},
+				AppchainNotification::BurnNft(mut event) => {
+					event.index = n.index;
+					obs.push(Observation::BurnNft(event));
+				},
+				AppchainNotification::GovernanceCall(mut event) => {
+					event.index = n.index;
+					obs.push(Observation::GovernanceCall(event));
+				},
 			}
 		}
 
@@ -236,6 +576,15 @@ impl<T: Config> Pallet<T> {
 		Ok(obs)
 	}
 
+	pub(crate) fn encode_get_validator_args(era: u32) -> Option<Vec<u8>> {
+		let a = String::from("{\"era_number\":\"");
+		let era = era.to_string();
+		let b = String::from("\"}");
+		let json = a + &era + &b;
+		let res = base64::encode(json).into_bytes();
+		Some(res)
+	}
+
 	pub(crate) fn encode_get_notification_args(start: u32, limit: u32) -> Option<Vec<u8>> {
 		let a = String::from("{\"start_index\":\"");
 		let start_index = start.to_string();
@@ -0,0 +1,233 @@
+//! Storage migrations for the octopus appchain pallet.
+
+use super::*;
+
+/// Migrate the storage of this pallet to the current storage version.
+pub fn migrate<T: Config>() -> Weight {
+	let on_chain_version = Pallet::<T>::on_chain_storage_version();
+	let mut weight: Weight = T::DbWeight::get().reads(1);
+
+	if on_chain_version < 1 {
+		weight = weight.saturating_add(v1::migrate::<T>());
+		StorageVersion::new(1).put::<Pallet<T>>();
+		weight = weight.saturating_add(T::DbWeight::get().writes(1));
+	}
+
+	if on_chain_version < 2 {
+		weight = weight.saturating_add(v2::migrate::<T>());
+		StorageVersion::new(2).put::<Pallet<T>>();
+		weight = weight.saturating_add(T::DbWeight::get().writes(1));
+	}
+
+	if on_chain_version < 3 {
+		weight = weight.saturating_add(v3::migrate::<T>());
+		StorageVersion::new(3).put::<Pallet<T>>();
+		weight = weight.saturating_add(T::DbWeight::get().writes(1));
+	}
+
+	if on_chain_version < 4 {
+		weight = weight.saturating_add(v4::migrate::<T>());
+		StorageVersion::new(4).put::<Pallet<T>>();
+		weight = weight.saturating_add(T::DbWeight::get().writes(1));
+	}
+
+	if on_chain_version < 5 {
+		weight = weight.saturating_add(v5::migrate::<T>());
+		StorageVersion::new(5).put::<Pallet<T>>();
+		weight = weight.saturating_add(T::DbWeight::get().writes(1));
+	}
+
+	weight
+}
+
+/// Version 1 introduces the pruning of observations and notification histories.
+pub mod v1 {
+	use super::*;
+
+	/// Initialize the pruning cursors at the start of the backlog accumulated before pruning
+	/// was introduced.
+	///
+	/// The backlog is not removed here, `prune_old_histories` drains it at most
+	/// `MaxPrunedPerBlock` ids at a time.
+	pub fn migrate<T: Config>() -> Weight {
+		NextPruneObservationId::<T>::put(0);
+		NextPruneNotificationId::<T>::put(0);
+		NextPruneSetId::<T>::put(0);
+
+		log!(info, "migrated to v1: pruning starts from the first observation ids");
+
+		T::DbWeight::get().writes(3)
+	}
+}
+
+/// Version 2 introduces the reverse index of the bridged assets.
+pub mod v2 {
+	use super::*;
+
+	/// Build `TokenIdByAssetId` from the assets registered at genesis.
+	pub fn migrate<T: Config>() -> Weight {
+		let mut count: Weight = 0;
+		for (token_id, asset_id) in <AssetIdByName<T>>::iter() {
+			<TokenIdByAssetId<T>>::insert(asset_id, token_id);
+			count = count.saturating_add(1);
+		}
+
+		log!(info, "migrated to v2: indexed {:?} assets", count);
+
+		T::DbWeight::get().reads_writes(count, count)
+	}
+}
+
+/// Version 3 stores the records of the processed notifications, indexed by their receivers.
+pub mod v3 {
+	use super::*;
+	use frame_support::{
+		storage::{migration::get_storage_value, unhashed},
+		traits::PalletInfoAccess,
+		StorageHasher, Twox64Concat,
+	};
+
+	/// The number of voters of an observation, in the `Observing` of the versions before 5.
+	fn voters_before_v5<T: Config>(observation: &Observation<T::AccountId>) -> usize {
+		let pallet_name = <Pallet<T> as PalletInfoAccess>::name();
+		get_storage_value::<Vec<T::AccountId>>(
+			pallet_name.as_bytes(),
+			b"Observing",
+			&Twox64Concat::hash(&observation.encode()),
+		)
+		.map_or(0, |voters| voters.len())
+	}
+
+	/// Turn the results of the notifications kept by `NotificationHistoryDepth` into records.
+	///
+	/// The older results are left to `prune_old_histories`, which removes them although they can
+	/// not be decoded as records. The transfer of a notification is taken from the retry queue, or from its
+	/// observation with the most voters, and is unknown if its observations have been pruned.
+	/// The block of a migrated record is unknown.
+	pub fn migrate<T: Config>() -> Weight {
+		let next_notification_id = NextNotificationId::<T>::get();
+		let start = NextPruneNotificationId::<T>::get()
+			.max(next_notification_id.saturating_sub(T::NotificationHistoryDepth::get()));
+		let mut reads: Weight = 2;
+		let mut writes: Weight = 0;
+		let mut count: u32 = 0;
+
+		for index in start..next_notification_id {
+			reads = reads.saturating_add(1);
+			let result = match unhashed::get::<NotificationResult>(
+				&<NotificationHistory<T>>::hashed_key_for(index),
+			) {
+				Some(result) => result,
+				None => continue,
+			};
+
+			reads = reads.saturating_add(3);
+			let observation = <FailedNotifications<T>>::get(index)
+				.map(|(observation, _)| observation)
+				.or_else(|| {
+					[ObservationType::Burn, ObservationType::LockAsset]
+						.iter()
+						.flat_map(|observation_type| {
+							<Observations<T>>::get(observation_type, index)
+						})
+						.max_by_key(voters_before_v5::<T>)
+				});
+			let record = NotificationRecord {
+				transfer: observation.as_ref().and_then(NotificationTransfer::new),
+				block_number: None,
+				result,
+			};
+
+			// The notifications are indexed from the oldest one, so the index of a receiver
+			// keeps the latest ones.
+			if let Some(receiver) = record.receiver() {
+				Pallet::<T>::index_notification(receiver, index);
+				reads = reads.saturating_add(1);
+				writes = writes.saturating_add(1);
+			}
+			<NotificationHistory<T>>::insert(index, record);
+			writes = writes.saturating_add(1);
+			count = count.saturating_add(1);
+		}
+
+		log!(info, "migrated to v3: recorded {:?} notifications from {:?}", count, start);
+
+		T::DbWeight::get().reads_writes(reads, writes)
+	}
+}
+
+/// Version 4 replaces the activation flag with the state of the appchain.
+pub mod v4 {
+	use super::*;
+	use frame_support::{storage::migration::take_storage_value, traits::PalletInfoAccess};
+
+	/// Turn `IsActivated` into `CurrentAppchainState` and `AnchorAppchainState`: an activated
+	/// appchain is active, the other one is booting.
+	pub fn migrate<T: Config>() -> Weight {
+		let pallet_name = <Pallet<T> as PalletInfoAccess>::name();
+		let is_activated = take_storage_value::<bool>(pallet_name.as_bytes(), b"IsActivated", &[])
+			.unwrap_or_default();
+		let state = if is_activated { AppchainState::Active } else { AppchainState::Booting };
+		<CurrentAppchainState<T>>::put(state);
+		<AnchorAppchainState<T>>::put(state);
+
+		log!(info, "migrated to v4: appchain state {:?}", state);
+
+		T::DbWeight::get().reads_writes(1, 3)
+	}
+}
+
+/// Version 5 keys the voters of the observations by the hash of the observations, and tallies
+/// their stake.
+pub mod v5 {
+	use super::*;
+	use frame_support::{
+		storage::migration::{remove_storage_prefix, take_storage_item},
+		traits::PalletInfoAccess,
+		Twox64Concat,
+	};
+
+	/// Move `Observing` from the lists of voters keyed by the encoded observations to a voter
+	/// per key under the hashes of the observations, and fill `ObservationTallies` and
+	/// `VotedObservations`.
+	///
+	/// Only the voters of the observations still accepted by `check_observation` are moved, the
+	/// other ones are dropped. The stake of the voters is tallied on the next vote.
+	pub fn migrate<T: Config>() -> Weight {
+		let pallet_name = <Pallet<T> as PalletInfoAccess>::name();
+		let keys = Pallet::<T>::pending_observation_keys();
+		let mut reads: Weight = keys.len() as Weight;
+		let mut writes: Weight = 0;
+
+		let mut entries = Vec::new();
+		for (observation_type, observation_id) in keys {
+			for observation in <Observations<T>>::get(observation_type, observation_id) {
+				reads = reads.saturating_add(1);
+				if let Some(voters) = take_storage_item::<_, Vec<T::AccountId>, Twox64Concat>(
+					pallet_name.as_bytes(),
+					b"Observing",
+					&observation,
+				) {
+					let hash = Pallet::<T>::observation_hash(&observation);
+					entries.push(((observation_type, observation_id), hash, voters));
+				}
+			}
+		}
+		// The old entries are all removed before inserting the new ones under the same prefix.
+		remove_storage_prefix(pallet_name.as_bytes(), b"Observing", &[]);
+		writes = writes.saturating_add(1);
+
+		for (time_slot, hash, voters) in entries.iter() {
+			for voter in voters {
+				<Observing<T>>::insert(hash, voter, ());
+				<VotedObservations<T>>::insert(time_slot, voter, hash);
+			}
+			<ObservationTallies<T>>::insert(hash, ObservationTally::untallied(voters.len() as u32));
+			writes = writes.saturating_add(1 + 2 * voters.len() as Weight);
+		}
+
+		log!(info, "migrated to v5: keyed {:?} observations by hash", entries.len());
+
+		T::DbWeight::get().reads_writes(reads, writes)
+	}
+}
@@ -14,12 +14,14 @@ pub use frame_support::{
 	construct_runtime,
 	pallet_prelude::GenesisBuild,
 	parameter_types,
-	traits::{Hooks, KeyOwnerProofSystem, OnFinalize, OnInitialize, Randomness, StorageInfo},
+	traits::{
+		Contains, Hooks, KeyOwnerProofSystem, OnFinalize, OnInitialize, Randomness, StorageInfo,
+	},
 	weights::{IdentityFee, Weight},
 	PalletId, StorageValue,
 };
 
-use frame_system::EnsureRoot;
+use frame_system::{EnsureOneOf, EnsureRoot};
 
 pub(crate) type BlockNumber = u32;
 pub type Signature = MultiSignature;
@@ -190,6 +192,32 @@ impl pallet_assets::Config for Test {
 	type WeightInfo = pallet_assets::weights::SubstrateWeight<Test>;
 }
 
+parameter_types! {
+	pub const ClassDeposit: Balance = 100 * DOLLARS;
+	pub const InstanceDeposit: Balance = 1 * DOLLARS;
+	pub const AttributeDepositBase: Balance = 10 * DOLLARS;
+	pub const DepositPerByte: Balance = 1 * DOLLARS;
+	pub const KeyLimit: u32 = 32;
+	pub const ValueLimit: u32 = 256;
+}
+
+impl pallet_uniques::Config for Test {
+	type Event = Event;
+	type ClassId = u128;
+	type InstanceId = u128;
+	type Currency = Balances;
+	type ForceOrigin = EnsureRoot<AccountId>;
+	type ClassDeposit = ClassDeposit;
+	type InstanceDeposit = InstanceDeposit;
+	type MetadataDepositBase = MetadataDepositBase;
+	type AttributeDepositBase = AttributeDepositBase;
+	type DepositPerByte = DepositPerByte;
+	type StringLimit = StringLimit;
+	type KeyLimit = KeyLimit;
+	type ValueLimit = ValueLimit;
+	type WeightInfo = pallet_uniques::weights::SubstrateWeight<Test>;
+}
+
 pub struct OctopusAppCrypto;
 
 impl frame_system::offchain::AppCrypto<<Signature as Verify>::Signer, Signature>
@@ -204,6 +232,7 @@ parameter_types! {
 	pub const SessionsPerEra: sp_staking::SessionIndex = 6;
 	pub const BondingDuration: pallet_octopus_lpos::EraIndex = 24 * 28;
 	pub const BlocksPerEra: u32 = EPOCH_DURATION_IN_BLOCKS * 6 / (SECS_PER_BLOCK as u32);
+	pub const ObservationRewardPoints: u32 = 1;
 }
 
 impl pallet_octopus_lpos::Config for Test {
@@ -214,11 +243,14 @@ impl pallet_octopus_lpos::Config for Test {
 	type SessionsPerEra = SessionsPerEra;
 	type BondingDuration = BondingDuration;
 	type BlocksPerEra = BlocksPerEra;
+	type ObservationRewardPoints = ObservationRewardPoints;
 	type SessionInterface = Self;
 	type AppchainInterface = OctopusAppchain;
 	type UpwardMessagesInterface = OctopusUpwardMessages;
 	type PalletId = OctopusAppchainPalletId;
 	type ValidatorsProvider = OctopusAppchain;
+	type ObserversProvider = OctopusAppchain;
+	type GovernanceOrigin = MainchainGovernanceOrigin;
 	type WeightInfo = pallet_octopus_lpos::weights::SubstrateWeight<Test>;
 }
 
@@ -240,11 +272,13 @@ construct_runtime!(
 		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
 		Timestamp: pallet_timestamp::{Pallet, Call, Storage, Inherent},
 		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
-		OctopusAppchain: pallet_octopus_appchain::{Pallet, Call, Storage, Config<T>, Event<T>, ValidateUnsigned}, // must before session
+		OctopusAppchain: pallet_octopus_appchain::{Pallet, Call, Storage, Config<T>, Event<T>, Origin, ValidateUnsigned}, // must before session
 		OctopusLpos: pallet_octopus_lpos::{Pallet, Call, Config, Storage, Event<T>},
 		OctopusUpwardMessages: pallet_octopus_upward_messages::{Pallet, Call, Storage, Event<T>},
 		Session: pallet_session::{Pallet, Call, Storage, Event, Config<T>},
+		Historical: pallet_session::historical::{Pallet},
 		Assets: pallet_assets::{Pallet, Call, Storage, Event<T>, Config<T>},
+		Uniques: pallet_uniques::{Pallet, Call, Storage, Event<T>},
 	}
 );
 
@@ -254,20 +288,211 @@ parameter_types! {
 	   pub const UnsignedPriority: u64 = 1 << 21;
 	   pub const RequestEventLimit: u32 = 10;
 	   pub const UpwardMessagesLimit: u32 = 10;
+	   pub const NotificationHistoryDepth: u32 = 10;
+	   pub const ObservationHistoryDepth: u32 = 5;
+	   pub const LateVotePeriod: BlockNumber = 10;
+	   pub const MaxNotificationsPerReceiver: u32 = 5;
+	   pub const MaxPrunedPerBlock: u32 = 5;
+	   pub const MaxRpcEndpoints: u32 = 3;
+	   pub const MaxRpcEndpointLength: u32 = 128;
+	   pub const MaxValidators: u32 = 100;
+	   pub const MaxObservationsPerId: u32 = 100;
+	   pub const MaxNotificationRetries: u32 = 3;
+	   pub const MaxMainchainCallWeight: Weight = 500_000_000;
+	   pub BridgeFeeBeneficiary: AccountId = sp_runtime::AccountId32::new([7u8; 32]);
+}
+
+/// Deposits the bridge fees into `BridgeFeeBeneficiary`.
+pub struct BridgeFeeToBeneficiary;
+
+impl OnUnbalanced<pallet_balances::NegativeImbalance<Test>> for BridgeFeeToBeneficiary {
+	fn on_nonzero_unbalanced(fee: pallet_balances::NegativeImbalance<Test>) {
+		Balances::resolve_creating(&BridgeFeeBeneficiary::get(), fee);
+	}
+}
+
+impl OnUnbalanced<fungibles::CreditOf<AccountId, Assets>> for BridgeFeeToBeneficiary {
+	fn on_nonzero_unbalanced(fee: fungibles::CreditOf<AccountId, Assets>) {
+		let _ =
+			<Assets as fungibles::Balanced<AccountId>>::resolve(&BridgeFeeBeneficiary::get(), fee);
+	}
+}
+
+type ReportedOffence = ConflictingObservationOffence<IdentificationTuple<Test>>;
+
+thread_local! {
+	static OFFENCES: std::cell::RefCell<Vec<ReportedOffence>> = Default::default();
+}
+
+/// Records the reported offences, to be inspected by the tests.
+pub struct OffenceHandler;
+
+impl OffenceHandler {
+	pub fn offences() -> Vec<ReportedOffence> {
+		OFFENCES.with(|offences| offences.borrow().clone())
+	}
+}
+
+impl ReportOffence<AccountId, IdentificationTuple<Test>, ReportedOffence> for OffenceHandler {
+	fn report_offence(
+		_reporters: Vec<AccountId>,
+		offence: ReportedOffence,
+	) -> Result<(), sp_staking::offence::OffenceError> {
+		OFFENCES.with(|offences| offences.borrow_mut().push(offence));
+		Ok(())
+	}
+
+	fn is_known_offence(
+		_offenders: &[IdentificationTuple<Test>],
+		_time_slot: &(ObservationType, u32),
+	) -> bool {
+		false
+	}
+}
+
+/// The state of the anchor contract served by `MockMainchainClient`.
+pub struct MockMainchain {
+	pub validator_sets: Vec<ValidatorSet<AccountId>>,
+	pub notifications: Vec<Observation<AccountId>>,
+	pub anchor_status: AnchorStatus,
+	pub appchain_state: AppchainState,
+	/// Whether the queries fail, as if the mainchain were unreachable.
+	pub unavailable: bool,
+}
+
+impl Default for MockMainchain {
+	/// The anchor contract of an active appchain.
+	fn default() -> Self {
+		Self {
+			validator_sets: Vec::new(),
+			notifications: Vec::new(),
+			anchor_status: AnchorStatus::default(),
+			appchain_state: AppchainState::Active,
+			unavailable: false,
+		}
+	}
+}
+
+thread_local! {
+	static MAINCHAIN: std::cell::RefCell<MockMainchain> = Default::default();
+}
+
+/// An in-memory mainchain client, to test the pallet without http fixtures.
+pub struct MockMainchainClient;
+
+impl MockMainchainClient {
+	pub fn mutate<R>(f: impl FnOnce(&mut MockMainchain) -> R) -> R {
+		MAINCHAIN.with(|mainchain| f(&mut mainchain.borrow_mut()))
+	}
+
+	fn query<R>(f: impl FnOnce(&MockMainchain) -> R) -> Result<R, &'static str> {
+		MAINCHAIN.with(|mainchain| {
+			let mainchain = mainchain.borrow();
+			if mainchain.unavailable {
+				return Err("Mainchain unavailable");
+			}
+			Ok(f(&mainchain))
+		})
+	}
+}
+
+impl MainchainClient<AccountId> for MockMainchainClient {
+	fn validator_set(
+		_mainchain_rpc_endpoints: &[String],
+		_anchor_contract: &[u8],
+		set_id: u32,
+	) -> Result<Option<ValidatorSet<AccountId>>, &'static str> {
+		Self::query(|mainchain| {
+			mainchain
+				.validator_sets
+				.iter()
+				.find(|val_set| val_set.set_id == set_id)
+				.cloned()
+		})
+	}
+
+	fn notification_histories(
+		_mainchain_rpc_endpoints: &[String],
+		_anchor_contract: &[u8],
+		start_index: u32,
+		limit: u32,
+	) -> Result<Vec<Observation<AccountId>>, &'static str> {
+		Self::query(|mainchain| {
+			mainchain
+				.notifications
+				.iter()
+				.filter(|obs| obs.observation_index() >= start_index)
+				.take(limit as usize)
+				.cloned()
+				.collect()
+		})
+	}
+
+	fn anchor_status(
+		_mainchain_rpc_endpoints: &[String],
+		_anchor_contract: &[u8],
+	) -> Result<AnchorStatus, &'static str> {
+		Self::query(|mainchain| mainchain.anchor_status.clone())
+	}
+
+	fn appchain_state(
+		_mainchain_rpc_endpoints: &[String],
+		_anchor_contract: &[u8],
+	) -> Result<Option<AppchainState>, &'static str> {
+		Self::query(|mainchain| Some(mainchain.appchain_state))
+	}
+}
+
+pub type MainchainGovernanceOrigin =
+	EnsureOneOf<AccountId, EnsureRoot<AccountId>, EnsureMainchain<AccountId>>;
+
+/// The calls which the registry on NEAR may dispatch.
+pub struct MainchainCallFilter;
+
+impl Contains<Call> for MainchainCallFilter {
+	fn contains(call: &Call) -> bool {
+		matches!(
+			call,
+			Call::OctopusAppchain(crate::Call::force_set_is_activated { .. })
+				| Call::OctopusAppchain(crate::Call::force_set_appchain_state { .. })
+				| Call::OctopusLpos(pallet_octopus_lpos::Call::force_set_era_payout { .. })
+		)
+	}
 }
 
 impl Config for Test {
 	type AuthorityId = OctopusAppCrypto;
 	type Event = Event;
+	type Origin = Origin;
 	type Call = Call;
 	type PalletId = OctopusAppchainPalletId;
 	type LposInterface = OctopusLpos;
 	type UpwardMessagesInterface = OctopusUpwardMessages;
+	type GovernanceOrigin = MainchainGovernanceOrigin;
+	type PauseOrigin = EnsureRoot<AccountId>;
+	type MainchainCallFilter = MainchainCallFilter;
+	type OnBridgeFee = BridgeFeeToBeneficiary;
+	type OnAssetBridgeFee = BridgeFeeToBeneficiary;
+	type ValidatorSet = Historical;
+	type ReportConflictingObservation = OffenceHandler;
+	type MainchainClient = MockMainchainClient;
 	type Currency = Balances;
 	type Assets = Assets;
+	type Uniques = Uniques;
 	type GracePeriod = GracePeriod;
 	type UnsignedPriority = UnsignedPriority;
 	type RequestEventLimit = RequestEventLimit;
+	type NotificationHistoryDepth = NotificationHistoryDepth;
+	type ObservationHistoryDepth = ObservationHistoryDepth;
+	type LateVotePeriod = LateVotePeriod;
+	type MaxNotificationsPerReceiver = MaxNotificationsPerReceiver;
+	type MaxPrunedPerBlock = MaxPrunedPerBlock;
+	type MaxRpcEndpoints = MaxRpcEndpoints;
+	type MaxRpcEndpointLength = MaxRpcEndpointLength;
+	type MaxValidators = MaxValidators;
+	type MaxObservationsPerId = MaxObservationsPerId;
+	type MaxNotificationRetries = MaxNotificationRetries;
+	type MaxMainchainCallWeight = MaxMainchainCallWeight;
 	type WeightInfo = ();
 }
 
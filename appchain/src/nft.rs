@@ -0,0 +1,77 @@
+//! NFTs bridged between NEP-171 contracts on NEAR and `Config::Uniques`.
+//!
+//! The NFT minted for a NEP-171 token is in the class registered for its contract, with the
+//! instance id hashed from its token id. An appchain-native NFT is escrowed in the pallet
+//! account while it is on NEAR.
+
+use super::*;
+
+impl<T: Config> Pallet<T> {
+	/// The instance id of the NFT minted for a NEP-171 token.
+	pub(crate) fn nft_instance_id(token_id: &[u8]) -> InstanceIdOf<T> {
+		InstanceId::from_le_bytes(sp_io::hashing::blake2_128(token_id))
+	}
+
+	pub(crate) fn ensure_nft_owner(
+		class_id: ClassIdOf<T>,
+		instance_id: InstanceIdOf<T>,
+		who: &T::AccountId,
+	) -> Result<(), Error<T>> {
+		let owner =
+			<T::Uniques as nonfungibles::Inspect<T::AccountId>>::owner(&class_id, &instance_id);
+		ensure!(owner.as_ref() == Some(who), Error::<T>::NotNftOwner);
+		Ok(())
+	}
+
+	/// The metadata of an appchain-native NFT, read as NEP-177 JSON from the attribute of the
+	/// empty key, which is the instance metadata of `pallet_uniques`. Empty if there is none.
+	pub(crate) fn native_nft_metadata(
+		class_id: ClassIdOf<T>,
+		instance_id: InstanceIdOf<T>,
+	) -> TokenMetadata {
+		<T::Uniques as nonfungibles::Inspect<T::AccountId>>::attribute(&class_id, &instance_id, &[])
+			.and_then(|data| serde_json::from_slice(&data).ok())
+			.unwrap_or_default()
+	}
+
+	pub(crate) fn mint_nft_inner(
+		contract_id: Vec<u8>,
+		token_id: Vec<u8>,
+		sender_id: Vec<u8>,
+		receiver: T::AccountId,
+		metadata: TokenMetadata,
+	) -> DispatchResult {
+		let class_id = <ClassIdByContractId<T>>::get(&contract_id)
+			.ok_or(Error::<T>::ContractIdNotRegistered)?;
+		let instance_id = Self::nft_instance_id(&token_id);
+		<T::Uniques as nonfungibles::Mutate<T::AccountId>>::mint_into(
+			&class_id,
+			&instance_id,
+			&receiver,
+		)?;
+		<NftTokenIds<T>>::insert(class_id, instance_id, token_id);
+		<NftMetadata<T>>::insert(class_id, instance_id, metadata);
+		Self::deposit_event(Event::NftMinted(class_id, instance_id, sender_id, receiver));
+
+		Ok(())
+	}
+
+	pub(crate) fn unlock_nft_inner(
+		class_id: ClassIdOf<T>,
+		instance_id: InstanceIdOf<T>,
+		sender_id: Vec<u8>,
+		receiver: T::AccountId,
+	) -> DispatchResult {
+		// Only the escrow of an appchain-native NFT can be unlocked.
+		ensure!(!<ContractIdByClassId<T>>::contains_key(class_id), Error::<T>::NotNativeNft);
+		Self::ensure_nft_owner(class_id, instance_id, &Self::account_id())?;
+		<T::Uniques as nonfungibles::Transfer<T::AccountId>>::transfer(
+			&class_id,
+			&instance_id,
+			&receiver,
+		)?;
+		Self::deposit_event(Event::NftUnlocked(class_id, instance_id, sender_id, receiver));
+
+		Ok(())
+	}
+}
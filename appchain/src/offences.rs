@@ -0,0 +1,126 @@
+//! Detection of the validators voting for conflicting observations.
+//!
+//! A validator voting for two different observations with the same observation id is recorded
+//! in `ConflictingObservations` and reported through `ReportConflictingObservation`. Its second
+//! vote is not counted, even if it arrives after the consensus on the other observation.
+//!
+//! The observation each validator voted for is kept in `VotedObservations` until the
+//! observations with its id are pruned, so a conflict is found without hashing the other
+//! observations.
+
+use super::*;
+use frame_support::traits::ValidatorSet as ValidatorSetT;
+use sp_runtime::traits::Convert;
+use sp_staking::{
+	offence::{Kind, Offence, ReportOffence},
+	SessionIndex,
+};
+
+/// A validator id with its full identification.
+pub type IdentificationTuple<T> = (
+	<<T as Config>::ValidatorSet as ValidatorSetT<
+		<T as frame_system::Config>::AccountId,
+	>>::ValidatorId,
+	<<T as Config>::ValidatorSet as ValidatorSetWithIdentification<
+		<T as frame_system::Config>::AccountId,
+	>>::Identification,
+);
+
+/// A validator has voted for two different observations with the same observation id.
+#[derive(Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct ConflictingObservationOffence<Offender> {
+	/// The session in which the second vote was submitted.
+	pub session_index: SessionIndex,
+	/// The size of the validator set in this session.
+	pub validator_set_count: u32,
+	/// The type and id of both observations.
+	pub time_slot: (ObservationType, u32),
+	/// The validator which voted for both observations.
+	pub offender: Offender,
+}
+
+impl<Offender: Clone> Offence<Offender> for ConflictingObservationOffence<Offender> {
+	const ID: Kind = *b"octopus:conflict";
+	type TimeSlot = (ObservationType, u32);
+
+	fn offenders(&self) -> Vec<Offender> {
+		vec![self.offender.clone()]
+	}
+
+	fn session_index(&self) -> SessionIndex {
+		self.session_index
+	}
+
+	fn validator_set_count(&self) -> u32 {
+		self.validator_set_count
+	}
+
+	fn time_slot(&self) -> Self::TimeSlot {
+		self.time_slot
+	}
+
+	// The same fraction as for an equivocation in block production.
+	fn slash_fraction(offenders_count: u32, validator_set_count: u32) -> Perbill {
+		let x = Perbill::from_rational(3 * offenders_count, validator_set_count);
+		x.square()
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// The hash of the other observation with the same type and id which the validator has voted
+	/// for, if any.
+	pub(crate) fn conflicting_observation(
+		validator_id: &T::AccountId,
+		hash: &T::Hash,
+		time_slot: (ObservationType, u32),
+	) -> Option<T::Hash> {
+		<VotedObservations<T>>::get(time_slot, validator_id).filter(|voted| voted != hash)
+	}
+
+	/// Record the conflicting votes of the validator and report them as an offence.
+	pub(crate) fn report_conflicting_observation(
+		validator_id: &T::AccountId,
+		first: T::Hash,
+		second: T::Hash,
+		time_slot: (ObservationType, u32),
+	) {
+		if <ConflictingObservations<T>>::contains_key(time_slot, validator_id) {
+			return;
+		}
+		log!(warn, "{:?} voted for conflicting observations {:?}", validator_id, time_slot);
+		<ConflictingObservations<T>>::insert(time_slot, validator_id, (first, second));
+		Self::deposit_event(Event::ConflictingObservation(
+			validator_id.clone(),
+			time_slot.0,
+			time_slot.1,
+		));
+
+		let offender = match Self::identification_of(validator_id) {
+			Some(offender) => offender,
+			None => {
+				log!(warn, "no identification of {:?} to report", validator_id);
+				return;
+			},
+		};
+		let offence = ConflictingObservationOffence {
+			session_index: T::ValidatorSet::session_index(),
+			validator_set_count: T::ValidatorSet::validators().len() as u32,
+			time_slot,
+			offender,
+		};
+		if let Err(error) = T::ReportConflictingObservation::report_offence(vec![], offence) {
+			log!(warn, "failed to report the offence of {:?}: {:?}", validator_id, error);
+		}
+	}
+
+	fn identification_of(account: &T::AccountId) -> Option<IdentificationTuple<T>> {
+		let validator_id =
+			<T::ValidatorSet as ValidatorSetT<T::AccountId>>::ValidatorIdOf::convert(
+				account.clone(),
+			)?;
+		let identification = <T::ValidatorSet as ValidatorSetWithIdentification<
+			T::AccountId,
+		>>::IdentificationOf::convert(validator_id.clone())?;
+		Some((validator_id, identification))
+	}
+}
@@ -0,0 +1,75 @@
+//! Pause switches of the bridge operations.
+//!
+//! Each operation, and each bridged asset, can be paused on its own by `PauseOrigin`, so an
+//! incident with one token does not stop the whole channel between the appchain and NEAR.
+//! Inbound transfers of a paused operation or asset wait in the retry queue until resumed.
+
+use super::*;
+use frame_support::dispatch::DispatchResultWithPostInfo;
+
+/// An operation of the bridge which can be paused.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub enum BridgeOperation {
+	/// Lock native tokens to mint them on NEAR.
+	Lock,
+	/// Burn assets to unlock their tokens on NEAR.
+	BurnAsset,
+	/// Unlock native tokens burnt on NEAR.
+	Unlock,
+	/// Mint assets for the tokens locked on NEAR.
+	MintAsset,
+	/// Apply the validator sets observed on NEAR.
+	UpdateValidatorSet,
+	/// Lock appchain-native assets to mint them on NEAR.
+	LockAsset,
+	/// Unlock appchain-native assets burnt on NEAR.
+	UnlockAsset,
+	/// Burn NFTs to unlock their NEP-171 tokens on NEAR.
+	BurnNft,
+	/// Lock appchain-native NFTs to mint them on NEAR.
+	LockNft,
+	/// Mint NFTs for the NEP-171 tokens locked on NEAR.
+	MintNft,
+	/// Unlock appchain-native NFTs burnt on NEAR.
+	UnlockNft,
+	/// Dispatch the governance calls from the mainchain. A call observed while paused is
+	/// rejected, not retried.
+	DispatchMainchainCall,
+}
+
+impl<T: Config> Pallet<T> {
+	/// Ensure that the operation, and the asset it transfers if any, are not paused.
+	pub(crate) fn ensure_not_paused(
+		operation: BridgeOperation,
+		asset_id: Option<AssetIdOf<T>>,
+	) -> Result<(), Error<T>> {
+		if <PausedOperations<T>>::get(operation) {
+			return Err(Error::<T>::OperationPaused);
+		}
+		if asset_id.map_or(false, <PausedAssets<T>>::get) {
+			return Err(Error::<T>::AssetPaused);
+		}
+		Ok(())
+	}
+
+	/// Apply the next validator set if its observation has reached consensus while the validator
+	/// set updates were paused, as all the validators may have voted for it already.
+	pub(crate) fn apply_paused_validator_set() -> DispatchResultWithPostInfo {
+		let total_stake =
+			T::LposInterface::active_total_stake().ok_or(Error::<T>::InvalidActiveTotalStake)?;
+		let set_id = <NextSetId<T>>::get();
+		for observation in <Observations<T>>::get(ObservationType::UpdateValidatorSet, set_id) {
+			let val_set = match observation {
+				Observation::UpdateValidatorSet(ref val_set) => val_set,
+				_ => continue,
+			};
+			let hash = Self::observation_hash(&observation);
+			let (tally, _) = Self::tally(&hash);
+			if Self::reaches_consensus(tally.stake, total_stake) {
+				log!(info, "applying the validator set {:?} observed while paused", set_id);
+				return Self::apply_validator_set(&hash, val_set, &Self::voters_of(&hash));
+			}
+		}
+		Ok(().into())
+	}
+}
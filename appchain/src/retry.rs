@@ -0,0 +1,204 @@
+//! Retry queue of the notifications whose inbound transfer failed.
+//!
+//! A failed notification keeps its full event in `FailedNotifications` until its transfer
+//! succeeds, either by an automatic retry in `on_idle`, by `retry_notification`, or by being
+//! redirected or refunded by governance.
+
+use super::*;
+use frame_support::storage::{with_transaction, TransactionOutcome};
+
+impl<T: Config> Pallet<T> {
+	/// Retry the transfer of a failed notification, and remove it from the queue on success.
+	///
+	/// A failed transfer is rolled back with its events, the failure has been reported already.
+	pub(crate) fn retry_failed_notification(
+		notification_id: u32,
+		observation: &Observation<T::AccountId>,
+	) -> Result<(), NotificationResult> {
+		with_transaction(|| match Self::execute_notification(observation) {
+			Ok(()) => TransactionOutcome::Commit(Ok(())),
+			Err(result) => TransactionOutcome::Rollback(Err(result)),
+		})?;
+
+		<FailedNotifications<T>>::remove(notification_id);
+		if <NotificationHistory<T>>::contains_key(notification_id) {
+			Self::record_notification(notification_id, observation, NotificationResult::Success);
+		}
+		log!(info, "failed notification {:?} retried", notification_id);
+		Self::deposit_event(Event::NotificationRetried(notification_id));
+		Ok(())
+	}
+
+	/// Retry the failed notifications which have automatic retries left, within the weight.
+	///
+	/// The retries resume after the last notification visited in the previous block, so that the
+	/// notifications at the start of the queue do not starve the others.
+	///
+	/// Nothing is retried while the inbound transfers are paused, or the appchain is halted.
+	pub(crate) fn retry_failed_notifications(remaining_weight: Weight) -> Weight {
+		let read_weight = T::DbWeight::get().reads(1);
+		let cursor_weight = T::DbWeight::get().reads_writes(1, 1);
+		let retry_weight = <T as Config>::WeightInfo::retry_notification();
+		let max_retries = T::MaxNotificationRetries::get();
+
+		if remaining_weight < read_weight.saturating_add(cursor_weight) {
+			return 0;
+		}
+		if <TransfersPaused<T>>::get(TransferDirection::Inbound) || Self::is_halted() {
+			return read_weight;
+		}
+
+		let mut consumed: Weight = read_weight.saturating_add(cursor_weight);
+		let mut notifications = match <FailedNotificationsCursor<T>>::get() {
+			Some(cursor) => <FailedNotifications<T>>::iter_from(cursor),
+			None => <FailedNotifications<T>>::iter(),
+		};
+		let mut last_visited = None;
+		let mut retrying = Vec::new();
+		let exhausted = loop {
+			if consumed.saturating_add(read_weight).saturating_add(retry_weight) > remaining_weight
+			{
+				break false;
+			}
+			let (notification_id, (observation, retries)) = match notifications.next() {
+				Some(notification) => notification,
+				None => break true,
+			};
+			consumed = consumed.saturating_add(read_weight);
+			last_visited = Some(notification_id);
+			if retries < max_retries {
+				consumed = consumed.saturating_add(retry_weight);
+				retrying.push((notification_id, observation, retries));
+			}
+		};
+
+		// Start over from the beginning of the queue once its end is reached.
+		if exhausted {
+			<FailedNotificationsCursor<T>>::kill();
+		} else if let Some(notification_id) = last_visited {
+			<FailedNotificationsCursor<T>>::put(<FailedNotifications<T>>::hashed_key_for(
+				notification_id,
+			));
+		}
+
+		for (notification_id, observation, retries) in retrying {
+			match Self::retry_failed_notification(notification_id, &observation) {
+				Ok(()) => {},
+				// An exceeded limit or a pause is not a failure of the transfer itself.
+				Err(NotificationResult::TransferLimited)
+				| Err(NotificationResult::OperationPaused) => {},
+				Err(_) => {
+					let retries = retries + 1;
+					<FailedNotifications<T>>::insert(notification_id, (observation, retries));
+					if retries == max_retries {
+						Self::deposit_event(Event::NotificationRetriesExhausted(notification_id));
+					}
+				},
+			}
+		}
+
+		consumed
+	}
+
+	/// Send the funds of a failed notification back to its sender on NEAR, and record it as
+	/// refunded.
+	pub(crate) fn refund_failed_notification(
+		notification_id: u32,
+		observation: Observation<T::AccountId>,
+	) -> DispatchResult {
+		let sender = Self::account_id();
+		let prefix = String::from("0x");
+		let hex_sender = prefix + &hex::encode(sender.encode());
+
+		match observation.clone() {
+			// The native tokens are still in the pallet account, mint them back on NEAR.
+			Observation::Burn(event) => {
+				let receiver_id = String::from_utf8(event.sender_id)
+					.map_err(|_| Error::<T>::InvalidReceiverId)?;
+				let message = LockPayload { sender: hex_sender, receiver_id, amount: event.amount };
+				T::UpwardMessagesInterface::submit(
+					&sender,
+					PayloadType::Lock,
+					&message.try_to_vec().unwrap(),
+				)?;
+			},
+			// No asset has been minted, unlock the tokens on NEAR.
+			Observation::LockAsset(event) => {
+				let token_id =
+					String::from_utf8(event.token_id).map_err(|_| Error::<T>::InvalidTokenId)?;
+				let receiver_id = String::from_utf8(event.sender_id)
+					.map_err(|_| Error::<T>::InvalidReceiverId)?;
+				let message = BurnAssetPayload {
+					token_id,
+					sender: hex_sender,
+					receiver_id,
+					amount: event.amount,
+				};
+				T::UpwardMessagesInterface::submit(
+					&sender,
+					PayloadType::BurnAsset,
+					&message.try_to_vec().unwrap(),
+				)?;
+			},
+			// The assets are still in escrow, mint them back on NEAR.
+			Observation::BurnNativeAsset(event) => {
+				let receiver_id = String::from_utf8(event.sender_id)
+					.map_err(|_| Error::<T>::InvalidReceiverId)?;
+				let message = LockAssetPayload {
+					asset_id: event.asset_id,
+					sender: hex_sender,
+					receiver_id,
+					amount: event.amount,
+				};
+				T::UpwardMessagesInterface::submit(
+					&sender,
+					PayloadType::LockAsset,
+					&message.try_to_vec().unwrap(),
+				)?;
+			},
+			// No NFT has been minted, unlock the token on NEAR.
+			Observation::LockNft(event) => {
+				let contract_id =
+					String::from_utf8(event.contract_id).map_err(|_| Error::<T>::InvalidTokenId)?;
+				let token_id =
+					String::from_utf8(event.token_id).map_err(|_| Error::<T>::InvalidTokenId)?;
+				let receiver_id = String::from_utf8(event.sender_id)
+					.map_err(|_| Error::<T>::InvalidReceiverId)?;
+				let message =
+					BurnNftPayload { contract_id, token_id, sender: hex_sender, receiver_id };
+				T::UpwardMessagesInterface::submit(
+					&sender,
+					PayloadType::BurnNft,
+					&message.try_to_vec().unwrap(),
+				)?;
+			},
+			// The NFT is still in escrow, mint it back on NEAR.
+			Observation::BurnNft(event) => {
+				let receiver_id = String::from_utf8(event.sender_id)
+					.map_err(|_| Error::<T>::InvalidReceiverId)?;
+				let message = LockNftPayload {
+					class: event.class,
+					instance: event.instance,
+					sender: hex_sender,
+					receiver_id,
+					metadata: Self::native_nft_metadata(event.class, event.instance),
+				};
+				T::UpwardMessagesInterface::submit(
+					&sender,
+					PayloadType::LockNft,
+					&message.try_to_vec().unwrap(),
+				)?;
+			},
+			Observation::UpdateValidatorSet(_)
+			| Observation::GovernanceCall(_)
+			| Observation::UpdateAppchainState(_) => {},
+		}
+
+		<FailedNotifications<T>>::remove(notification_id);
+		if <NotificationHistory<T>>::contains_key(notification_id) {
+			Self::record_notification(notification_id, &observation, NotificationResult::Refunded);
+		}
+		Self::deposit_event(Event::NotificationRefunded(notification_id));
+		Ok(())
+	}
+}
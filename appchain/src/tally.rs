@@ -0,0 +1,166 @@
+//! Votes for the observations and the running tally of their stake.
+//!
+//! The voters of an observation and the tally of their number and stake are keyed by the hash of
+//! the observation, so a vote reads and writes a constant number of entries. A vote adds the
+//! stake of its voter to the tally, which is only recomputed from all the voters when the active
+//! era, and so the stake of the validators, has changed since.
+//!
+//! A vote arriving shortly after the consensus is still credited to its voter, so that the
+//! validators voting a little later than the others are not taken for inactive ones.
+
+use super::*;
+use sp_runtime::traits::{Hash, Saturating};
+
+/// The number of voters of an observation, and their stake in the era it has been tallied in.
+#[derive(Encode, Decode, Clone, Copy, Default, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct ObservationTally {
+	pub voters: u32,
+	pub era: u32,
+	pub stake: u128,
+}
+
+impl ObservationTally {
+	/// The tally of `voters` whose stake has not been read yet, and is tallied when the tally is
+	/// next read for a vote.
+	pub(crate) fn untallied(voters: u32) -> Self {
+		Self { voters, era: u32::MAX, stake: 0 }
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// The key of the voters and the tally of an observation.
+	pub fn observation_hash(observation: &Observation<T::AccountId>) -> T::Hash {
+		T::Hashing::hash_of(observation)
+	}
+
+	/// The era in which the stake of the voters is tallied.
+	pub(crate) fn tally_era() -> u32 {
+		T::LposInterface::active_era_index().unwrap_or_default()
+	}
+
+	/// The voters of an observation.
+	pub(crate) fn voters_of(hash: &T::Hash) -> Vec<T::AccountId> {
+		<Observing<T>>::iter_prefix(hash).map(|(voter, ())| voter).collect()
+	}
+
+	/// The active stake of the voters.
+	pub(crate) fn stake_of_voters(voters: &[T::AccountId]) -> u128 {
+		voters.iter().fold(0u128, |stake, voter| {
+			stake.saturating_add(T::LposInterface::active_stake_of(voter))
+		})
+	}
+
+	/// The tally of an observation in the active era, and the number of voters whose stake has
+	/// been read to tally it.
+	pub(crate) fn tally(hash: &T::Hash) -> (ObservationTally, u32) {
+		let era = Self::tally_era();
+		let mut tally = <ObservationTallies<T>>::get(hash);
+		if tally.era == era || tally.voters == 0 {
+			return (ObservationTally { era, ..tally }, 0);
+		}
+
+		tally.era = era;
+		tally.stake = Self::stake_of_voters(&Self::voters_of(hash));
+		<ObservationTallies<T>>::insert(hash, tally);
+		(tally, tally.voters)
+	}
+
+	/// The stake of the voters of an observation in the active era, without updating its tally.
+	pub(crate) fn observation_stake(hash: &T::Hash) -> u128 {
+		let tally = <ObservationTallies<T>>::get(hash);
+		if tally.era == Self::tally_era() || tally.voters == 0 {
+			tally.stake
+		} else {
+			Self::stake_of_voters(&Self::voters_of(hash))
+		}
+	}
+
+	/// Add the vote of the validator to the tally of an observation, with the type and the id of
+	/// the observation in `time_slot`.
+	///
+	/// Returns the stake of the voters and the number of voters whose stake has been tallied.
+	pub(crate) fn vote(
+		hash: &T::Hash,
+		time_slot: (ObservationType, u32),
+		validator_id: &T::AccountId,
+	) -> Result<(u128, u32), Error<T>> {
+		let (mut tally, tallied) = Self::tally(hash);
+		if <Observing<T>>::contains_key(hash, validator_id) {
+			log!(warn, "{:?} submits a duplicate ocw tx", validator_id);
+			return Ok((tally.stake, tallied));
+		}
+		ensure!(tally.voters < T::MaxValidators::get(), Error::<T>::TooManyVoters);
+
+		<Observing<T>>::insert(hash, validator_id, ());
+		<VotedObservations<T>>::insert(time_slot, validator_id, hash);
+		tally.voters = tally.voters.saturating_add(1);
+		tally.stake = tally.stake.saturating_add(T::LposInterface::active_stake_of(validator_id));
+		<ObservationTallies<T>>::insert(hash, tally);
+		Ok((tally.stake, tallied.saturating_add(1)))
+	}
+
+	/// Withdraw the vote of the validator from the tally of an observation.
+	pub(crate) fn remove_vote(hash: &T::Hash, validator_id: &T::AccountId) {
+		if !<Observing<T>>::contains_key(hash, validator_id) {
+			return;
+		}
+		<Observing<T>>::remove(hash, validator_id);
+
+		let era = Self::tally_era();
+		<ObservationTallies<T>>::mutate(hash, |tally| {
+			tally.voters = tally.voters.saturating_sub(1);
+			// An outdated stake is recomputed on the next vote.
+			if tally.era == era {
+				tally.stake =
+					tally.stake.saturating_sub(T::LposInterface::active_stake_of(validator_id));
+			}
+		});
+	}
+
+	/// Credit the vote for an observation which has already reached consensus, if it arrives
+	/// within `LateVotePeriod` blocks and in the same era. A late vote of a validator which voted
+	/// for another observation with the same id is reported and not credited.
+	///
+	/// Returns the number of voters whose stake has been tallied, or `None` if the observation
+	/// has not reached consensus or the vote is too late.
+	pub(crate) fn credit_late_vote(
+		hash: &T::Hash,
+		time_slot: (ObservationType, u32),
+		validator_id: &T::AccountId,
+	) -> Result<Option<u32>, Error<T>> {
+		let (reached_at, era_index) = match <ConsensusReachedAt<T>>::get(hash) {
+			Some(consensus) => consensus,
+			None => return Ok(None),
+		};
+		let now = <frame_system::Pallet<T>>::block_number();
+		if now > reached_at.saturating_add(T::LateVotePeriod::get())
+			|| T::LposInterface::active_era_index() != Some(era_index)
+		{
+			return Ok(None);
+		}
+		if <Observing<T>>::contains_key(hash, validator_id) {
+			log!(warn, "{:?} submits a duplicate ocw tx", validator_id);
+			return Ok(Some(0));
+		}
+		if let Some(first) = Self::conflicting_observation(validator_id, hash, time_slot) {
+			Self::report_conflicting_observation(validator_id, first, *hash, time_slot);
+			return Ok(Some(0));
+		}
+
+		let (_, tallied) = Self::vote(hash, time_slot, validator_id)?;
+		<ObservationParticipants<T>>::mutate(era_index, validator_id, |count| {
+			*count = count.saturating_add(1)
+		});
+		Ok(Some(tallied))
+	}
+
+	/// Remove the voters, the tally and the consensus of an observation.
+	///
+	/// Returns the number of voters removed.
+	pub(crate) fn remove_votes(hash: &T::Hash) -> u32 {
+		let voters = <ObservationTallies<T>>::take(hash).voters;
+		<Observing<T>>::remove_prefix(hash, None);
+		<ConsensusReachedAt<T>>::remove(hash);
+		voters
+	}
+}
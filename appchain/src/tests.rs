@@ -1,10 +1,12 @@
 use crate::mock::*;
 use crate::Error;
 use crate::*;
-use frame_support::{assert_noop, assert_ok};
+use frame_support::{assert_noop, assert_ok, Hashable};
 use pallet_balances::Error as BalancesError;
-use pallet_octopus_support::traits::{AppchainInterface, ValidatorsProvider};
-use sp_core::offchain::{testing, OffchainWorkerExt, TransactionPoolExt};
+use pallet_octopus_support::traits::{AppchainInterface, ObserversProvider, ValidatorsProvider};
+use sp_core::offchain::{
+	testing, OffchainDbExt, OffchainWorkerExt, StorageKind, Timestamp, TransactionPoolExt,
+};
 use sp_keyring::{sr25519::Keyring, AccountKeyring};
 use sp_keystore::{
 	testing::KeyStore,
@@ -61,6 +63,8 @@ fn test_force_set_params() {
 			],
 		);
 
+		assert_ok!(OctopusAppchain::force_set_is_activated(Origin::root(), false));
+		assert_eq!(OctopusAppchain::appchain_state(), AppchainState::Booting);
 		assert_ok!(OctopusAppchain::force_set_is_activated(Origin::root(), true));
 		assert_eq!(OctopusAppchain::is_activated(), true);
 		assert_noop!(
@@ -69,6 +73,13 @@ fn test_force_set_params() {
 		);
 		assert_eq!(OctopusAppchain::is_activated(), true);
 
+		// An active appchain is frozen, not booting again.
+		assert_ok!(OctopusAppchain::force_set_is_activated(Origin::root(), false));
+		assert_eq!(OctopusAppchain::appchain_state(), AppchainState::Frozen);
+		assert_eq!(OctopusAppchain::is_activated(), false);
+		assert_ok!(OctopusAppchain::force_set_is_activated(Origin::root(), true));
+		assert_eq!(OctopusAppchain::appchain_state(), AppchainState::Active);
+
 		assert_ok!(OctopusAppchain::force_set_next_set_id(Origin::root(), 1));
 		assert_eq!(OctopusAppchain::next_set_id(), 1);
 		assert_noop!(
@@ -164,6 +175,15 @@ fn test_burn_asset() {
 			sp_runtime::MultiAddress::Id(alice),
 			1000000000000000000
 		));
+		assert_noop!(
+			OctopusAppchain::burn_asset(
+				origin.clone(),
+				0,
+				"Test-Account.testnet".to_string().as_bytes().to_vec(),
+				10000000000
+			),
+			Error::<Test>::InvalidNearAccountId
+		);
 		// assert_ok!(OctopusAppchain::burn_asset(
 		// 	origin.clone(),
 		// 	0,
@@ -173,6 +193,240 @@ fn test_burn_asset() {
 	});
 }
 
+#[test]
+fn test_asset_registry() {
+	let ferdie: AccountId = AccountKeyring::Ferdie.into();
+	let token_id = b"wrap.testnet".to_vec();
+	new_tester().execute_with(|| {
+		assert_eq!(TokenIdByAssetId::<Test>::get(2), Some(b"usdc.testnet".to_vec()));
+
+		assert_noop!(
+			OctopusAppchain::register_asset(Origin::signed(ferdie), token_id.clone(), 3),
+			BadOrigin
+		);
+		assert_noop!(
+			OctopusAppchain::register_asset(Origin::root(), vec![0, 159], 3),
+			Error::<Test>::InvalidTokenId
+		);
+		assert_noop!(
+			OctopusAppchain::register_asset(Origin::root(), b"usdc.testnet".to_vec(), 3),
+			Error::<Test>::TokenIdAlreadyRegistered
+		);
+		assert_noop!(
+			OctopusAppchain::register_asset(Origin::root(), token_id.clone(), 2),
+			Error::<Test>::AssetIdAlreadyRegistered
+		);
+
+		assert_ok!(OctopusAppchain::register_asset(Origin::root(), token_id.clone(), 3));
+		assert_eq!(AssetIdByName::<Test>::get(&token_id), 3);
+		assert_eq!(TokenIdByAssetId::<Test>::get(3), Some(token_id.clone()));
+
+		assert_noop!(
+			OctopusAppchain::update_asset(Origin::root(), b"unknown.testnet".to_vec(), 4),
+			Error::<Test>::TokenIdNotRegistered
+		);
+		assert_noop!(
+			OctopusAppchain::update_asset(Origin::root(), token_id.clone(), 2),
+			Error::<Test>::AssetIdAlreadyRegistered
+		);
+		assert_ok!(OctopusAppchain::update_asset(Origin::root(), token_id.clone(), 4));
+		assert_eq!(AssetIdByName::<Test>::get(&token_id), 4);
+		assert_eq!(TokenIdByAssetId::<Test>::get(3), None);
+		assert_eq!(TokenIdByAssetId::<Test>::get(4), Some(token_id.clone()));
+
+		assert_ok!(OctopusAppchain::deregister_asset(Origin::root(), token_id.clone()));
+		assert!(!AssetIdByName::<Test>::contains_key(&token_id));
+		assert_eq!(TokenIdByAssetId::<Test>::get(4), None);
+		assert_noop!(
+			OctopusAppchain::deregister_asset(Origin::root(), token_id.clone()),
+			Error::<Test>::TokenIdNotRegistered
+		);
+
+		assert!(System::events().iter().any(|record| record.event
+			== Event::OctopusAppchain(crate::Event::AssetUpdated(token_id.clone(), 3, 4))));
+	});
+}
+
+#[test]
+fn test_token_decimals_conversion() {
+	// 18 decimals on NEAR, 12 decimals on the appchain.
+	let decimals = TokenDecimals { near: 18, appchain: 12 };
+	assert_eq!(decimals.to_appchain(1_500_000_000_000_000_000), Some(1_500_000_000_000));
+	assert_eq!(decimals.to_appchain(1_500_000_000_000_999_999), None);
+	assert_eq!(decimals.to_near(1_500_000_000_000), Some(1_500_000_000_000_000_000));
+	assert_eq!(decimals.to_near(u128::MAX), None);
+
+	// 12 decimals on NEAR, 18 decimals on the appchain.
+	let decimals = TokenDecimals { near: 12, appchain: 18 };
+	assert_eq!(decimals.to_appchain(1_500_000_000_000), Some(1_500_000_000_000_000_000));
+	assert_eq!(decimals.to_near(1_500_000_000_000_000_000), Some(1_500_000_000_000));
+	assert_eq!(decimals.to_near(1_500_000_000_000_000_001), None);
+
+	// 24 decimals on NEAR, 18 decimals on the appchain.
+	let decimals = TokenDecimals { near: 24, appchain: 18 };
+	assert_eq!(
+		decimals.to_appchain(2_000_000_000_000_000_000_000_000),
+		Some(2_000_000_000_000_000_000)
+	);
+	assert_eq!(decimals.to_appchain(999_999), None);
+	assert_eq!(
+		decimals.to_near(2_000_000_000_000_000_000),
+		Some(2_000_000_000_000_000_000_000_000)
+	);
+
+	// 18 decimals on NEAR, 24 decimals on the appchain.
+	let decimals = TokenDecimals { near: 18, appchain: 24 };
+	assert_eq!(
+		decimals.to_appchain(2_000_000_000_000_000_000),
+		Some(2_000_000_000_000_000_000_000_000)
+	);
+	assert_eq!(decimals.to_appchain(u128::MAX), None);
+	assert_eq!(
+		decimals.to_near(2_000_000_000_000_000_000_000_000),
+		Some(2_000_000_000_000_000_000)
+	);
+	assert_eq!(decimals.to_near(2_000_000_000_000_000_000_000_001), None);
+
+	assert!(TokenDecimals { near: 24, appchain: 0 }.is_valid());
+	assert!(!TokenDecimals { near: 40, appchain: 0 }.is_valid());
+}
+
+#[test]
+fn test_burn_asset_with_decimals() {
+	let alice: AccountId = AccountKeyring::Alice.into();
+	let origin = Origin::signed(alice.clone());
+	new_tester().execute_with(|| {
+		assert_ok!(Assets::force_create(
+			Origin::root(),
+			0,
+			sp_runtime::MultiAddress::Id(alice.clone()),
+			true,
+			1
+		));
+		assert_ok!(OctopusAppchain::register_asset(Origin::root(), b"wrap.testnet".to_vec(), 0));
+		// The appchain-native assets have decimals too.
+		assert_ok!(OctopusAppchain::set_asset_decimals(
+			Origin::root(),
+			1,
+			Some(TokenDecimals { near: 12, appchain: 18 })
+		));
+		assert_eq!(AssetDecimals::<Test>::get(1), Some(TokenDecimals { near: 12, appchain: 18 }));
+		assert_noop!(
+			OctopusAppchain::set_asset_decimals(
+				Origin::root(),
+				0,
+				Some(TokenDecimals { near: 40, appchain: 0 })
+			),
+			Error::<Test>::InvalidDecimals
+		);
+		assert_ok!(OctopusAppchain::set_asset_decimals(
+			Origin::root(),
+			0,
+			Some(TokenDecimals { near: 12, appchain: 18 })
+		));
+		assert_ok!(OctopusAppchain::force_set_is_activated(Origin::root(), true));
+		assert_ok!(OctopusAppchain::mint_asset(
+			Origin::root(),
+			0,
+			"test-account.testnet".to_string().as_bytes().to_vec(),
+			sp_runtime::MultiAddress::Id(alice.clone()),
+			1_000_000_000_000_000_000
+		));
+
+		assert_noop!(
+			OctopusAppchain::burn_asset(
+				origin.clone(),
+				0,
+				"test-account.testnet".to_string().as_bytes().to_vec(),
+				1_000_001
+			),
+			Error::<Test>::AmountNotConvertible
+		);
+		assert_ok!(OctopusAppchain::burn_asset(
+			origin,
+			0,
+			"test-account.testnet".to_string().as_bytes().to_vec(),
+			2_000_000
+		));
+		assert_eq!(Assets::balance(0, alice), 999_999_999_998_000_000);
+
+		assert_ok!(OctopusAppchain::deregister_asset(Origin::root(), b"wrap.testnet".to_vec()));
+		assert_eq!(AssetDecimals::<Test>::get(0), None);
+	});
+}
+
+#[test]
+fn test_lock_with_native_token_decimals() {
+	let alice: AccountId = AccountKeyring::Alice.into();
+	new_tester().execute_with(|| {
+		let origin = Origin::signed(OctopusAppchain::pallet_account());
+		assert_noop!(
+			OctopusAppchain::set_native_token_decimals(
+				Origin::signed(alice.clone()),
+				Some(TokenDecimals { near: 12, appchain: 18 })
+			),
+			BadOrigin
+		);
+		assert_ok!(OctopusAppchain::set_native_token_decimals(
+			Origin::root(),
+			Some(TokenDecimals { near: 12, appchain: 18 })
+		));
+		assert_ok!(OctopusAppchain::force_set_is_activated(Origin::root(), true));
+
+		assert_noop!(
+			OctopusAppchain::lock(
+				origin.clone(),
+				"test-account.testnet".to_string().as_bytes().to_vec(),
+				1_000_000_000_001
+			),
+			Error::<Test>::AmountNotConvertible
+		);
+		assert_ok!(OctopusAppchain::lock(
+			origin,
+			"test-account.testnet".to_string().as_bytes().to_vec(),
+			1_000_000_000_000
+		));
+	});
+}
+
+#[test]
+fn test_unlock_with_native_token_decimals() {
+	let ferdie: AccountId = AccountKeyring::Ferdie.into();
+	let burn = |amount| {
+		Observation::Burn(BurnEvent {
+			index: 0,
+			sender_id: "andy-pallet-test.testnet".to_string().as_bytes().to_vec(),
+			receiver: ferdie.clone(),
+			amount,
+		})
+	};
+	new_tester().execute_with(|| {
+		assert_ok!(OctopusAppchain::set_native_token_decimals(
+			Origin::root(),
+			Some(TokenDecimals { near: 24, appchain: 18 })
+		));
+
+		// The dust of the amount can not be dropped, it has been burnt on NEAR.
+		assert_eq!(
+			OctopusAppchain::execute_notification(&burn(2_000_000_000_000_000_000_000_001)),
+			Err(NotificationResult::UnlockFailed)
+		);
+
+		// The failure reports the amount on the appchain.
+		let amount: u128 = 1_000_000_000_000_000_000_000_000_000_000;
+		assert_eq!(
+			OctopusAppchain::execute_notification(&burn(amount * 1_000_000)),
+			Err(NotificationResult::UnlockFailed)
+		);
+		assert!(System::events().iter().any(|record| record.event
+			== Event::OctopusAppchain(crate::Event::UnlockFailed(
+				"andy-pallet-test.testnet".to_string().as_bytes().to_vec(),
+				ferdie.clone(),
+				amount
+			))));
+	});
+}
+
 #[test]
 fn test_lock() {
 	let alice: AccountId = AccountKeyring::Alice.into();
@@ -192,6 +446,12 @@ fn test_lock() {
 			OctopusAppchain::lock(origin.clone(), vec![0, 159], 1000000000),
 			Error::<Test>::InvalidReceiverId
 		);
+		for receiver_id in ["Test-Account.testnet", "test-account..testnet", "a", "test account"] {
+			assert_noop!(
+				OctopusAppchain::lock(origin.clone(), receiver_id.as_bytes().to_vec(), 1000000000),
+				Error::<Test>::InvalidNearAccountId
+			);
+		}
 
 		// TODO:
 		// assert_noop!(
@@ -275,107 +535,1729 @@ fn test_submit_observations() {
 }
 
 #[test]
-fn test_encode_args_works() {
-	let test_get_validators_data = vec![
-		(0u32, Some(b"eyJlcmFfbnVtYmVyIjoiMCJ9".to_vec())),
-		(4294967295u32, Some(b"eyJlcmFfbnVtYmVyIjoiNDI5NDk2NzI5NSJ9".to_vec())),
-	];
+fn test_submit_observations_refunds_weight() {
+	let (obs_payload, msig) = mock_payload_and_signature(AccountKeyring::Alice);
+	let stash: Balance = 100 * 1_000_000_000_000_000_000; // 100 OCT with 18 decimals
+	let validators =
+		vec![(AccountKeyring::Alice.into(), stash), (AccountKeyring::Bob.into(), stash)];
 
-	for (set_id, expected) in test_get_validators_data {
-		assert_eq!(expected, OctopusAppchain::encode_get_validator_args(set_id));
-	}
+	new_tester().execute_with(|| {
+		OctopusLpos::trigger_new_era(1, validators);
+		advance_session();
+		let post_info =
+			OctopusAppchain::submit_observations(Origin::none(), obs_payload, msig).unwrap();
+		assert_eq!(
+			post_info.actual_weight,
+			Some(<Test as crate::Config>::WeightInfo::submit_observations(1, 1))
+		);
+		assert!(
+			post_info.actual_weight.unwrap()
+				< <Test as crate::Config>::WeightInfo::submit_observations(1, MaxValidators::get())
+		);
+	});
+}
 
-	let test_get_notify_data = vec![
-		(0u32, 0u32, Some(b"eyJzdGFydF9pbmRleCI6IjAiLCJxdWFudGl0eSI6IjAifQ==".to_vec())),
-		(
-			4294967295u32,
-			4294967295u32,
-			Some(
-				b"eyJzdGFydF9pbmRleCI6IjQyOTQ5NjcyOTUiLCJxdWFudGl0eSI6IjQyOTQ5NjcyOTUifQ=="
-					.to_vec(),
+#[test]
+fn test_consensus_threshold() {
+	let ferdie: AccountId = AccountKeyring::Ferdie.into();
+	new_tester().execute_with(|| {
+		// More than 2/3 of the stake by default.
+		assert!(!OctopusAppchain::reaches_consensus(2, 3));
+		assert!(!OctopusAppchain::reaches_consensus(200, 300));
+		assert!(OctopusAppchain::reaches_consensus(201, 300));
+
+		assert_noop!(
+			OctopusAppchain::set_consensus_threshold(
+				Origin::signed(ferdie),
+				Some(Perbill::from_percent(50))
 			),
-		),
-	];
+			BadOrigin
+		);
+		assert_noop!(
+			OctopusAppchain::set_consensus_threshold(
+				Origin::root(),
+				Some(Perbill::from_percent(49))
+			),
+			Error::<Test>::InvalidConsensusThreshold
+		);
+		assert_noop!(
+			OctopusAppchain::set_consensus_threshold(Origin::root(), Some(Perbill::one())),
+			Error::<Test>::InvalidConsensusThreshold
+		);
 
-	for (start, limit, expected) in test_get_notify_data {
-		assert_eq!(expected, OctopusAppchain::encode_get_notification_args(start, limit));
-	}
+		assert_ok!(OctopusAppchain::set_consensus_threshold(
+			Origin::root(),
+			Some(Perbill::from_percent(50))
+		));
+		assert!(!OctopusAppchain::reaches_consensus(50, 100));
+		assert!(OctopusAppchain::reaches_consensus(51, 100));
+		assert!(OctopusAppchain::reaches_consensus(2, 3));
+		assert!(!OctopusAppchain::reaches_consensus(0, 0));
+
+		assert_ok!(OctopusAppchain::set_consensus_threshold(Origin::root(), None));
+		assert!(!OctopusAppchain::reaches_consensus(2, 3));
+		assert!(System::events().iter().any(|record| record.event
+			== Event::OctopusAppchain(crate::Event::ConsensusThresholdUpdated(None))));
+	});
 }
 
-fn expected_val_set() -> Observation<AccountId> {
-	let id = hex::decode("d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d")
-		.map(|b| AccountId::decode(&mut &b[..]))
-		.unwrap()
-		.unwrap();
-	let alice = Validator { validator_id_in_appchain: id, total_stake: 10000000000 };
-
-	let id = hex::decode("8eaf04151687736326c9fea17e25fc5287613693c912909cb226aa4794f26a48")
-		.map(|b| AccountId::decode(&mut &b[..]))
-		.unwrap()
-		.unwrap();
-	let bob = Validator { validator_id_in_appchain: id, total_stake: 10000000000 };
+#[test]
+fn test_observation_limits() {
+	let alice: AccountId = AccountKeyring::Alice.into();
+	let stash: Balance = 100 * 1_000_000_000_000_000_000; // 100 OCT with 18 decimals
+	let validators = vec![(alice.clone(), stash), (AccountKeyring::Bob.into(), stash)];
+	let observation = |amount| {
+		Observation::Burn(BurnEvent {
+			index: 0,
+			sender_id: "andy-pallet-test.testnet".to_string().as_bytes().to_vec(),
+			receiver: AccountKeyring::Dave.into(),
+			amount,
+		})
+	};
 
-	let id = hex::decode("90b5ab205c6974c9ea841be688864633dc9ca8a357843eeacf2314649965fe22")
-		.map(|b| AccountId::decode(&mut &b[..]))
-		.unwrap()
-		.unwrap();
-	let charlie = Validator { validator_id_in_appchain: id, total_stake: 100000000000 };
+	new_tester().execute_with(|| {
+		OctopusLpos::trigger_new_era(1, validators);
+		advance_session();
 
-	let id = hex::decode("306721211d5404bd9da88e0204360a1a9ab8b87c66c1bc2fcdd37f3c2222cc20")
-		.map(|b| AccountId::decode(&mut &b[..]))
-		.unwrap()
-		.unwrap();
-	let dave = Validator { validator_id_in_appchain: id, total_stake: 10000000000 };
+		let observations: Vec<_> =
+			(0..MaxObservationsPerId::get() as u128).map(observation).collect();
+		Observations::<Test>::insert(ObservationType::Burn, 0, observations);
+		assert_eq!(
+			OctopusAppchain::submit_observation(&alice, observation(1000)).map_err(|e| e.error),
+			Err(Error::<Test>::ObservationsExceededLimit.into())
+		);
+		assert_ok!(OctopusAppchain::submit_observation(&alice, observation(1)));
 
-	Observation::UpdateValidatorSet(ValidatorSet {
-		set_id: 1,
-		validators: vec![alice, bob, charlie, dave],
-	})
+		ObservationTallies::<Test>::insert(
+			OctopusAppchain::observation_hash(&observation(2)),
+			ObservationTally { voters: MaxValidators::get(), ..Default::default() },
+		);
+		assert_eq!(
+			OctopusAppchain::submit_observation(&alice, observation(2)).map_err(|e| e.error),
+			Err(Error::<Test>::TooManyVoters.into())
+		);
+	});
 }
 
-fn validator_set_1_response(state: &mut testing::OffchainState) {
-	state.expect_request(testing::PendingRequest {
-		method: "POST".into(),
-		uri: "https://rpc.testnet.near.org".into(),
-		headers: vec![("Content-Type".into(), "application/json".into())],
-		body: br#"
-		{
-			"jsonrpc": "2.0",
-			"id": "dontcare",
-			"method": "query",
-			"params": {
-				"request_type": "call_function",
-				"finality": "final",
-				"account_id": "oct-test.testnet",
-				"method_name": "get_validator_list_of",
-				"args_base64": "eyJlcmFfbnVtYmVyIjoiMSJ9"
-			}
-		}"#.to_vec(),
-		response: Some(br#"
-		{
-			"jsonrpc": "2.0",
-			"result": {
-				"block_hash": "EczErquQLMpUvTQpKupoQp5yNkgNbniMSHq1gVvhAf84",
-				"block_height": 1,
-				"logs": [],
-		 		"result": [
-					91,123,34,118,97,108,105,100,97,116,111,114,95,105,100,95,105,110,95,97,112,112,99,104,97,105,110,34,58,34,48,120,100,52,51,53,57,51,99,55,49,53,102,100,100,51,49,99,54,49,49,52,49,97,98,100,48,52,97,57,57,102,100,54,56,50,50,99,56,53,53,56,56,53,52,99,99,100,101,51,57,97,53,54,56,52,101,55,97,53,54,100,97,50,55,100,34,44,34,116,111,116,97,108,95,115,116,97,107,101,34,58,34,49,48,48,48,48,48,48,48,48,48,48,34,125,44,123,34,118,97,108,105,100,97,116,111,114,95,105,100,95,105,110,95,97,112,
-					112,99,104,97,105,110,34,58,34,48,120,56,101,97,102,48,52,49,53,49,54,56,55,55,51,54,51,50,54,99,57,102,101,97,49,55,101,50,53,102,99,53,50,56,55,54,49,51,54,57,51,99,57,49,50,57,48,57,99,98,50,50,54,97,97,52,55,57,52,102,50,54,97,52,56,34,44,34,116,111,116,97,108,95,115,116,97,107,101,34,58,34,49,48,48,48,48,48,48,48,48,48,48,34,125,44,123,34,118,97,108,105,100,97,116,111,114,95,105,100,95,105,110,95,97,112,112,99,104,97,105,110,34,58,34,48,120,57,48,98,53,97,98,50,48,53,99,54,57,55,52,99,
-					57,101,97,56,52,49,98,101,54,56,56,56,54,52,54,51,51,100,99,57,99,97,56,97,51,53,55,56,52,51,101,101,97,99,102,50,51,49,52,54,52,57,57,54,53,102,101,50,50,34,44,34,116,111,116,97,108,95,115,116,97,107,101,34,58,34,49,48,48,48,48,48,48,48,48,48,48,48,34,125,44,123,34,118,97,108,105,100,97,116,111,114,95,105,100,95,105,110,95,97,112,112,99,104,97,105,110,34,58,34,48,120,51,48,54,55,50,49,50,49,49,100,53,52,48,52,98,100,57,100,97,56,56,101,48,50,48,52,51,54,48,97,49,97,57,97,98,56,98,56,55,99,
-					54,54,99,49,98,99,50,102,99,100,100,51,55,102,51,99,50,50,50,50,99,99,50,48,34,44,34,116,111,116,97,108,95,115,116,97,107,101,34,58,34,49,48,48,48,48,48,48,48,48,48,48,34,125,93
-				]
-			},
-			"id": "dontcare"
-		}
-			"#.to_vec()),
-		sent: true,
-		..Default::default()
+#[test]
+fn test_report_conflicting_observations() {
+	let alice: AccountId = AccountKeyring::Alice.into();
+	let bob: AccountId = AccountKeyring::Bob.into();
+	let charlie: AccountId = AccountKeyring::Charlie.into();
+	let stash: Balance = 100 * 1_000_000_000_000_000_000; // 100 OCT with 18 decimals
+	let validators =
+		vec![(alice.clone(), stash), (bob.clone(), stash), (charlie.clone(), stash / 10)];
+	let observation = |amount| {
+		Observation::Burn(BurnEvent {
+			index: 0,
+			sender_id: "andy-pallet-test.testnet".to_string().as_bytes().to_vec(),
+			receiver: AccountKeyring::Dave.into(),
+			amount,
+		})
+	};
+	let hash = |amount| OctopusAppchain::observation_hash(&observation(amount));
+	let time_slot = (ObservationType::Burn, 0);
+
+	new_tester().execute_with(|| {
+		OctopusLpos::trigger_new_era(1, validators);
+		advance_session();
+		let era_index = OctopusLpos::active_era().unwrap().index;
+
+		assert_eq!(OctopusAppchain::submit_observation(&alice, observation(100)), Ok(1));
+		assert_eq!(VotedObservations::<Test>::get(time_slot, &alice), Some(hash(100)));
+		// The conflicting vote is reported and not counted.
+		assert_eq!(OctopusAppchain::submit_observation(&alice, observation(200)), Ok(0));
+		assert!(OctopusAppchain::voters_of(&hash(200)).is_empty());
+		assert_eq!(
+			ConflictingObservations::<Test>::get(time_slot, &alice),
+			Some((hash(100), hash(200)))
+		);
+		assert!(System::events().iter().any(|record| record.event
+			== Event::OctopusAppchain(crate::Event::ConflictingObservation(
+				alice.clone(),
+				ObservationType::Burn,
+				0
+			))));
+
+		let offences = OffenceHandler::offences();
+		assert_eq!(offences.len(), 1);
+		assert_eq!(offences[0].offender.0, alice);
+		assert_eq!(offences[0].time_slot, time_slot);
+		assert_eq!(offences[0].session_index, Session::current_index());
+
+		// The same conflict is reported once.
+		assert_eq!(OctopusAppchain::submit_observation(&alice, observation(300)), Ok(0));
+		assert_eq!(OffenceHandler::offences().len(), 1);
+
+		// A late vote conflicting with a previous vote is reported and not credited.
+		assert_eq!(OctopusAppchain::submit_observation(&charlie, observation(200)), Ok(1));
+		assert_ok!(OctopusAppchain::submit_observation(&bob, observation(100)));
+		assert_eq!(NextNotificationId::<Test>::get(), 1);
+		assert_eq!(OctopusAppchain::submit_observation(&charlie, observation(100)), Ok(0));
+		assert!(!Observing::<Test>::contains_key(hash(100), &charlie));
+		assert_eq!(ObservationParticipants::<Test>::get(era_index, &charlie), 0);
+		assert_eq!(
+			ConflictingObservations::<Test>::get(time_slot, &charlie),
+			Some((hash(200), hash(100)))
+		);
+		assert_eq!(OffenceHandler::offences().len(), 2);
+
+		// The votes and the conflicts are pruned with the observations.
+		OctopusAppchain::prune_observations(ObservationType::Burn, 0);
+		assert_eq!(VotedObservations::<Test>::iter_prefix(time_slot).count(), 0);
+		assert_eq!(ConflictingObservations::<Test>::iter_prefix(time_slot).count(), 0);
 	});
 }
 
-fn empty_validator_set_1_response(state: &mut testing::OffchainState) {
-	state.expect_request(testing::PendingRequest {
-		method: "POST".into(),
+#[test]
+fn test_observation_stake_tally() {
+	let alice: AccountId = AccountKeyring::Alice.into();
+	let bob: AccountId = AccountKeyring::Bob.into();
+	let charlie: AccountId = AccountKeyring::Charlie.into();
+	let stash: Balance = 100 * 1_000_000_000_000_000_000; // 100 OCT with 18 decimals
+	let validators = vec![(alice.clone(), stash), (bob.clone(), stash), (charlie.clone(), stash)];
+	let observation = Observation::Burn(BurnEvent {
+		index: 0,
+		sender_id: "andy-pallet-test.testnet".to_string().as_bytes().to_vec(),
+		receiver: AccountKeyring::Dave.into(),
+		amount: 100,
+	});
+
+	new_tester().execute_with(|| {
+		OctopusLpos::trigger_new_era(1, validators);
+		advance_session();
+		let era_index = OctopusLpos::active_era().unwrap().index;
+		let hash = OctopusAppchain::observation_hash(&observation);
+
+		// Each vote adds its voter and its stake to the tally.
+		assert_eq!(OctopusAppchain::submit_observation(&alice, observation.clone()), Ok(1));
+		assert_eq!(OctopusAppchain::voters_of(&hash), vec![alice.clone()]);
+		let tally = ObservationTally { voters: 1, era: era_index, stake: stash };
+		assert_eq!(ObservationTallies::<Test>::get(hash), tally);
+		assert_eq!(OctopusAppchain::submit_observation(&alice, observation.clone()), Ok(0));
+		assert_eq!(ObservationTallies::<Test>::get(hash), tally);
+
+		// The tally of another era is recomputed from the voters.
+		ObservationTallies::<Test>::insert(hash, ObservationTally { era: era_index + 1, ..tally });
+		assert_eq!(OctopusAppchain::submit_observation(&bob, observation.clone()), Ok(2));
+		assert_eq!(
+			ObservationTallies::<Test>::get(hash),
+			ObservationTally { voters: 2, era: era_index, stake: 2 * stash }
+		);
+
+		// A withdrawn vote leaves the tally.
+		OctopusAppchain::remove_vote(&hash, &bob);
+		assert!(!Observing::<Test>::contains_key(hash, &bob));
+		assert_eq!(ObservationTallies::<Test>::get(hash), tally);
+
+		// The voters and their tally are pruned with the observation.
+		OctopusAppchain::prune_observations(ObservationType::Burn, 0);
+		assert!(!Observing::<Test>::contains_key(hash, &alice));
+		assert!(!ObservationTallies::<Test>::contains_key(hash));
+	});
+}
+
+#[test]
+fn test_record_observers() {
+	let alice: AccountId = AccountKeyring::Alice.into();
+	let bob: AccountId = AccountKeyring::Bob.into();
+	let charlie: AccountId = AccountKeyring::Charlie.into();
+	let stash: Balance = 100 * 1_000_000_000_000_000_000; // 100 OCT with 18 decimals
+	let validators = vec![(alice.clone(), stash), (bob.clone(), stash), (charlie.clone(), stash)];
+	let observation = Observation::Burn(BurnEvent {
+		index: 0,
+		sender_id: "andy-pallet-test.testnet".to_string().as_bytes().to_vec(),
+		receiver: AccountKeyring::Dave.into(),
+		amount: 100,
+	});
+
+	new_tester().execute_with(|| {
+		OctopusLpos::trigger_new_era(1, validators);
+		advance_session();
+		let era_index = OctopusLpos::active_era().unwrap().index;
+
+		assert_ok!(OctopusAppchain::submit_observation(&alice, observation.clone()));
+		assert_ok!(OctopusAppchain::submit_observation(&bob, observation.clone()));
+		assert_eq!(OctopusAppchain::observers(era_index), (0, vec![]));
+
+		assert_ok!(OctopusAppchain::submit_observation(&charlie, observation.clone()));
+		let (observations, mut observers) = OctopusAppchain::observers(era_index);
+		observers.sort();
+		let mut expected = vec![(alice, 1), (bob, 1), (charlie, 1)];
+		expected.sort();
+		assert_eq!(observations, 1);
+		assert_eq!(observers, expected);
+
+		OctopusAppchain::clear_observers(era_index);
+		assert_eq!(OctopusAppchain::observers(era_index), (0, vec![]));
+	});
+}
+
+#[test]
+fn test_credit_late_votes() {
+	let alice: AccountId = AccountKeyring::Alice.into();
+	let bob: AccountId = AccountKeyring::Bob.into();
+	let charlie: AccountId = AccountKeyring::Charlie.into();
+	let dave: AccountId = AccountKeyring::Dave.into();
+	let stash: Balance = 100 * 1_000_000_000_000_000_000; // 100 OCT with 18 decimals
+	let validators = vec![
+		(alice.clone(), stash),
+		(bob.clone(), stash),
+		(charlie.clone(), stash),
+		(dave.clone(), stash),
+	];
+	let observation = |index| {
+		Observation::Burn(BurnEvent {
+			index,
+			sender_id: "andy-pallet-test.testnet".to_string().as_bytes().to_vec(),
+			receiver: AccountKeyring::Dave.into(),
+			amount: 100,
+		})
+	};
+
+	new_tester().execute_with(|| {
+		OctopusLpos::trigger_new_era(1, validators);
+		advance_session();
+		let era_index = OctopusLpos::active_era().unwrap().index;
+		let participation =
+			|validator: &AccountId| ObservationParticipants::<Test>::get(era_index, validator);
+
+		for validator in [&alice, &bob, &charlie] {
+			assert_ok!(OctopusAppchain::submit_observation(validator, observation(0)));
+		}
+		assert_eq!(NextNotificationId::<Test>::get(), 1);
+
+		// A vote within the late vote period is credited, once.
+		assert_ok!(OctopusAppchain::submit_observation(&dave, observation(0)));
+		assert_eq!(participation(&dave), 1);
+		assert_ok!(OctopusAppchain::submit_observation(&dave, observation(0)));
+		assert_eq!(participation(&dave), 1);
+		assert_eq!(OctopusAppchain::observers(era_index).0, 1);
+
+		for validator in [&alice, &bob, &charlie] {
+			assert_ok!(OctopusAppchain::submit_observation(validator, observation(1)));
+		}
+		System::set_block_number(System::block_number() + LateVotePeriod::get() + 1);
+		assert_noop!(
+			OctopusAppchain::submit_observation(&dave, observation(1)),
+			Error::<Test>::InvalidNotificationId
+		);
+		assert_eq!(participation(&dave), 1);
+		assert_eq!(participation(&alice), 2);
+	});
+}
+
+#[test]
+fn test_pending_observations() {
+	let alice: AccountId = AccountKeyring::Alice.into();
+	let bob: AccountId = AccountKeyring::Bob.into();
+	let charlie: AccountId = AccountKeyring::Charlie.into();
+	let stash: Balance = 100 * 1_000_000_000_000_000_000; // 100 OCT with 18 decimals
+	let validators = vec![(alice.clone(), stash), (bob.clone(), stash), (charlie.clone(), stash)];
+	let observation = Observation::Burn(BurnEvent {
+		index: 0,
+		sender_id: "andy-pallet-test.testnet".to_string().as_bytes().to_vec(),
+		receiver: AccountKeyring::Dave.into(),
+		amount: 100,
+	});
+
+	new_tester().execute_with(|| {
+		OctopusLpos::trigger_new_era(1, validators);
+		advance_session();
+
+		assert_ok!(OctopusAppchain::submit_observation(&alice, observation.clone()));
+		assert_eq!(
+			OctopusAppchain::pending_observations(),
+			vec![PendingObservation {
+				observation_type: ObservationType::Burn,
+				observation_id: 0,
+				observation: observation.clone(),
+				voters: vec![alice.clone()],
+				stake: stash,
+				stake_percentage: Perbill::from_rational(1u32, 3u32),
+			}]
+		);
+		assert_eq!(OctopusAppchain::notification_history(0, 10), vec![]);
+		assert_eq!(OctopusAppchain::notification_status(0), NotificationStatus::Pending);
+
+		// An outdated tally is recomputed for the query, not stored.
+		let hash = OctopusAppchain::observation_hash(&observation);
+		let outdated = ObservationTally { voters: 1, era: u32::MAX, stake: 0 };
+		ObservationTallies::<Test>::insert(hash, outdated);
+		assert_eq!(OctopusAppchain::pending_observations()[0].stake, stash);
+		assert_eq!(ObservationTallies::<Test>::get(hash), outdated);
+
+		assert_ok!(OctopusAppchain::submit_observation(&bob, observation.clone()));
+		assert_ok!(OctopusAppchain::submit_observation(&charlie, observation.clone()));
+		assert_eq!(OctopusAppchain::pending_observations(), vec![]);
+		assert_eq!(OctopusAppchain::notification_history(0, 10).len(), 1);
+		let record = NotificationHistory::<Test>::get(0).unwrap();
+		let transfer = record.transfer.clone().unwrap();
+		assert_eq!(transfer.receiver, AccountKeyring::Dave.into());
+		assert_eq!(transfer.token_id, None);
+		assert_eq!(transfer.amount, 100);
+		assert_eq!(record.block_number, Some(System::block_number()));
+		assert_eq!(
+			OctopusAppchain::notifications_of(&AccountKeyring::Dave.into()),
+			vec![(0, record)]
+		);
+		assert!(matches!(OctopusAppchain::notification_status(0), NotificationStatus::Executed(_)));
+	});
+}
+
+#[test]
+fn test_retry_failed_notification() {
+	let ferdie: AccountId = AccountKeyring::Ferdie.into();
+	let (obs_payload1, msig1) = mock_payload_and_signature(AccountKeyring::Alice);
+	let (obs_payload2, msig2) = mock_payload_and_signature(AccountKeyring::Bob);
+	let stash: Balance = 100 * 1_000_000_000_000_000_000; // 100 OCT with 18 decimals
+	let validators =
+		vec![(AccountKeyring::Alice.into(), stash), (AccountKeyring::Bob.into(), stash)];
+	let receiver = match expected_burn_notify() {
+		Observation::Burn(event) => event.receiver,
+		_ => unreachable!(),
+	};
+
+	new_tester().execute_with(|| {
+		OctopusLpos::trigger_new_era(1, validators);
+		advance_session();
+		assert_ok!(OctopusAppchain::submit_observations(Origin::none(), obs_payload1, msig1));
+		assert_ok!(OctopusAppchain::submit_observations(Origin::none(), obs_payload2, msig2));
+
+		// The amount is below the existential deposit of the new receiver.
+		assert_eq!(
+			NotificationHistory::<Test>::get(0).map(|record| record.result),
+			Some(NotificationResult::UnlockFailed)
+		);
+		assert_eq!(FailedNotifications::<Test>::get(0), Some((expected_burn_notify(), 0)));
+
+		assert_noop!(
+			OctopusAppchain::retry_notification(Origin::signed(ferdie.clone()), 0),
+			Error::<Test>::NotificationRetryFailed
+		);
+		assert_noop!(
+			OctopusAppchain::retry_notification(Origin::signed(ferdie.clone()), 1),
+			Error::<Test>::NotificationNotFailed
+		);
+
+		for _ in 0..MaxNotificationRetries::get() + 1 {
+			<OctopusAppchain as Hooks<BlockNumber>>::on_idle(1, Weight::max_value());
+		}
+		assert_eq!(
+			FailedNotifications::<Test>::get(0),
+			Some((expected_burn_notify(), MaxNotificationRetries::get()))
+		);
+
+		assert_ok!(Balances::set_balance(
+			Origin::root(),
+			sp_runtime::MultiAddress::Id(receiver.clone()),
+			DOLLARS,
+			0
+		));
+		assert_ok!(OctopusAppchain::retry_notification(Origin::signed(ferdie), 0));
+		assert_eq!(
+			NotificationHistory::<Test>::get(0).map(|record| record.result),
+			Some(NotificationResult::Success)
+		);
+		assert!(!FailedNotifications::<Test>::contains_key(0));
+		assert_eq!(Balances::free_balance(&receiver), DOLLARS + 100000);
+	});
+}
+
+#[test]
+fn test_retry_failed_notifications_in_turn() {
+	new_tester().execute_with(|| {
+		for notification_id in 0..3 {
+			FailedNotifications::<Test>::insert(notification_id, (expected_burn_notify(), 0));
+		}
+		let retry_weight = <() as crate::weights::WeightInfo>::retry_notification();
+
+		// One retry per block, each failed notification gets its turn.
+		System::reset_events();
+		for _ in 0..3 {
+			<OctopusAppchain as Hooks<BlockNumber>>::on_idle(1, retry_weight);
+		}
+		for notification_id in 0..3 {
+			assert_eq!(
+				FailedNotifications::<Test>::get(notification_id),
+				Some((expected_burn_notify(), 1))
+			);
+		}
+		// The failed retries are rolled back, without reporting the failure again.
+		assert!(!System::events().iter().any(|record| matches!(
+			record.event,
+			Event::OctopusAppchain(crate::Event::UnlockFailed(..))
+		)));
+
+		for _ in 0..3 * MaxNotificationRetries::get() {
+			<OctopusAppchain as Hooks<BlockNumber>>::on_idle(1, retry_weight);
+		}
+		for notification_id in 0..3 {
+			assert_eq!(
+				FailedNotifications::<Test>::get(notification_id),
+				Some((expected_burn_notify(), MaxNotificationRetries::get()))
+			);
+			assert_eq!(
+				System::events()
+					.iter()
+					.filter(|record| record.event
+						== Event::OctopusAppchain(crate::Event::NotificationRetriesExhausted(
+							notification_id
+						)))
+					.count(),
+				1
+			);
+		}
+	});
+}
+
+#[test]
+fn test_redirect_and_refund_failed_notifications() {
+	let ferdie: AccountId = AccountKeyring::Ferdie.into();
+	let failed_notification = |index| {
+		Observation::Burn(BurnEvent {
+			index,
+			sender_id: "andy-pallet-test.testnet".to_string().as_bytes().to_vec(),
+			receiver: AccountKeyring::Dave.into(),
+			amount: 10 * DOLLARS,
+		})
+	};
+
+	new_tester().execute_with(|| {
+		FailedNotifications::<Test>::insert(0, (failed_notification(0), 3));
+		FailedNotifications::<Test>::insert(1, (failed_notification(1), 3));
+		for index in 0..2 {
+			OctopusAppchain::record_notification(
+				index,
+				&failed_notification(index),
+				NotificationResult::UnlockFailed,
+			);
+		}
+		assert_eq!(OctopusAppchain::notifications_of(&AccountKeyring::Dave.into()).len(), 2);
+
+		assert_noop!(
+			OctopusAppchain::redirect_notification(
+				Origin::signed(ferdie.clone()),
+				0,
+				sp_runtime::MultiAddress::Id(ferdie.clone())
+			),
+			BadOrigin
+		);
+		assert_ok!(OctopusAppchain::redirect_notification(
+			Origin::root(),
+			0,
+			sp_runtime::MultiAddress::Id(ferdie.clone())
+		));
+		assert!(!FailedNotifications::<Test>::contains_key(0));
+		assert_eq!(Balances::free_balance(&ferdie), 10 * DOLLARS);
+		// The record follows the new receiver.
+		assert_eq!(OctopusAppchain::notifications_of(&AccountKeyring::Dave.into()).len(), 1);
+		let records = OctopusAppchain::notifications_of(&ferdie);
+		assert_eq!(records.len(), 1);
+		assert_eq!(records[0].0, 0);
+		assert_eq!(records[0].1.receiver(), Some(&ferdie));
+		assert_eq!(records[0].1.result, NotificationResult::Success);
+
+		assert_noop!(OctopusAppchain::refund_notification(Origin::signed(ferdie), 1), BadOrigin);
+		assert_ok!(OctopusAppchain::refund_notification(Origin::root(), 1));
+		assert!(!FailedNotifications::<Test>::contains_key(1));
+		let records = OctopusAppchain::notifications_of(&AccountKeyring::Dave.into());
+		assert_eq!(records[0].0, 1);
+		assert_eq!(records[0].1.result, NotificationResult::Refunded);
+		assert_noop!(
+			OctopusAppchain::refund_notification(Origin::root(), 1),
+			Error::<Test>::NotificationNotFailed
+		);
+
+		let events = System::events();
+		assert!(events.iter().any(|record| record.event
+			== Event::OctopusAppchain(crate::Event::NotificationRedirected(
+				0,
+				AccountKeyring::Ferdie.into()
+			))));
+		assert!(events
+			.iter()
+			.any(|record| record.event
+				== Event::OctopusAppchain(crate::Event::NotificationRefunded(1))));
+	});
+}
+
+#[test]
+fn test_transfer_volume_rolls() {
+	let mut volume = TransferVolume { window_start: 0u32, current: 0, previous: 0 };
+	volume.roll(5, 10);
+	volume.current += 100;
+	assert_eq!(volume.amount(5, 10), 100);
+
+	// Half of the previous window still overlaps the rolling window.
+	volume.roll(15, 10);
+	assert_eq!(volume, TransferVolume { window_start: 10, current: 0, previous: 100 });
+	assert_eq!(volume.amount(15, 10), 50);
+
+	volume.roll(30, 10);
+	assert_eq!(volume, TransferVolume { window_start: 30, current: 0, previous: 0 });
+}
+
+#[test]
+fn test_outbound_transfer_limit() {
+	let alice: AccountId = AccountKeyring::Alice.into();
+	let origin = Origin::signed(alice.clone());
+	let limit = TransferLimit { max_amount: 2000000000, window: 10 };
+	new_tester().execute_with(|| {
+		assert_ok!(OctopusAppchain::force_set_is_activated(Origin::root(), true));
+		assert_ok!(Balances::set_balance(
+			Origin::root(),
+			sp_runtime::MultiAddress::Id(alice.clone()),
+			DOLLARS,
+			0
+		));
+		assert_noop!(
+			OctopusAppchain::set_transfer_limit(
+				origin.clone(),
+				TransferDirection::Outbound,
+				None,
+				Some(limit)
+			),
+			BadOrigin
+		);
+		assert_noop!(
+			OctopusAppchain::set_transfer_limit(
+				Origin::root(),
+				TransferDirection::Outbound,
+				None,
+				Some(TransferLimit { max_amount: 2000000000, window: 0 })
+			),
+			Error::<Test>::InvalidTransferLimit
+		);
+		assert_ok!(OctopusAppchain::set_transfer_limit(
+			Origin::root(),
+			TransferDirection::Outbound,
+			Some(BridgedToken::Native),
+			Some(limit)
+		));
+
+		assert_ok!(OctopusAppchain::lock(
+			origin.clone(),
+			"test-account.testnet".to_string().as_bytes().to_vec(),
+			1500000000
+		));
+		// A transfer which would fail anyway does not trip the limit.
+		let charlie: AccountId = AccountKeyring::Charlie.into();
+		assert_noop!(
+			OctopusAppchain::lock(
+				Origin::signed(charlie),
+				"test-account.testnet".to_string().as_bytes().to_vec(),
+				1000000000
+			),
+			BalancesError::<Test>::InsufficientBalance
+		);
+		assert!(!TransfersPaused::<Test>::get(TransferDirection::Outbound));
+
+		let balance = Balances::free_balance(&alice);
+		assert_ok!(OctopusAppchain::lock(
+			origin.clone(),
+			"test-account.testnet".to_string().as_bytes().to_vec(),
+			1000000000
+		));
+		// The transfer is reverted, and the outbound transfers paused.
+		assert_eq!(Balances::free_balance(&alice), balance);
+		assert!(TransfersPaused::<Test>::get(TransferDirection::Outbound));
+		assert!(System::events().iter().any(|record| record.event
+			== Event::OctopusAppchain(crate::Event::TransferLimitExceeded(
+				TransferDirection::Outbound,
+				BridgedToken::Native
+			))));
+
+		System::set_block_number(System::block_number() + 20);
+		assert_noop!(
+			OctopusAppchain::lock(
+				origin.clone(),
+				"test-account.testnet".to_string().as_bytes().to_vec(),
+				1000000000
+			),
+			Error::<Test>::TransfersPaused
+		);
+		assert_ok!(OctopusAppchain::resume_transfers(Origin::root(), TransferDirection::Outbound));
+		assert_ok!(OctopusAppchain::lock(
+			origin,
+			"test-account.testnet".to_string().as_bytes().to_vec(),
+			1000000000
+		));
+	});
+}
+
+#[test]
+fn test_inbound_transfer_limit_pauses_transfers() {
+	let (obs_payload1, msig1) = mock_payload_and_signature(AccountKeyring::Alice);
+	let (obs_payload2, msig2) = mock_payload_and_signature(AccountKeyring::Bob);
+	let stash: Balance = 100 * 1_000_000_000_000_000_000; // 100 OCT with 18 decimals
+	let validators =
+		vec![(AccountKeyring::Alice.into(), stash), (AccountKeyring::Bob.into(), stash)];
+	let receiver = match expected_burn_notify() {
+		Observation::Burn(event) => event.receiver,
+		_ => unreachable!(),
+	};
+
+	new_tester().execute_with(|| {
+		assert_ok!(Balances::set_balance(
+			Origin::root(),
+			sp_runtime::MultiAddress::Id(receiver.clone()),
+			DOLLARS,
+			0
+		));
+		assert_ok!(OctopusAppchain::set_transfer_limit(
+			Origin::root(),
+			TransferDirection::Inbound,
+			None,
+			Some(TransferLimit { max_amount: 50000, window: 10 })
+		));
+
+		OctopusLpos::trigger_new_era(1, validators);
+		advance_session();
+		assert_ok!(OctopusAppchain::submit_observations(Origin::none(), obs_payload1, msig1));
+		assert_ok!(OctopusAppchain::submit_observations(Origin::none(), obs_payload2, msig2));
+
+		assert_eq!(
+			NotificationHistory::<Test>::get(0).map(|record| record.result),
+			Some(NotificationResult::TransferLimited)
+		);
+		assert_eq!(FailedNotifications::<Test>::get(0), Some((expected_burn_notify(), 0)));
+		assert!(TransfersPaused::<Test>::get(TransferDirection::Inbound));
+		assert!(System::events().iter().any(|record| record.event
+			== Event::OctopusAppchain(crate::Event::TransferLimitExceeded(
+				TransferDirection::Inbound,
+				BridgedToken::Native
+			))));
+
+		// Paused notifications do not use up their automatic retries.
+		<OctopusAppchain as Hooks<BlockNumber>>::on_idle(1, Weight::max_value());
+		assert_eq!(FailedNotifications::<Test>::get(0), Some((expected_burn_notify(), 0)));
+
+		assert_ok!(OctopusAppchain::set_transfer_limit(
+			Origin::root(),
+			TransferDirection::Inbound,
+			None,
+			Some(TransferLimit { max_amount: 200000, window: 10 })
+		));
+		assert_noop!(
+			OctopusAppchain::resume_transfers(
+				Origin::signed(AccountKeyring::Ferdie.into()),
+				TransferDirection::Inbound
+			),
+			BadOrigin
+		);
+		assert_ok!(OctopusAppchain::resume_transfers(Origin::root(), TransferDirection::Inbound));
+		assert!(!TransfersPaused::<Test>::get(TransferDirection::Inbound));
+
+		<OctopusAppchain as Hooks<BlockNumber>>::on_idle(1, Weight::max_value());
+		assert_eq!(
+			NotificationHistory::<Test>::get(0).map(|record| record.result),
+			Some(NotificationResult::Success)
+		);
+		assert!(!FailedNotifications::<Test>::contains_key(0));
+		assert_eq!(Balances::free_balance(&receiver), DOLLARS + 100000);
+		assert_eq!(
+			TransferVolumes::<Test>::get(TransferDirection::Inbound, None::<BridgedToken<u32>>)
+				.current,
+			100000
+		);
+	});
+}
+
+#[test]
+fn test_pause_bridge_operations() {
+	let alice: AccountId = AccountKeyring::Alice.into();
+	let origin = Origin::signed(alice.clone());
+	let (obs_payload1, msig1) = mock_payload_and_signature(AccountKeyring::Alice);
+	let (obs_payload2, msig2) = mock_payload_and_signature(AccountKeyring::Bob);
+	let stash: Balance = 100 * 1_000_000_000_000_000_000; // 100 OCT with 18 decimals
+	let validators =
+		vec![(AccountKeyring::Alice.into(), stash), (AccountKeyring::Bob.into(), stash)];
+	let receiver = match expected_burn_notify() {
+		Observation::Burn(event) => event.receiver,
+		_ => unreachable!(),
+	};
+
+	new_tester().execute_with(|| {
+		assert_ok!(OctopusAppchain::force_set_is_activated(Origin::root(), true));
+		assert_ok!(Balances::set_balance(
+			Origin::root(),
+			sp_runtime::MultiAddress::Id(receiver.clone()),
+			DOLLARS,
+			0
+		));
+		assert_noop!(
+			OctopusAppchain::set_operation_paused(origin.clone(), BridgeOperation::Lock, true),
+			BadOrigin
+		);
+		assert_ok!(OctopusAppchain::set_operation_paused(
+			Origin::root(),
+			BridgeOperation::Lock,
+			true
+		));
+		assert_ok!(OctopusAppchain::set_operation_paused(
+			Origin::root(),
+			BridgeOperation::Unlock,
+			true
+		));
+		assert_noop!(
+			OctopusAppchain::lock(
+				origin.clone(),
+				"test-account.testnet".to_string().as_bytes().to_vec(),
+				1000000000
+			),
+			Error::<Test>::OperationPaused
+		);
+
+		OctopusLpos::trigger_new_era(1, validators);
+		advance_session();
+		assert_ok!(OctopusAppchain::submit_observations(Origin::none(), obs_payload1, msig1));
+		assert_ok!(OctopusAppchain::submit_observations(Origin::none(), obs_payload2, msig2));
+		assert_eq!(
+			NotificationHistory::<Test>::get(0).map(|record| record.result),
+			Some(NotificationResult::OperationPaused)
+		);
+		assert_eq!(FailedNotifications::<Test>::get(0), Some((expected_burn_notify(), 0)));
+
+		// Resuming the unlocks does not resume the locks.
+		assert_ok!(OctopusAppchain::set_operation_paused(
+			Origin::root(),
+			BridgeOperation::Unlock,
+			false
+		));
+		<OctopusAppchain as Hooks<BlockNumber>>::on_idle(1, Weight::max_value());
+		assert_eq!(
+			NotificationHistory::<Test>::get(0).map(|record| record.result),
+			Some(NotificationResult::Success)
+		);
+		assert_eq!(Balances::free_balance(&receiver), DOLLARS + 100000);
+		assert!(PausedOperations::<Test>::get(BridgeOperation::Lock));
+		assert!(System::events().iter().any(|record| record.event
+			== Event::OctopusAppchain(crate::Event::OperationPauseUpdated(
+				BridgeOperation::Unlock,
+				false
+			))));
+	});
+}
+
+#[test]
+fn test_resume_validator_set_updates_applies_the_observed_set() {
+	let alice: AccountId = AccountKeyring::Alice.into();
+	let bob: AccountId = AccountKeyring::Bob.into();
+	let stash: Balance = 100 * 1_000_000_000_000_000_000; // 100 OCT with 18 decimals
+	let validators = vec![(alice.clone(), stash), (bob.clone(), stash)];
+
+	new_tester().execute_with(|| {
+		OctopusLpos::trigger_new_era(1, validators);
+		advance_session();
+		assert_ok!(OctopusAppchain::set_operation_paused(
+			Origin::root(),
+			BridgeOperation::UpdateValidatorSet,
+			true
+		));
+
+		// All the validators vote while paused, nothing submits the observation again.
+		assert_ok!(OctopusAppchain::submit_observation(&alice, expected_val_set()));
+		assert_ok!(OctopusAppchain::submit_observation(&bob, expected_val_set()));
+		let planned: Vec<(AccountId, u128)> = expected_validator_set()
+			.validators
+			.into_iter()
+			.map(|v| (v.validator_id_in_appchain, v.total_stake))
+			.collect();
+		assert_eq!(NextSetId::<Test>::get(), 1);
+		assert_ne!(PlannedValidators::<Test>::get(), planned);
+
+		assert_ok!(OctopusAppchain::set_operation_paused(
+			Origin::root(),
+			BridgeOperation::UpdateValidatorSet,
+			false
+		));
+		assert_eq!(NextSetId::<Test>::get(), 2);
+		assert_eq!(PlannedValidators::<Test>::get(), planned);
+
+		// Nothing left to apply.
+		assert_ok!(OctopusAppchain::set_operation_paused(
+			Origin::root(),
+			BridgeOperation::UpdateValidatorSet,
+			false
+		));
+		assert_eq!(NextSetId::<Test>::get(), 2);
+	});
+}
+
+#[test]
+fn test_pause_bridged_asset() {
+	let alice: AccountId = AccountKeyring::Alice.into();
+	let origin = Origin::signed(alice.clone());
+	new_tester().execute_with(|| {
+		assert_ok!(OctopusAppchain::force_set_is_activated(Origin::root(), true));
+		assert_ok!(Assets::force_create(
+			Origin::root(),
+			0,
+			sp_runtime::MultiAddress::Id(alice.clone()),
+			true,
+			1
+		));
+		assert_ok!(OctopusAppchain::register_asset(Origin::root(), b"usdc.testnet".to_vec(), 0));
+		assert_ok!(OctopusAppchain::mint_asset(
+			Origin::root(),
+			0,
+			"test-account.testnet".to_string().as_bytes().to_vec(),
+			sp_runtime::MultiAddress::Id(alice.clone()),
+			1000000
+		));
+
+		assert_noop!(OctopusAppchain::set_asset_paused(origin.clone(), 0, true), BadOrigin);
+		assert_ok!(OctopusAppchain::set_asset_paused(Origin::root(), 0, true));
+		assert_noop!(
+			OctopusAppchain::burn_asset(
+				origin.clone(),
+				0,
+				"test-account.testnet".to_string().as_bytes().to_vec(),
+				100000
+			),
+			Error::<Test>::AssetPaused
+		);
+
+		assert_ok!(OctopusAppchain::set_asset_paused(Origin::root(), 0, false));
+		assert!(!PausedAssets::<Test>::contains_key(0));
+		assert_ok!(OctopusAppchain::burn_asset(
+			origin,
+			0,
+			"test-account.testnet".to_string().as_bytes().to_vec(),
+			100000
+		));
+	});
+}
+
+#[test]
+fn test_lock_with_bridge_fee() {
+	let alice: AccountId = AccountKeyring::Alice.into();
+	let origin = Origin::signed(alice.clone());
+	let fee = BridgeFee { flat: DOLLARS, basis_points: 100, min_amount: 2 * DOLLARS };
+	new_tester().execute_with(|| {
+		assert_ok!(OctopusAppchain::force_set_is_activated(Origin::root(), true));
+		assert_ok!(Balances::set_balance(
+			Origin::root(),
+			sp_runtime::MultiAddress::Id(alice.clone()),
+			10 * DOLLARS,
+			0
+		));
+		assert_noop!(
+			OctopusAppchain::set_bridge_fee(origin.clone(), BridgedToken::Native, Some(fee)),
+			BadOrigin
+		);
+		assert_noop!(
+			OctopusAppchain::set_bridge_fee(
+				Origin::root(),
+				BridgedToken::Native,
+				Some(BridgeFee { basis_points: 10_001, ..fee })
+			),
+			Error::<Test>::InvalidBridgeFee
+		);
+		assert_ok!(OctopusAppchain::set_bridge_fee(
+			Origin::root(),
+			BridgedToken::Native,
+			Some(fee)
+		));
+
+		assert_noop!(
+			OctopusAppchain::lock(
+				origin.clone(),
+				"test-account.testnet".to_string().as_bytes().to_vec(),
+				DOLLARS
+			),
+			Error::<Test>::AmountTooLow
+		);
+		assert_ok!(OctopusAppchain::lock(
+			origin,
+			"test-account.testnet".to_string().as_bytes().to_vec(),
+			5 * DOLLARS
+		));
+		assert_eq!(Balances::free_balance(&alice), 5 * DOLLARS);
+		assert_eq!(Balances::free_balance(&BridgeFeeBeneficiary::get()), 105 * CENTS);
+		assert!(System::events().iter().any(|record| record.event
+			== Event::OctopusAppchain(crate::Event::Locked(
+				alice.clone(),
+				"test-account.testnet".to_string().as_bytes().to_vec(),
+				395 * CENTS,
+				105 * CENTS
+			))));
+	});
+}
+
+#[test]
+fn test_burn_asset_with_bridge_fee() {
+	let alice: AccountId = AccountKeyring::Alice.into();
+	let origin = Origin::signed(alice.clone());
+	new_tester().execute_with(|| {
+		assert_ok!(OctopusAppchain::force_set_is_activated(Origin::root(), true));
+		assert_ok!(Assets::force_create(
+			Origin::root(),
+			0,
+			sp_runtime::MultiAddress::Id(alice.clone()),
+			true,
+			1
+		));
+		assert_ok!(OctopusAppchain::register_asset(Origin::root(), b"wrap.testnet".to_vec(), 0));
+		assert_ok!(OctopusAppchain::set_asset_decimals(
+			Origin::root(),
+			0,
+			Some(TokenDecimals { near: 12, appchain: 18 })
+		));
+		assert_ok!(OctopusAppchain::set_bridge_fee(
+			Origin::root(),
+			BridgedToken::Asset(0),
+			Some(BridgeFee { flat: 0, basis_points: 30, min_amount: 0 })
+		));
+		assert_ok!(OctopusAppchain::mint_asset(
+			Origin::root(),
+			0,
+			"test-account.testnet".to_string().as_bytes().to_vec(),
+			sp_runtime::MultiAddress::Id(alice.clone()),
+			1_000_000_000_000_000_000
+		));
+
+		// The dust which can not be represented on NEAR is charged with the fee.
+		assert_ok!(OctopusAppchain::burn_asset(
+			origin,
+			0,
+			"test-account.testnet".to_string().as_bytes().to_vec(),
+			1_000_000_000_123
+		));
+		assert_eq!(Assets::balance(0, alice.clone()), 999_998_999_999_999_877);
+		assert_eq!(Assets::balance(0, BridgeFeeBeneficiary::get()), 3_000_000_123);
+		assert!(System::events().iter().any(|record| record.event
+			== Event::OctopusAppchain(crate::Event::AssetBurned(
+				0,
+				alice.clone(),
+				"test-account.testnet".to_string().as_bytes().to_vec(),
+				997_000_000_000,
+				3_000_000_123
+			))));
+	});
+}
+
+#[test]
+fn test_lock_asset() {
+	let alice: AccountId = AccountKeyring::Alice.into();
+	let origin = Origin::signed(alice.clone());
+	new_tester().execute_with(|| {
+		assert_ok!(Assets::force_create(
+			Origin::root(),
+			5,
+			sp_runtime::MultiAddress::Id(alice.clone()),
+			true,
+			1
+		));
+		assert_ok!(Assets::mint(
+			origin.clone(),
+			5,
+			sp_runtime::MultiAddress::Id(alice.clone()),
+			10_000
+		));
+		assert_noop!(
+			OctopusAppchain::lock_asset(
+				origin.clone(),
+				5,
+				"test-account.testnet".to_string().as_bytes().to_vec(),
+				1_000
+			),
+			Error::<Test>::NotActivated
+		);
+
+		assert_ok!(OctopusAppchain::force_set_is_activated(Origin::root(), true));
+		assert_noop!(
+			OctopusAppchain::lock_asset(
+				origin.clone(),
+				5,
+				"Test-Account.testnet".to_string().as_bytes().to_vec(),
+				1_000
+			),
+			Error::<Test>::InvalidNearAccountId
+		);
+		// The asset of a bridged NEP-141 token can only be burnt.
+		assert_noop!(
+			OctopusAppchain::lock_asset(
+				origin.clone(),
+				2,
+				"test-account.testnet".to_string().as_bytes().to_vec(),
+				1_000
+			),
+			Error::<Test>::NotNativeAsset
+		);
+
+		assert_ok!(OctopusAppchain::lock_asset(
+			origin.clone(),
+			5,
+			"test-account.testnet".to_string().as_bytes().to_vec(),
+			1_000
+		));
+		assert_eq!(Assets::balance(5, alice.clone()), 9_000);
+		assert_eq!(Assets::balance(5, OctopusAppchain::pallet_account()), 1_000);
+		assert!(System::events().iter().any(|record| record.event
+			== Event::OctopusAppchain(crate::Event::AssetLocked(
+				5,
+				alice.clone(),
+				"test-account.testnet".to_string().as_bytes().to_vec(),
+				1_000,
+				0
+			))));
+
+		assert_ok!(OctopusAppchain::set_operation_paused(
+			Origin::root(),
+			BridgeOperation::LockAsset,
+			true
+		));
+		assert_noop!(
+			OctopusAppchain::lock_asset(
+				origin,
+				5,
+				"test-account.testnet".to_string().as_bytes().to_vec(),
+				1_000
+			),
+			Error::<Test>::OperationPaused
+		);
+	});
+}
+
+#[test]
+fn test_unlock_asset() {
+	let alice: AccountId = AccountKeyring::Alice.into();
+	let bob: AccountId = AccountKeyring::Bob.into();
+	let burn_native_asset = |index, asset_id| {
+		Observation::BurnNativeAsset(BurnNativeAssetEvent {
+			index,
+			asset_id,
+			sender_id: "andy-pallet-test.testnet".to_string().as_bytes().to_vec(),
+			receiver: AccountKeyring::Bob.into(),
+			amount: 400,
+		})
+	};
+
+	new_tester().execute_with(|| {
+		assert_ok!(Assets::force_create(
+			Origin::root(),
+			5,
+			sp_runtime::MultiAddress::Id(alice.clone()),
+			true,
+			1
+		));
+		assert_ok!(Assets::mint(
+			Origin::signed(alice.clone()),
+			5,
+			sp_runtime::MultiAddress::Id(alice.clone()),
+			10_000
+		));
+
+		// Nothing has been locked yet.
+		assert_eq!(
+			OctopusAppchain::execute_notification(&burn_native_asset(0, 5)),
+			Err(NotificationResult::AssetUnlockFailed)
+		);
+
+		assert_ok!(OctopusAppchain::force_set_is_activated(Origin::root(), true));
+		assert_ok!(OctopusAppchain::lock_asset(
+			Origin::signed(alice),
+			5,
+			"test-account.testnet".to_string().as_bytes().to_vec(),
+			1_000
+		));
+		assert_ok!(OctopusAppchain::execute_notification(&burn_native_asset(0, 5)));
+		assert_eq!(Assets::balance(5, bob.clone()), 400);
+		assert_eq!(Assets::balance(5, OctopusAppchain::pallet_account()), 600);
+		assert!(System::events().iter().any(|record| record.event
+			== Event::OctopusAppchain(crate::Event::AssetUnlocked(
+				5,
+				"andy-pallet-test.testnet".to_string().as_bytes().to_vec(),
+				bob.clone(),
+				400
+			))));
+
+		// The asset of a bridged NEP-141 token is never in escrow.
+		assert_eq!(
+			OctopusAppchain::execute_notification(&burn_native_asset(1, 2)),
+			Err(NotificationResult::AssetUnlockFailed)
+		);
+
+		assert_ok!(OctopusAppchain::set_operation_paused(
+			Origin::root(),
+			BridgeOperation::UnlockAsset,
+			true
+		));
+		assert_eq!(
+			OctopusAppchain::execute_notification(&burn_native_asset(1, 5)),
+			Err(NotificationResult::OperationPaused)
+		);
+
+		OctopusAppchain::record_notification(
+			1,
+			&burn_native_asset(1, 5),
+			NotificationResult::OperationPaused,
+		);
+		let records = OctopusAppchain::notifications_of(&bob);
+		assert_eq!(records.len(), 1);
+		let transfer = records[0].1.transfer.clone().unwrap();
+		assert_eq!(transfer.asset_id, Some(5));
+		assert_eq!(transfer.token_id, None);
+
+		// A failed unlock is refunded by minting the wrapped token back on NEAR.
+		FailedNotifications::<Test>::insert(1, (burn_native_asset(1, 5), 3));
+		assert_ok!(OctopusAppchain::refund_notification(Origin::root(), 1));
+		assert!(!FailedNotifications::<Test>::contains_key(1));
+		assert_eq!(Assets::balance(5, OctopusAppchain::pallet_account()), 600);
+	});
+}
+
+fn nft_owner(class_id: u128, instance_id: u128) -> Option<AccountId> {
+	<Uniques as frame_support::traits::tokens::nonfungibles::Inspect<AccountId>>::owner(
+		&class_id,
+		&instance_id,
+	)
+}
+
+#[test]
+fn test_nft_class_registry() {
+	let ferdie: AccountId = AccountKeyring::Ferdie.into();
+	let contract_id = b"nft.testnet".to_vec();
+	new_tester().execute_with(|| {
+		assert_noop!(
+			OctopusAppchain::register_nft_class(Origin::signed(ferdie), contract_id.clone(), 7),
+			BadOrigin
+		);
+		assert_noop!(
+			OctopusAppchain::register_nft_class(Origin::root(), vec![0, 159], 7),
+			Error::<Test>::InvalidTokenId
+		);
+		assert_ok!(OctopusAppchain::register_nft_class(Origin::root(), contract_id.clone(), 7));
+		assert_noop!(
+			OctopusAppchain::register_nft_class(Origin::root(), contract_id.clone(), 8),
+			Error::<Test>::ContractIdAlreadyRegistered
+		);
+		assert_noop!(
+			OctopusAppchain::register_nft_class(Origin::root(), b"paras.testnet".to_vec(), 7),
+			Error::<Test>::ClassIdAlreadyRegistered
+		);
+		assert_eq!(ContractIdByClassId::<Test>::get(7), Some(contract_id.clone()));
+
+		// The class can not be deregistered while it has NFTs minted for bridged tokens.
+		NftTokenIds::<Test>::insert(7, 0, b"token-0".to_vec());
+		assert_noop!(
+			OctopusAppchain::deregister_nft_class(Origin::root(), contract_id.clone()),
+			Error::<Test>::NftClassNotEmpty
+		);
+		NftTokenIds::<Test>::remove(7, 0);
+
+		assert_ok!(OctopusAppchain::deregister_nft_class(Origin::root(), contract_id.clone()));
+		assert_eq!(ClassIdByContractId::<Test>::get(&contract_id), None);
+		assert_eq!(ContractIdByClassId::<Test>::get(7), None);
+		assert_noop!(
+			OctopusAppchain::deregister_nft_class(Origin::root(), contract_id),
+			Error::<Test>::ContractIdNotRegistered
+		);
+	});
+}
+
+#[test]
+fn test_bridge_nep171_nft() {
+	let bob: AccountId = AccountKeyring::Bob.into();
+	let ferdie: AccountId = AccountKeyring::Ferdie.into();
+	let metadata = TokenMetadata {
+		title: Some("Octopus #1".to_string()),
+		media: Some("https://oct.network/1.png".to_string()),
+		copies: Some(1),
+		..Default::default()
+	};
+	let lock_nft = |index, contract_id: &[u8]| {
+		Observation::LockNft(LockNftEvent {
+			index,
+			contract_id: contract_id.to_vec(),
+			token_id: b"1".to_vec(),
+			sender_id: "andy-pallet-test.testnet".to_string().as_bytes().to_vec(),
+			receiver: AccountKeyring::Bob.into(),
+			metadata: metadata.clone(),
+		})
+	};
+
+	new_tester().execute_with(|| {
+		assert_ok!(Uniques::force_create(
+			Origin::root(),
+			7,
+			sp_runtime::MultiAddress::Id(OctopusAppchain::pallet_account()),
+			true
+		));
+
+		// The contract is not registered.
+		assert_eq!(
+			OctopusAppchain::execute_notification(&lock_nft(0, b"nft.testnet")),
+			Err(NotificationResult::NftMintFailed)
+		);
+
+		assert_ok!(OctopusAppchain::register_nft_class(Origin::root(), b"nft.testnet".to_vec(), 7));
+		assert_ok!(OctopusAppchain::execute_notification(&lock_nft(0, b"nft.testnet")));
+		let instance_id = OctopusAppchain::nft_instance_id(b"1");
+		assert_eq!(nft_owner(7, instance_id), Some(bob.clone()));
+		assert_eq!(NftTokenIds::<Test>::get(7, instance_id), Some(b"1".to_vec()));
+		assert_eq!(NftMetadata::<Test>::get(7, instance_id), Some(metadata.clone()));
+		assert!(System::events().iter().any(|record| record.event
+			== Event::OctopusAppchain(crate::Event::NftMinted(
+				7,
+				instance_id,
+				"andy-pallet-test.testnet".to_string().as_bytes().to_vec(),
+				bob.clone()
+			))));
+
+		// The same token can not be minted twice.
+		assert_eq!(
+			OctopusAppchain::execute_notification(&lock_nft(1, b"nft.testnet")),
+			Err(NotificationResult::NftMintFailed)
+		);
+
+		assert_ok!(OctopusAppchain::force_set_is_activated(Origin::root(), true));
+		assert_noop!(
+			OctopusAppchain::burn_nft(
+				Origin::signed(ferdie),
+				7,
+				instance_id,
+				"test-account.testnet".to_string().as_bytes().to_vec()
+			),
+			Error::<Test>::NotNftOwner
+		);
+		assert_noop!(
+			OctopusAppchain::burn_nft(
+				Origin::signed(bob.clone()),
+				7,
+				instance_id.wrapping_add(1),
+				"test-account.testnet".to_string().as_bytes().to_vec()
+			),
+			Error::<Test>::UnknownNftInstance
+		);
+		assert_ok!(OctopusAppchain::burn_nft(
+			Origin::signed(bob.clone()),
+			7,
+			instance_id,
+			"test-account.testnet".to_string().as_bytes().to_vec()
+		));
+		assert_eq!(nft_owner(7, instance_id), None);
+		assert_eq!(NftTokenIds::<Test>::get(7, instance_id), None);
+		assert_eq!(NftMetadata::<Test>::get(7, instance_id), None);
+		assert!(System::events().iter().any(|record| record.event
+			== Event::OctopusAppchain(crate::Event::NftBurned(
+				7,
+				instance_id,
+				bob.clone(),
+				"test-account.testnet".to_string().as_bytes().to_vec()
+			))));
+
+		// A burnt token can be bridged again.
+		assert_ok!(OctopusAppchain::execute_notification(&lock_nft(2, b"nft.testnet")));
+		assert_eq!(nft_owner(7, instance_id), Some(bob));
+	});
+}
+
+#[test]
+fn test_lock_and_unlock_native_nft() {
+	let alice: AccountId = AccountKeyring::Alice.into();
+	let bob: AccountId = AccountKeyring::Bob.into();
+	let burn_nft = |index| {
+		Observation::BurnNft(BurnNftEvent {
+			index,
+			class: 8,
+			instance: 0,
+			sender_id: "andy-pallet-test.testnet".to_string().as_bytes().to_vec(),
+			receiver: AccountKeyring::Bob.into(),
+		})
+	};
+
+	new_tester().execute_with(|| {
+		assert_ok!(Uniques::force_create(
+			Origin::root(),
+			8,
+			sp_runtime::MultiAddress::Id(alice.clone()),
+			true
+		));
+		assert_ok!(Uniques::mint(
+			Origin::signed(alice.clone()),
+			8,
+			0,
+			sp_runtime::MultiAddress::Id(alice.clone())
+		));
+		assert_ok!(OctopusAppchain::force_set_is_activated(Origin::root(), true));
+
+		assert_noop!(
+			OctopusAppchain::lock_nft(
+				Origin::signed(bob.clone()),
+				8,
+				0,
+				"test-account.testnet".to_string().as_bytes().to_vec()
+			),
+			Error::<Test>::NotNftOwner
+		);
+		assert_ok!(OctopusAppchain::register_nft_class(Origin::root(), b"nft.testnet".to_vec(), 8));
+		assert_noop!(
+			OctopusAppchain::lock_nft(
+				Origin::signed(alice.clone()),
+				8,
+				0,
+				"test-account.testnet".to_string().as_bytes().to_vec()
+			),
+			Error::<Test>::NotNativeNft
+		);
+		assert_ok!(OctopusAppchain::deregister_nft_class(Origin::root(), b"nft.testnet".to_vec()));
+
+		assert_ok!(OctopusAppchain::lock_nft(
+			Origin::signed(alice.clone()),
+			8,
+			0,
+			"test-account.testnet".to_string().as_bytes().to_vec()
+		));
+		assert_eq!(nft_owner(8, 0), Some(OctopusAppchain::pallet_account()));
+		assert!(System::events().iter().any(|record| record.event
+			== Event::OctopusAppchain(crate::Event::NftLocked(
+				8,
+				0,
+				alice.clone(),
+				"test-account.testnet".to_string().as_bytes().to_vec()
+			))));
+
+		assert_ok!(OctopusAppchain::execute_notification(&burn_nft(0)));
+		assert_eq!(nft_owner(8, 0), Some(bob.clone()));
+
+		// The NFT is no longer in escrow.
+		assert_eq!(
+			OctopusAppchain::execute_notification(&burn_nft(1)),
+			Err(NotificationResult::NftUnlockFailed)
+		);
+		OctopusAppchain::record_notification(1, &burn_nft(1), NotificationResult::NftUnlockFailed);
+		let records = OctopusAppchain::notifications_of(&bob);
+		let transfer = records[0].1.transfer.clone().unwrap();
+		assert_eq!(transfer.nft_instance, Some((8, 0)));
+		assert_eq!(transfer.amount, 1);
+	});
+}
+
+#[test]
+fn test_dispatch_mainchain_call() {
+	let governance_call = |index, call: mock::Call| {
+		Observation::GovernanceCall(GovernanceCallEvent { index, call: call.encode() })
+	};
+
+	new_tester().execute_with(|| {
+		System::set_block_number(1);
+		assert_eq!(OctopusAppchain::is_activated(), false);
+		let activate = governance_call(
+			0,
+			mock::Call::OctopusAppchain(crate::Call::force_set_is_activated { is_activated: true }),
+		);
+		assert_ok!(OctopusAppchain::execute_notification(&activate));
+		assert_eq!(OctopusAppchain::is_activated(), true);
+		assert!(System::events().iter().any(|record| record.event
+			== Event::OctopusAppchain(crate::Event::MainchainCallDispatched(0, Ok(())))));
+
+		// Only the whitelisted calls are dispatched.
+		let set_next_set_id = governance_call(
+			1,
+			mock::Call::OctopusAppchain(crate::Call::force_set_next_set_id { next_set_id: 5 }),
+		);
+		assert_eq!(
+			OctopusAppchain::execute_notification(&set_next_set_id),
+			Err(NotificationResult::CallRejected)
+		);
+		assert_eq!(OctopusAppchain::next_set_id(), 0);
+		assert!(System::events()
+			.iter()
+			.any(|record| record.event
+				== Event::OctopusAppchain(crate::Event::MainchainCallRejected(1))));
+
+		let invalid =
+			Observation::GovernanceCall(GovernanceCallEvent { index: 2, call: vec![255] });
+		assert_eq!(
+			OctopusAppchain::execute_notification(&invalid),
+			Err(NotificationResult::CallRejected)
+		);
+		// A call must be decoded from all of its bytes.
+		let mut call = mock::Call::OctopusAppchain(crate::Call::force_set_is_activated {
+			is_activated: false,
+		})
+		.encode();
+		call.push(0);
+		let trailing = Observation::GovernanceCall(GovernanceCallEvent { index: 3, call });
+		assert_eq!(
+			OctopusAppchain::execute_notification(&trailing),
+			Err(NotificationResult::CallRejected)
+		);
+		assert_eq!(OctopusAppchain::is_activated(), true);
+
+		// The mainchain origin is not a signed origin.
+		assert_noop!(
+			OctopusAppchain::lock(
+				MainchainOrigin::Mainchain.into(),
+				"test-account.testnet".to_string().as_bytes().to_vec(),
+				100
+			),
+			BadOrigin
+		);
+		assert_ok!(OctopusAppchain::force_set_is_activated(
+			MainchainOrigin::Mainchain.into(),
+			false
+		));
+		assert_eq!(OctopusAppchain::is_activated(), false);
+	});
+}
+
+#[test]
+fn test_appchain_state_follows_anchor_contract() {
+	let alice: AccountId = AccountKeyring::Alice.into();
+	let bob: AccountId = AccountKeyring::Bob.into();
+	let stash: Balance = 100 * 1_000_000_000_000_000_000; // 100 OCT with 18 decimals
+	let validators = vec![(alice.clone(), stash), (bob.clone(), stash)];
+	let state_change =
+		|index, state| Observation::UpdateAppchainState(AppchainStateChange { index, state });
+	let burn = Observation::Burn(BurnEvent {
+		index: 0,
+		sender_id: "andy-pallet-test.testnet".to_string().as_bytes().to_vec(),
+		receiver: AccountKeyring::Ferdie.into(),
+		amount: 10 * DOLLARS,
+	});
+
+	new_tester().execute_with(|| {
+		OctopusLpos::trigger_new_era(1, validators);
+		advance_session();
+		assert_eq!(OctopusAppchain::appchain_state(), AppchainState::Booting);
+
+		assert_eq!(
+			OctopusAppchain::submit_observation(&alice, state_change(0, AppchainState::Frozen)),
+			Ok(1)
+		);
+		// The state changed on NEAR, the latest vote replaces the previous one.
+		assert_eq!(
+			OctopusAppchain::submit_observation(&alice, state_change(0, AppchainState::Active)),
+			Ok(1)
+		);
+		let frozen = OctopusAppchain::observation_hash(&state_change(0, AppchainState::Frozen));
+		assert!(OctopusAppchain::voters_of(&frozen).is_empty());
+		assert!(OffenceHandler::offences().is_empty());
+		assert_eq!(
+			OctopusAppchain::submit_observation(&bob, state_change(0, AppchainState::Active)),
+			Ok(1)
+		);
+		assert_eq!(OctopusAppchain::appchain_state(), AppchainState::Active);
+		assert!(OctopusAppchain::is_activated());
+		assert_eq!(NextStateChangeId::<Test>::get(), 1);
+		assert!(!Observations::<Test>::contains_key(ObservationType::UpdateAppchainState, 0));
+		assert!(System::events().iter().any(|record| record.event
+			== Event::OctopusAppchain(crate::Event::AppchainStateChanged(
+				AppchainState::Booting,
+				AppchainState::Active
+			))));
+		assert!(OctopusAppchain::submit_observation(
+			&alice,
+			state_change(0, AppchainState::Frozen)
+		)
+		.is_err());
+
+		// Freezing the appchain halts the transfers.
+		OctopusAppchain::submit_observation(&alice, state_change(1, AppchainState::Frozen))
+			.unwrap();
+		OctopusAppchain::submit_observation(&bob, state_change(1, AppchainState::Frozen)).unwrap();
+		assert_eq!(OctopusAppchain::appchain_state(), AppchainState::Frozen);
+		assert!(!OctopusAppchain::is_activated());
+		assert_noop!(
+			OctopusAppchain::lock(
+				Origin::signed(alice.clone()),
+				"test-account.testnet".to_string().as_bytes().to_vec(),
+				100
+			),
+			Error::<Test>::NotActivated
+		);
+		assert_eq!(
+			OctopusAppchain::execute_notification(&burn),
+			Err(NotificationResult::OperationPaused)
+		);
+		FailedNotifications::<Test>::insert(0, (burn.clone(), 0));
+		<OctopusAppchain as Hooks<BlockNumber>>::on_idle(1, Weight::max_value());
+		assert_eq!(FailedNotifications::<Test>::get(0), Some((burn.clone(), 0)));
+
+		assert_noop!(
+			OctopusAppchain::force_set_appchain_state(
+				Origin::signed(alice.clone()),
+				AppchainState::Active
+			),
+			BadOrigin
+		);
+		assert_ok!(OctopusAppchain::force_set_appchain_state(
+			Origin::root(),
+			AppchainState::Active
+		));
+		// The override holds until the state of the anchor contract changes.
+		assert_eq!(OctopusAppchain::observed_state_change(AppchainState::Frozen), None);
+		assert_eq!(
+			OctopusAppchain::observed_state_change(AppchainState::Active),
+			Some(AppchainStateChange { index: 2, state: AppchainState::Active })
+		);
+		<OctopusAppchain as Hooks<BlockNumber>>::on_idle(1, Weight::max_value());
+		assert!(!FailedNotifications::<Test>::contains_key(0));
+		assert_eq!(Balances::free_balance(&AccountKeyring::Ferdie.into()), 10 * DOLLARS);
+	});
+}
+
+#[test]
+fn test_observing_appchain_state() {
+	let (offchain, _) = testing::TestOffchainExt::new();
+	let (pool, pool_state) = testing::TestTransactionPoolExt::new();
+	let keystore = KeyStore::new();
+	let public_key =
+		SyncCryptoStore::sr25519_generate_new(&keystore, crate::crypto::Public::ID, None).unwrap();
+
+	let mut t = new_tester();
+	t.register_extension(OffchainWorkerExt::new(offchain));
+	t.register_extension(TransactionPoolExt::new(pool));
+	t.register_extension(KeystoreExt(Arc::new(keystore)));
+
+	MockMainchainClient::mutate(|mainchain| {
+		mainchain.notifications.push(expected_burn_notify());
+		mainchain.appchain_state = AppchainState::Frozen;
+	});
+
+	let public = <Test as SigningTypes>::Public::from(public_key);
+	let account = public.clone().into_account();
+
+	t.execute_with(|| {
+		assert_ok!(OctopusAppchain::force_set_is_activated(Origin::root(), true));
+		OctopusAppchain::observing_mainchain(
+			2,
+			&["https://rpc.testnet.near.org".to_string()],
+			b"oct-test.testnet".to_vec(),
+			public,
+			account,
+		)
+		.unwrap();
+
+		// Only the new state is submitted, the notifications of a frozen appchain are not.
+		let tx = pool_state.write().transactions.pop().unwrap();
+		let tx = Extrinsic::decode(&mut &*tx).unwrap();
+		if let mock::Call::OctopusAppchain(crate::Call::submit_observations { payload, .. }) =
+			tx.call
+		{
+			assert_eq!(
+				payload.observations,
+				vec![Observation::UpdateAppchainState(AppchainStateChange {
+					index: 0,
+					state: AppchainState::Frozen
+				})]
+			);
+		} else {
+			panic!("unexpected call: {:?}", tx.call);
+		}
+	});
+}
+
+#[test]
+fn test_encode_args_works() {
+	let test_get_validators_data = vec![
+		(0u32, Some(b"eyJlcmFfbnVtYmVyIjoiMCJ9".to_vec())),
+		(4294967295u32, Some(b"eyJlcmFfbnVtYmVyIjoiNDI5NDk2NzI5NSJ9".to_vec())),
+	];
+
+	for (set_id, expected) in test_get_validators_data {
+		assert_eq!(expected, NearMainchainClient::<Test>::encode_get_validator_args(set_id));
+	}
+
+	let test_get_notify_data = vec![
+		(0u32, 0u32, Some(b"eyJzdGFydF9pbmRleCI6IjAiLCJxdWFudGl0eSI6IjAifQ==".to_vec())),
+		(
+			4294967295u32,
+			4294967295u32,
+			Some(
+				b"eyJzdGFydF9pbmRleCI6IjQyOTQ5NjcyOTUiLCJxdWFudGl0eSI6IjQyOTQ5NjcyOTUifQ=="
+					.to_vec(),
+			),
+		),
+	];
+
+	for (start, limit, expected) in test_get_notify_data {
+		assert_eq!(
+			expected,
+			NearMainchainClient::<Test>::encode_get_notification_args(start, limit)
+		);
+	}
+}
+
+fn expected_val_set() -> Observation<AccountId> {
+	Observation::UpdateValidatorSet(expected_validator_set())
+}
+
+fn expected_validator_set() -> ValidatorSet<AccountId> {
+	let id = hex::decode("d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d")
+		.map(|b| AccountId::decode(&mut &b[..]))
+		.unwrap()
+		.unwrap();
+	let alice = Validator { validator_id_in_appchain: id, total_stake: 10000000000 };
+
+	let id = hex::decode("8eaf04151687736326c9fea17e25fc5287613693c912909cb226aa4794f26a48")
+		.map(|b| AccountId::decode(&mut &b[..]))
+		.unwrap()
+		.unwrap();
+	let bob = Validator { validator_id_in_appchain: id, total_stake: 10000000000 };
+
+	let id = hex::decode("90b5ab205c6974c9ea841be688864633dc9ca8a357843eeacf2314649965fe22")
+		.map(|b| AccountId::decode(&mut &b[..]))
+		.unwrap()
+		.unwrap();
+	let charlie = Validator { validator_id_in_appchain: id, total_stake: 100000000000 };
+
+	let id = hex::decode("306721211d5404bd9da88e0204360a1a9ab8b87c66c1bc2fcdd37f3c2222cc20")
+		.map(|b| AccountId::decode(&mut &b[..]))
+		.unwrap()
+		.unwrap();
+	let dave = Validator { validator_id_in_appchain: id, total_stake: 10000000000 };
+
+	ValidatorSet { set_id: 1, validators: vec![alice, bob, charlie, dave] }
+}
+
+fn validator_set_1_response(state: &mut testing::OffchainState) {
+	state.expect_request(testing::PendingRequest {
+		method: "POST".into(),
+		uri: "https://rpc.testnet.near.org".into(),
+		headers: vec![("Content-Type".into(), "application/json".into())],
+		body: br#"
+		{
+			"jsonrpc": "2.0",
+			"id": "dontcare",
+			"method": "query",
+			"params": {
+				"request_type": "call_function",
+				"finality": "final",
+				"account_id": "oct-test.testnet",
+				"method_name": "get_validator_list_of",
+				"args_base64": "eyJlcmFfbnVtYmVyIjoiMSJ9"
+			}
+		}"#.to_vec(),
+		response: Some(br#"
+		{
+			"jsonrpc": "2.0",
+			"result": {
+				"block_hash": "EczErquQLMpUvTQpKupoQp5yNkgNbniMSHq1gVvhAf84",
+				"block_height": 1,
+				"logs": [],
+		 		"result": [
+					91,123,34,118,97,108,105,100,97,116,111,114,95,105,100,95,105,110,95,97,112,112,99,104,97,105,110,34,58,34,48,120,100,52,51,53,57,51,99,55,49,53,102,100,100,51,49,99,54,49,49,52,49,97,98,100,48,52,97,57,57,102,100,54,56,50,50,99,56,53,53,56,56,53,52,99,99,100,101,51,57,97,53,54,56,52,101,55,97,53,54,100,97,50,55,100,34,44,34,116,111,116,97,108,95,115,116,97,107,101,34,58,34,49,48,48,48,48,48,48,48,48,48,48,34,125,44,123,34,118,97,108,105,100,97,116,111,114,95,105,100,95,105,110,95,97,112,
+					112,99,104,97,105,110,34,58,34,48,120,56,101,97,102,48,52,49,53,49,54,56,55,55,51,54,51,50,54,99,57,102,101,97,49,55,101,50,53,102,99,53,50,56,55,54,49,51,54,57,51,99,57,49,50,57,48,57,99,98,50,50,54,97,97,52,55,57,52,102,50,54,97,52,56,34,44,34,116,111,116,97,108,95,115,116,97,107,101,34,58,34,49,48,48,48,48,48,48,48,48,48,48,34,125,44,123,34,118,97,108,105,100,97,116,111,114,95,105,100,95,105,110,95,97,112,112,99,104,97,105,110,34,58,34,48,120,57,48,98,53,97,98,50,48,53,99,54,57,55,52,99,
+					57,101,97,56,52,49,98,101,54,56,56,56,54,52,54,51,51,100,99,57,99,97,56,97,51,53,55,56,52,51,101,101,97,99,102,50,51,49,52,54,52,57,57,54,53,102,101,50,50,34,44,34,116,111,116,97,108,95,115,116,97,107,101,34,58,34,49,48,48,48,48,48,48,48,48,48,48,48,34,125,44,123,34,118,97,108,105,100,97,116,111,114,95,105,100,95,105,110,95,97,112,112,99,104,97,105,110,34,58,34,48,120,51,48,54,55,50,49,50,49,49,100,53,52,48,52,98,100,57,100,97,56,56,101,48,50,48,52,51,54,48,97,49,97,57,97,98,56,98,56,55,99,
+					54,54,99,49,98,99,50,102,99,100,100,51,55,102,51,99,50,50,50,50,99,99,50,48,34,44,34,116,111,116,97,108,95,115,116,97,107,101,34,58,34,49,48,48,48,48,48,48,48,48,48,48,34,125,93
+				]
+			},
+			"id": "dontcare"
+		}
+			"#.to_vec()),
+		sent: true,
+		..Default::default()
+	});
+}
+
+fn empty_validator_set_1_response(state: &mut testing::OffchainState) {
+	state.expect_request(testing::PendingRequest {
+		method: "POST".into(),
 		uri: "https://rpc.testnet.near.org".into(),
 		headers: vec![("Content-Type".into(), "application/json".into())],
 		body: br#"
@@ -412,6 +2294,120 @@ fn empty_validator_set_1_response(state: &mut testing::OffchainState) {
 	});
 }
 
+fn appchain_state_response_from(state: &mut testing::OffchainState, uri: &str) {
+	state.expect_request(testing::PendingRequest {
+		method: "POST".into(),
+		uri: uri.into(),
+		headers: vec![("Content-Type".into(), "application/json".into())],
+		body: br#"
+		{
+			"jsonrpc": "2.0",
+			"id": "dontcare",
+			"method": "query",
+			"params": {
+				"request_type": "call_function",
+				"finality": "final",
+				"account_id": "oct-test.testnet",
+				"method_name": "get_appchain_state",
+				"args_base64": "e30="
+			}
+		}"#
+		.to_vec(),
+		response: Some(
+			br#"
+		{
+			"jsonrpc": "2.0",
+			"result": {
+				"block_hash": "EczErquQLMpUvTQpKupoQp5yNkgNbniMSHq1gVvhAf84",
+				"block_height": 1,
+				"logs": [],
+				"result": [34,65,99,116,105,118,101,34]
+			},
+			"id": "dontcare"
+		}
+			"#
+			.to_vec(),
+		),
+		sent: true,
+		..Default::default()
+	});
+}
+
+fn broken_appchain_state_response(state: &mut testing::OffchainState, uri: &str) {
+	state.expect_request(testing::PendingRequest {
+		method: "POST".into(),
+		uri: uri.into(),
+		headers: vec![("Content-Type".into(), "application/json".into())],
+		body: br#"
+		{
+			"jsonrpc": "2.0",
+			"id": "dontcare",
+			"method": "query",
+			"params": {
+				"request_type": "call_function",
+				"finality": "final",
+				"account_id": "oct-test.testnet",
+				"method_name": "get_appchain_state",
+				"args_base64": "e30="
+			}
+		}"#
+		.to_vec(),
+		response: Some(b"502 Bad Gateway".to_vec()),
+		sent: true,
+		..Default::default()
+	});
+}
+
+fn broken_validator_set_1_response(state: &mut testing::OffchainState, uri: &str) {
+	state.expect_request(testing::PendingRequest {
+		method: "POST".into(),
+		uri: uri.into(),
+		headers: vec![("Content-Type".into(), "application/json".into())],
+		body: br#"
+		{
+			"jsonrpc": "2.0",
+			"id": "dontcare",
+			"method": "query",
+			"params": {
+				"request_type": "call_function",
+				"finality": "final",
+				"account_id": "oct-test.testnet",
+				"method_name": "get_validator_list_of",
+				"args_base64": "eyJlcmFfbnVtYmVyIjoiMSJ9"
+			}
+		}"#
+		.to_vec(),
+		response: Some(b"502 Bad Gateway".to_vec()),
+		sent: true,
+		..Default::default()
+	});
+}
+
+fn broken_burn_notify_response(state: &mut testing::OffchainState, uri: &str) {
+	state.expect_request(testing::PendingRequest {
+		method: "POST".into(),
+		uri: uri.into(),
+		headers: vec![("Content-Type".into(), "application/json".into())],
+		body: br#"
+		{
+			"jsonrpc": "2.0",
+			"id": "dontcare",
+			"method": "query",
+			"params": {
+				"request_type": "call_function",
+				"finality": "final",
+				"account_id": "oct-test.testnet",
+				"method_name": "get_appchain_notification_histories",
+				"args_base64": "eyJzdGFydF9pbmRleCI6IjAiLCJxdWFudGl0eSI6IjEwIn0="
+			}
+		}"#
+		.to_vec(),
+		response: Some(b"502 Bad Gateway".to_vec()),
+		sent: true,
+		..Default::default()
+	});
+}
+
 fn expected_burn_notify() -> Observation<AccountId> {
 	let receiver = hex::decode("94f135526ec5fe830e0cbc6fd58683cb2d9ee06522cd9a2c0481268c5c73674f")
 		.map(|b| AccountId::decode(&mut &b[..]))
@@ -427,9 +2423,13 @@ fn expected_burn_notify() -> Observation<AccountId> {
 }
 
 fn burn_notify_response(state: &mut testing::OffchainState) {
+	burn_notify_response_from(state, "https://rpc.testnet.near.org");
+}
+
+fn burn_notify_response_from(state: &mut testing::OffchainState, uri: &str) {
 	state.expect_request(testing::PendingRequest {
 		method: "POST".into(),
-		uri: "https://rpc.testnet.near.org".into(),
+		uri: uri.into(),
 		headers: vec![("Content-Type".into(), "application/json".into())],
 		body: br#"
 		{
@@ -465,10 +2465,60 @@ fn burn_notify_response(state: &mut testing::OffchainState) {
 	});
 }
 
+fn anchor_status_response(state: &mut testing::OffchainState) {
+	state.expect_request(testing::PendingRequest {
+		method: "POST".into(),
+		uri: "https://rpc.testnet.near.org".into(),
+		headers: vec![("Content-Type".into(), "application/json".into())],
+		body: br#"
+		{
+			"jsonrpc": "2.0",
+			"id": "dontcare",
+			"method": "query",
+			"params": {
+				"request_type": "call_function",
+				"finality": "final",
+				"account_id": "oct-test.testnet",
+				"method_name": "get_anchor_status",
+				"args_base64": "e30="
+			}
+		}"#
+		.to_vec(),
+		response: Some(
+			br#"
+		{
+			"jsonrpc": "2.0",
+			"result": {
+				"block_hash": "EczErquQLMpUvTQpKupoQp5yNkgNbniMSHq1gVvhAf84",
+				"block_height": 1,
+				"logs": [],
+				"result": [
+					123,34,116,111,116,97,108,95,115,116,97,107,101,95,105,110,95,110,101,120,116,95,101,114,97,
+					34,58,34,48,34,44,34,118,97,108,105,100,97,116,111,114,95,99,111,117,110,116,95,105,110,95,110,
+					101,120,116,95,101,114,97,34,58,34,52,34,44,34,105,110,100,101,120,95,114,97,110,103,101,95,
+					111,102,95,97,112,112,99,104,97,105,110,95,110,111,116,105,102,105,99,97,116,105,111,110,95,
+					104,105,115,116,111,114,121,34,58,123,34,115,116,97,114,116,95,105,110,100,101,120,34,58,34,
+					48,34,44,34,101,110,100,95,105,110,100,101,120,34,58,34,57,34,125,44,34,105,110,100,101,120,
+					95,114,97,110,103,101,95,111,102,95,118,97,108,105,100,97,116,111,114,95,115,101,116,95,104,
+					105,115,116,111,114,121,34,58,123,34,115,116,97,114,116,95,105,110,100,101,120,34,58,34,48,34,
+					44,34,101,110,100,95,105,110,100,101,120,34,58,34,49,34,125,125
+				]
+			},
+			"id": "dontcare"
+		}
+			"#
+			.to_vec(),
+		),
+		sent: true,
+		..Default::default()
+	});
+}
+
 #[test]
 fn test_make_http_call_and_parse_result() {
 	let (offchain, state) = testing::TestOffchainExt::new();
 	let mut t = new_tester();
+	t.register_extension(OffchainDbExt::new(offchain.clone()));
 	t.register_extension(OffchainWorkerExt::new(offchain));
 
 	validator_set_1_response(&mut state.write());
@@ -477,47 +2527,82 @@ fn test_make_http_call_and_parse_result() {
 	burn_notify_response(&mut state.write());
 
 	t.execute_with(|| {
-		let val_set = OctopusAppchain::get_validator_list_of(
-			"https://rpc.testnet.near.org",
-			b"oct-test.testnet".to_vec(),
-			1,
-		)
-		.ok();
-		assert_eq!(val_set, Some(vec![expected_val_set()]));
+		let endpoints = vec!["https://rpc.testnet.near.org".to_string()];
 
-		let burn_notify = OctopusAppchain::get_appchain_notification_histories(
-			"https://rpc.testnet.near.org",
-			b"oct-test.testnet".to_vec(),
+		let val_set =
+			NearMainchainClient::<Test>::validator_set(&endpoints, b"oct-test.testnet", 1);
+		assert_eq!(val_set, Ok(Some(expected_validator_set())));
+
+		let burn_notify = NearMainchainClient::<Test>::notification_histories(
+			&endpoints,
+			b"oct-test.testnet",
 			0,
 			10,
-		)
-		.ok();
-		assert_eq!(burn_notify, Some(vec![expected_burn_notify()]));
+		);
+		assert_eq!(burn_notify, Ok(vec![expected_burn_notify()]));
 
-		let val_set = OctopusAppchain::get_validator_list_of(
-			"https://rpc.testnet.near.org",
-			b"oct-test.testnet".to_vec(),
-			1,
-		)
-		.ok();
-		assert_eq!(val_set, Some(vec![expected_val_set()]));
+		let val_set =
+			NearMainchainClient::<Test>::validator_set(&endpoints, b"oct-test.testnet", 1);
+		assert_eq!(val_set, Ok(Some(expected_validator_set())));
 
-		let burn_notify = OctopusAppchain::get_appchain_notification_histories(
-			"https://rpc.testnet.near.org",
-			b"oct-test.testnet".to_vec(),
+		let burn_notify = NearMainchainClient::<Test>::notification_histories(
+			&endpoints,
+			b"oct-test.testnet",
 			0,
 			10,
-		)
-		.ok();
-		assert_eq!(burn_notify, Some(vec![expected_burn_notify()]));
+		);
+		assert_eq!(burn_notify, Ok(vec![expected_burn_notify()]));
+	});
+}
+
+#[test]
+fn test_parse_empty_validator_set_and_anchor_status() {
+	let (offchain, state) = testing::TestOffchainExt::new();
+	let mut t = new_tester();
+	t.register_extension(OffchainDbExt::new(offchain.clone()));
+	t.register_extension(OffchainWorkerExt::new(offchain));
+
+	empty_validator_set_1_response(&mut state.write());
+	anchor_status_response(&mut state.write());
+
+	t.execute_with(|| {
+		let endpoints = vec!["https://rpc.testnet.near.org".to_string()];
+
+		let val_set =
+			NearMainchainClient::<Test>::validator_set(&endpoints, b"oct-test.testnet", 1);
+		assert_eq!(val_set, Ok(None));
+
+		let anchor_status =
+			NearMainchainClient::<Test>::anchor_status(&endpoints, b"oct-test.testnet");
+		assert_eq!(
+			anchor_status,
+			Ok(AnchorStatus {
+				validator_set_history: IndexRange { start_index: 0, end_index: 1 },
+				notification_history: IndexRange { start_index: 0, end_index: 9 },
+			})
+		);
 	});
 }
 
+#[test]
+fn test_parse_appchain_state() {
+	let parse = |state: &str| {
+		NearMainchainClient::<Test>::parse_appchain_state(format!("{:?}", state).as_bytes())
+	};
+	assert_eq!(parse("Staging"), Ok(Some(AppchainState::Booting)));
+	assert_eq!(parse("Active"), Ok(Some(AppchainState::Active)));
+	assert_eq!(parse("Frozen"), Ok(Some(AppchainState::Frozen)));
+	assert_eq!(parse("Dead"), Ok(Some(AppchainState::Broken)));
+	// An unknown state is not a failure of the endpoint.
+	assert_eq!(parse("Migrating"), Ok(None));
+	assert!(NearMainchainClient::<Test>::parse_appchain_state(b"502 Bad Gateway").is_err());
+}
+
 #[test]
 fn test_submit_validator_sets_on_chain() {
 	const PHRASE: &str =
 		"news slush supreme milk chapter athlete soap sausage put clutch what kitten";
-	let (offchain, offchain_state) = testing::TestOffchainExt::new();
+	let (offchain, _) = testing::TestOffchainExt::new();
 	let (pool, pool_state) = testing::TestTransactionPoolExt::new();
 
 	let keystore = KeyStore::new();
@@ -535,11 +2620,15 @@ fn test_submit_validator_sets_on_chain() {
 		.clone();
 
 	let mut t = new_tester();
+	t.register_extension(OffchainDbExt::new(offchain.clone()));
 	t.register_extension(OffchainWorkerExt::new(offchain));
 	t.register_extension(TransactionPoolExt::new(pool));
 	t.register_extension(KeystoreExt(Arc::new(keystore)));
 
-	validator_set_1_response(&mut offchain_state.write());
+	MockMainchainClient::mutate(|mainchain| {
+		mainchain.validator_sets.push(expected_validator_set());
+		mainchain.notifications.push(expected_burn_notify());
+	});
 
 	let public = <Test as SigningTypes>::Public::from(public_key);
 	let account = public.clone().into_account();
@@ -550,10 +2639,11 @@ fn test_submit_validator_sets_on_chain() {
 	};
 
 	t.execute_with(|| {
+		assert_ok!(OctopusAppchain::force_set_is_activated(Origin::root(), true));
 		assert_ok!(OctopusAppchain::force_set_next_set_id(Origin::root(), 1));
 		OctopusAppchain::observing_mainchain(
 			2,
-			"https://rpc.testnet.near.org",
+			&["https://rpc.testnet.near.org".to_string()],
 			b"oct-test.testnet".to_vec(),
 			public,
 			account,
@@ -587,7 +2677,7 @@ fn test_submit_validator_sets_on_chain() {
 fn test_submit_notifies_on_chain() {
 	const PHRASE: &str =
 		"news slush supreme milk chapter athlete soap sausage put clutch what kitten";
-	let (offchain, offchain_state) = testing::TestOffchainExt::new();
+	let (offchain, _) = testing::TestOffchainExt::new();
 	let (pool, pool_state) = testing::TestTransactionPoolExt::new();
 
 	let keystore = KeyStore::new();
@@ -605,12 +2695,14 @@ fn test_submit_notifies_on_chain() {
 		.clone();
 
 	let mut t = new_tester();
+	t.register_extension(OffchainDbExt::new(offchain.clone()));
 	t.register_extension(OffchainWorkerExt::new(offchain));
 	t.register_extension(TransactionPoolExt::new(pool));
 	t.register_extension(KeystoreExt(Arc::new(keystore)));
 
-	empty_validator_set_1_response(&mut offchain_state.write());
-	burn_notify_response(&mut offchain_state.write());
+	MockMainchainClient::mutate(|mainchain| {
+		mainchain.notifications.push(expected_burn_notify());
+	});
 
 	let public = <Test as SigningTypes>::Public::from(public_key);
 	let account = public.clone().into_account();
@@ -621,10 +2713,11 @@ fn test_submit_notifies_on_chain() {
 	};
 
 	t.execute_with(|| {
+		assert_ok!(OctopusAppchain::force_set_is_activated(Origin::root(), true));
 		assert_ok!(OctopusAppchain::force_set_next_set_id(Origin::root(), 1));
 		OctopusAppchain::observing_mainchain(
 			2,
-			"https://rpc.testnet.near.org",
+			&["https://rpc.testnet.near.org".to_string()],
 			b"oct-test.testnet".to_vec(),
 			public,
 			account,
@@ -653,3 +2746,556 @@ fn test_submit_notifies_on_chain() {
 		}
 	});
 }
+
+fn burn_notify_of(index: u32) -> Observation<AccountId> {
+	Observation::Burn(BurnEvent {
+		index,
+		sender_id: "andy-pallet-test.testnet".to_string().as_bytes().to_vec(),
+		receiver: AccountKeyring::Ferdie.into(),
+		amount: 100000,
+	})
+}
+
+fn insert_notification_histories(count: u32) {
+	let alice: AccountId = AccountKeyring::Alice.into();
+	for index in 0..count {
+		let observation = burn_notify_of(index);
+		Observations::<Test>::insert(ObservationType::Burn, index, vec![observation.clone()]);
+		let hash = OctopusAppchain::observation_hash(&observation);
+		Observing::<Test>::insert(hash, alice.clone(), ());
+		ObservationTallies::<Test>::insert(
+			hash,
+			ObservationTally { voters: 1, ..Default::default() },
+		);
+		let record = NotificationRecord::new(&observation, 1, NotificationResult::Success);
+		NotificationHistory::<Test>::insert(index, record.unwrap());
+	}
+	NextNotificationId::<Test>::put(count);
+}
+
+/// Insert the notification histories and the voters in the format of the storage version 2.
+fn insert_v2_notification_histories(count: u32) {
+	let alice: AccountId = AccountKeyring::Alice.into();
+	for index in 0..count {
+		let observation = burn_notify_of(index);
+		Observations::<Test>::insert(ObservationType::Burn, index, vec![observation.clone()]);
+		frame_support::storage::migration::put_storage_value(
+			b"OctopusAppchain",
+			b"Observing",
+			&observation.twox_64_concat(),
+			vec![alice.clone()],
+		);
+		frame_support::storage::unhashed::put(
+			&NotificationHistory::<Test>::hashed_key_for(index),
+			&NotificationResult::Success,
+		);
+	}
+	NextNotificationId::<Test>::put(count);
+}
+
+#[test]
+fn test_prune_old_histories() {
+	let observed = |index| {
+		!OctopusAppchain::voters_of(&OctopusAppchain::observation_hash(&burn_notify_of(index)))
+			.is_empty()
+	};
+	new_tester().execute_with(|| {
+		insert_notification_histories(20);
+		NextPruneSetId::<Test>::put(1);
+
+		// The observations are kept for fewer indexes than the records, and pruned first.
+		<OctopusAppchain as OnInitialize<BlockNumber>>::on_initialize(2);
+		assert_eq!(NextPruneObservationId::<Test>::get(), 5);
+		assert_eq!(NextPruneNotificationId::<Test>::get(), 0);
+		for index in 0..5 {
+			assert!(!Observations::<Test>::contains_key(ObservationType::Burn, index));
+			assert!(!observed(index));
+			assert!(NotificationHistory::<Test>::contains_key(index));
+		}
+		assert!(observed(5));
+
+		<OctopusAppchain as OnInitialize<BlockNumber>>::on_initialize(3);
+		<OctopusAppchain as OnInitialize<BlockNumber>>::on_initialize(4);
+		assert_eq!(NextPruneObservationId::<Test>::get(), 15);
+		assert!(!Observations::<Test>::contains_key(ObservationType::Burn, 14));
+		assert!(observed(15));
+
+		<OctopusAppchain as OnInitialize<BlockNumber>>::on_initialize(5);
+		assert_eq!(NextPruneNotificationId::<Test>::get(), 5);
+		assert!(!NotificationHistory::<Test>::contains_key(4));
+		assert!(NotificationHistory::<Test>::contains_key(5));
+
+		<OctopusAppchain as OnInitialize<BlockNumber>>::on_initialize(6);
+		assert_eq!(NextPruneNotificationId::<Test>::get(), 10);
+		for index in 10..20 {
+			assert!(NotificationHistory::<Test>::contains_key(index));
+		}
+		for index in 15..20 {
+			assert!(Observations::<Test>::contains_key(ObservationType::Burn, index));
+			assert!(observed(index));
+		}
+
+		// Nothing more to prune until new notifications are processed.
+		<OctopusAppchain as OnInitialize<BlockNumber>>::on_initialize(7);
+		assert_eq!(NextPruneObservationId::<Test>::get(), 15);
+		assert_eq!(NextPruneNotificationId::<Test>::get(), 10);
+	});
+}
+
+#[test]
+fn test_prune_applied_validator_set_observations() {
+	new_tester().execute_with(|| {
+		let alice: AccountId = AccountKeyring::Alice.into();
+		let observation = expected_val_set();
+		Observations::<Test>::insert(
+			ObservationType::UpdateValidatorSet,
+			1,
+			vec![observation.clone()],
+		);
+		let hash = OctopusAppchain::observation_hash(&observation);
+		Observing::<Test>::insert(hash, alice.clone(), ());
+
+		<OctopusAppchain as OnInitialize<BlockNumber>>::on_initialize(2);
+		assert!(Observing::<Test>::contains_key(hash, &alice));
+		assert_eq!(NextPruneSetId::<Test>::get(), 1);
+
+		assert_ok!(OctopusAppchain::force_set_next_set_id(Origin::root(), 2));
+		<OctopusAppchain as OnInitialize<BlockNumber>>::on_initialize(3);
+		assert!(!Observations::<Test>::contains_key(ObservationType::UpdateValidatorSet, 1));
+		assert!(!Observing::<Test>::contains_key(hash, &alice));
+		assert_eq!(NextPruneSetId::<Test>::get(), 2);
+
+		// Lowering the next set id moves the cursor back to the ids to observe again.
+		assert_ok!(OctopusAppchain::force_set_next_set_id(Origin::root(), 1));
+		assert_eq!(NextPruneSetId::<Test>::get(), 1);
+	});
+}
+
+#[test]
+fn test_migrate_to_v1_drains_backlog() {
+	new_tester().execute_with(|| {
+		insert_v2_notification_histories(30);
+		StorageVersion::new(0).put::<OctopusAppchain>();
+
+		crate::migrations::migrate::<Test>();
+
+		assert_eq!(OctopusAppchain::on_chain_storage_version(), 5);
+		assert_eq!(NextPruneObservationId::<Test>::get(), 0);
+		assert_eq!(NextPruneNotificationId::<Test>::get(), 0);
+		assert_eq!(NextPruneSetId::<Test>::get(), 0);
+		assert_eq!(NotificationHistory::<Test>::iter_keys().count(), 30);
+		// Only the results kept by `NotificationHistoryDepth` are turned into records.
+		assert_eq!(NotificationHistory::<Test>::get(19), None);
+		assert!(NotificationHistory::<Test>::get(20).is_some());
+
+		// The backlog is drained at most `MaxPrunedPerBlock` ids at a time.
+		<OctopusAppchain as OnInitialize<BlockNumber>>::on_initialize(2);
+		assert_eq!(NextPruneObservationId::<Test>::get(), 5);
+		for block in 3..12 {
+			<OctopusAppchain as OnInitialize<BlockNumber>>::on_initialize(block);
+		}
+		assert_eq!(NextPruneObservationId::<Test>::get(), 25);
+		assert_eq!(NextPruneNotificationId::<Test>::get(), 20);
+		assert_eq!(NotificationHistory::<Test>::iter_keys().count(), 10);
+		assert_eq!(Observations::<Test>::iter_prefix(ObservationType::Burn).count(), 5);
+		assert!(!NotificationHistory::<Test>::contains_key(19));
+		assert!(NotificationHistory::<Test>::contains_key(20));
+
+		// Running the migration again is a no-op.
+		insert_notification_histories(5);
+		crate::migrations::migrate::<Test>();
+		assert!(NotificationHistory::<Test>::contains_key(0));
+	});
+}
+
+#[test]
+fn test_migrate_to_v2_indexes_assets() {
+	new_tester().execute_with(|| {
+		AssetIdByName::<Test>::insert(b"wrap.testnet".to_vec(), 3);
+		TokenIdByAssetId::<Test>::remove_all(None);
+		StorageVersion::new(1).put::<OctopusAppchain>();
+
+		crate::migrations::migrate::<Test>();
+
+		assert_eq!(OctopusAppchain::on_chain_storage_version(), 5);
+		assert_eq!(TokenIdByAssetId::<Test>::get(2), Some(b"usdc.testnet".to_vec()));
+		assert_eq!(TokenIdByAssetId::<Test>::get(3), Some(b"wrap.testnet".to_vec()));
+	});
+}
+
+#[test]
+fn test_migrate_to_v3_records_notifications() {
+	let ferdie: AccountId = AccountKeyring::Ferdie.into();
+	new_tester().execute_with(|| {
+		insert_v2_notification_histories(8);
+		Observations::<Test>::remove(ObservationType::Burn, 7);
+		StorageVersion::new(2).put::<OctopusAppchain>();
+
+		crate::migrations::migrate::<Test>();
+
+		assert_eq!(OctopusAppchain::on_chain_storage_version(), 5);
+		assert_eq!(
+			NotificationHistory::<Test>::get(0),
+			Some(NotificationRecord {
+				transfer: NotificationTransfer::new(&burn_notify_of(0)),
+				block_number: None,
+				result: NotificationResult::Success,
+			})
+		);
+		// The transfer of a notification without observation is unknown.
+		assert_eq!(
+			NotificationHistory::<Test>::get(7),
+			Some(NotificationRecord {
+				transfer: None,
+				block_number: None,
+				result: NotificationResult::Success,
+			})
+		);
+		// The index keeps the latest notifications of the receiver.
+		assert_eq!(NotificationsByReceiver::<Test>::get(&ferdie), vec![2, 3, 4, 5, 6]);
+	});
+}
+
+#[test]
+fn test_migrate_to_v4_sets_appchain_state() {
+	new_tester().execute_with(|| {
+		frame_support::storage::migration::put_storage_value(
+			b"OctopusAppchain",
+			b"IsActivated",
+			&[],
+			true,
+		);
+		StorageVersion::new(3).put::<OctopusAppchain>();
+
+		crate::migrations::migrate::<Test>();
+
+		assert_eq!(OctopusAppchain::on_chain_storage_version(), 5);
+		assert_eq!(OctopusAppchain::appchain_state(), AppchainState::Active);
+		assert_eq!(AnchorAppchainState::<Test>::get(), AppchainState::Active);
+		assert_eq!(
+			frame_support::storage::migration::get_storage_value::<bool>(
+				b"OctopusAppchain",
+				b"IsActivated",
+				&[]
+			),
+			None
+		);
+	});
+}
+
+#[test]
+fn test_migrate_to_v5_keys_observations_by_hash() {
+	let alice: AccountId = AccountKeyring::Alice.into();
+	let stash: Balance = 100 * 1_000_000_000_000_000_000; // 100 OCT with 18 decimals
+	let validators = vec![(alice.clone(), stash), (AccountKeyring::Bob.into(), stash)];
+	new_tester().execute_with(|| {
+		OctopusLpos::trigger_new_era(1, validators);
+		advance_session();
+		insert_v2_notification_histories(3);
+		NextNotificationId::<Test>::put(1);
+		StorageVersion::new(4).put::<OctopusAppchain>();
+
+		crate::migrations::migrate::<Test>();
+
+		assert_eq!(OctopusAppchain::on_chain_storage_version(), 5);
+		// Only the voters of the observations still to be observed are kept.
+		assert_eq!(Observing::<Test>::iter().count(), 2);
+		let hash = OctopusAppchain::observation_hash(&burn_notify_of(0));
+		assert!(OctopusAppchain::voters_of(&hash).is_empty());
+		let hash = OctopusAppchain::observation_hash(&burn_notify_of(1));
+		assert_eq!(OctopusAppchain::voters_of(&hash), vec![alice.clone()]);
+		assert_eq!(ObservationTallies::<Test>::get(hash).voters, 1);
+		assert_eq!(OctopusAppchain::observation_stake(&hash), stash);
+
+		// The stake of the voters is tallied on the next vote.
+		assert_ok!(OctopusAppchain::submit_observation(
+			&AccountKeyring::Bob.into(),
+			burn_notify_of(1)
+		));
+		assert_eq!(NextNotificationId::<Test>::get(), 2);
+	});
+}
+
+#[test]
+fn test_set_mainchain_rpc_endpoints() {
+	let ferdie: AccountId = AccountKeyring::Ferdie.into();
+	let endpoints = vec![
+		b"https://rpc.testnet.near.org".to_vec(),
+		b"https://archival-rpc.testnet.near.org".to_vec(),
+	];
+	new_tester().execute_with(|| {
+		assert_ok!(OctopusAppchain::set_mainchain_rpc_endpoints(Origin::root(), endpoints.clone()));
+		assert_eq!(OctopusAppchain::mainchain_rpc_endpoints(), endpoints);
+
+		assert_noop!(
+			OctopusAppchain::set_mainchain_rpc_endpoints(Origin::signed(ferdie), vec![]),
+			BadOrigin
+		);
+		assert_noop!(
+			OctopusAppchain::set_mainchain_rpc_endpoints(
+				Origin::root(),
+				vec![b"https://rpc.testnet.near.org".to_vec(); 4]
+			),
+			Error::<Test>::TooManyRpcEndpoints
+		);
+		assert_noop!(
+			OctopusAppchain::set_mainchain_rpc_endpoints(
+				Origin::root(),
+				vec![b"ftp://rpc.testnet.near.org".to_vec()]
+			),
+			Error::<Test>::InvalidRpcEndpoint
+		);
+		assert_noop!(
+			OctopusAppchain::set_mainchain_rpc_endpoints(Origin::root(), vec![vec![0, 159]]),
+			Error::<Test>::InvalidRpcEndpoint
+		);
+		let mut long_endpoint = b"https://".to_vec();
+		long_endpoint.resize(MaxRpcEndpointLength::get() as usize + 1, b'a');
+		assert_noop!(
+			OctopusAppchain::set_mainchain_rpc_endpoints(Origin::root(), vec![long_endpoint]),
+			Error::<Test>::RpcEndpointTooLong
+		);
+
+		assert_ok!(OctopusAppchain::set_mainchain_rpc_endpoints(Origin::root(), vec![]));
+		assert!(OctopusAppchain::mainchain_rpc_endpoints().is_empty());
+	});
+}
+
+#[test]
+fn test_get_mainchain_rpc_endpoints() {
+	let (offchain, _state) = testing::TestOffchainExt::new();
+	let mut t = new_tester();
+	t.register_extension(OffchainDbExt::new(offchain.clone()));
+	t.register_extension(OffchainWorkerExt::new(offchain));
+
+	t.execute_with(|| {
+		assert_eq!(
+			OctopusAppchain::get_mainchain_rpc_endpoints(true),
+			vec!["https://rpc.testnet.near.org".to_string()]
+		);
+		assert_eq!(
+			OctopusAppchain::get_mainchain_rpc_endpoints(false),
+			vec!["https://rpc.mainnet.near.org".to_string()]
+		);
+
+		assert_ok!(OctopusAppchain::set_mainchain_rpc_endpoints(
+			Origin::root(),
+			vec![
+				b"https://rpc.testnet.near.org".to_vec(),
+				b"https://archival-rpc.testnet.near.org".to_vec(),
+			],
+		));
+		assert_eq!(
+			OctopusAppchain::get_mainchain_rpc_endpoints(true),
+			vec![
+				"https://rpc.testnet.near.org".to_string(),
+				"https://archival-rpc.testnet.near.org".to_string(),
+			]
+		);
+
+		sp_io::offchain::local_storage_set(
+			StorageKind::PERSISTENT,
+			b"octopus_appchain::mainchain_rpc_endpoint",
+			b"https://archival-rpc.testnet.near.org",
+		);
+		assert_eq!(
+			OctopusAppchain::get_mainchain_rpc_endpoints(true),
+			vec![
+				"https://archival-rpc.testnet.near.org".to_string(),
+				"https://rpc.testnet.near.org".to_string(),
+			]
+		);
+	});
+}
+
+#[test]
+fn test_observing_mainchain_with_failover_endpoint() {
+	let (offchain, offchain_state) = testing::TestOffchainExt::new();
+	let mut t = new_tester();
+	t.register_extension(OffchainDbExt::new(offchain.clone()));
+	t.register_extension(OffchainWorkerExt::new(offchain));
+
+	broken_appchain_state_response(&mut offchain_state.write(), "https://rpc.broken.org");
+	broken_validator_set_1_response(&mut offchain_state.write(), "https://rpc.broken.org");
+	broken_burn_notify_response(&mut offchain_state.write(), "https://rpc.broken.org");
+	appchain_state_response_from(&mut offchain_state.write(), "https://rpc.testnet.near.org");
+	validator_set_1_response(&mut offchain_state.write());
+	burn_notify_response(&mut offchain_state.write());
+
+	t.execute_with(|| {
+		let obs = NearMainchainClient::<Test>::observations(
+			&["https://rpc.broken.org".to_string(), "https://rpc.testnet.near.org".to_string()],
+			b"oct-test.testnet",
+			1,
+			0,
+			10,
+		);
+		assert_eq!(obs, Ok((Some(AppchainState::Active), vec![expected_val_set()])));
+	});
+}
+
+#[test]
+fn test_sort_endpoints_by_health() {
+	let (offchain, offchain_state) = testing::TestOffchainExt::new();
+	let mut t = new_tester();
+	t.register_extension(OffchainDbExt::new(offchain.clone()));
+	t.register_extension(OffchainWorkerExt::new(offchain));
+
+	let a = "https://rpc-a.testnet.near.org".to_string();
+	let b = "https://rpc-b.testnet.near.org".to_string();
+	let c = "https://rpc-c.testnet.near.org".to_string();
+	let endpoints = vec![a.clone(), b.clone(), c.clone()];
+
+	offchain_state.write().timestamp = Timestamp::from_unix_millis(1_000_000);
+	t.execute_with(|| {
+		// Without any statistics the configured order is kept.
+		assert_eq!(OctopusAppchain::sort_endpoints_by_health(&endpoints), endpoints);
+
+		// `a` fails, then `b` succeeds.
+		OctopusAppchain::record_endpoint_failure(&a, 1_000_000);
+		OctopusAppchain::record_endpoint_success(&b, 200);
+		assert_eq!(OctopusAppchain::endpoint_health(&a).consecutive_failures, 1);
+		assert_eq!(OctopusAppchain::endpoint_health(&a).cool_down_until, 1_030_000);
+		assert_eq!(OctopusAppchain::endpoint_health(&b).successes, 1);
+		assert_eq!(
+			OctopusAppchain::sort_endpoints_by_health(&endpoints),
+			vec![b.clone(), c.clone(), a.clone()]
+		);
+	});
+
+	// The cool-down of `a` is over, but it is still ranked after the others.
+	offchain_state.write().timestamp = Timestamp::from_unix_millis(1_030_000);
+	t.execute_with(|| {
+		assert_eq!(
+			OctopusAppchain::sort_endpoints_by_health(&endpoints),
+			vec![b.clone(), c.clone(), a.clone()]
+		);
+
+		// Consecutive failures double the cool-down period.
+		OctopusAppchain::record_endpoint_failure(&a, 1_030_000);
+		assert_eq!(OctopusAppchain::endpoint_health(&a).consecutive_failures, 2);
+		assert_eq!(OctopusAppchain::endpoint_health(&a).cool_down_until, 1_090_000);
+	});
+
+	offchain_state.write().timestamp = Timestamp::from_unix_millis(1_090_000);
+	t.execute_with(|| {
+		// A successful query resets the cool-down of `a`.
+		OctopusAppchain::record_endpoint_success(&a, 500);
+		let health = OctopusAppchain::endpoint_health(&a);
+		assert_eq!(health.consecutive_failures, 0);
+		assert_eq!(health.cool_down_until, 0);
+		assert_eq!((health.successes, health.failures), (1, 2));
+	});
+}
+
+#[test]
+fn test_observing_mainchain_skips_cooling_down_endpoint() {
+	let (offchain, offchain_state) = testing::TestOffchainExt::new();
+	let mut t = new_tester();
+	t.register_extension(OffchainDbExt::new(offchain.clone()));
+	t.register_extension(OffchainWorkerExt::new(offchain));
+
+	let endpoints =
+		vec!["https://rpc.broken.org".to_string(), "https://rpc.testnet.near.org".to_string()];
+
+	// The first run fails over from the broken endpoint.
+	broken_appchain_state_response(&mut offchain_state.write(), "https://rpc.broken.org");
+	broken_validator_set_1_response(&mut offchain_state.write(), "https://rpc.broken.org");
+	broken_burn_notify_response(&mut offchain_state.write(), "https://rpc.broken.org");
+	appchain_state_response_from(&mut offchain_state.write(), "https://rpc.testnet.near.org");
+	validator_set_1_response(&mut offchain_state.write());
+	burn_notify_response(&mut offchain_state.write());
+	t.execute_with(|| {
+		assert_eq!(
+			NearMainchainClient::<Test>::observations(&endpoints, b"oct-test.testnet", 1, 0, 10),
+			Ok((Some(AppchainState::Active), vec![expected_val_set()]))
+		);
+	});
+
+	// The second run only queries the healthy endpoint, and not the notifications while the
+	// validator set is pending.
+	appchain_state_response_from(&mut offchain_state.write(), "https://rpc.testnet.near.org");
+	validator_set_1_response(&mut offchain_state.write());
+	t.execute_with(|| {
+		assert_eq!(
+			NearMainchainClient::<Test>::observations(&endpoints, b"oct-test.testnet", 1, 0, 10),
+			Ok((Some(AppchainState::Active), vec![expected_val_set()]))
+		);
+		assert!(OctopusAppchain::endpoint_health("https://rpc.broken.org")
+			.is_cooling_down(sp_io::offchain::timestamp().unix_millis()));
+	});
+}
+
+#[test]
+fn test_observing_mainchain_retries_failed_query_only() {
+	let (offchain, offchain_state) = testing::TestOffchainExt::new();
+	let mut t = new_tester();
+	t.register_extension(OffchainDbExt::new(offchain.clone()));
+	t.register_extension(OffchainWorkerExt::new(offchain));
+
+	let endpoints =
+		vec!["https://rpc.testnet.near.org".to_string(), "https://rpc.mirror.org".to_string()];
+
+	// All the queries are sent to the first endpoint, only the failed one is sent to the second.
+	appchain_state_response_from(&mut offchain_state.write(), "https://rpc.testnet.near.org");
+	empty_validator_set_1_response(&mut offchain_state.write());
+	broken_burn_notify_response(&mut offchain_state.write(), "https://rpc.testnet.near.org");
+	burn_notify_response_from(&mut offchain_state.write(), "https://rpc.mirror.org");
+
+	t.execute_with(|| {
+		assert_eq!(
+			NearMainchainClient::<Test>::observations(&endpoints, b"oct-test.testnet", 1, 0, 10),
+			Ok((Some(AppchainState::Active), vec![expected_burn_notify()]))
+		);
+		assert_eq!(OctopusAppchain::endpoint_health("https://rpc.testnet.near.org").failures, 1);
+		assert_eq!(OctopusAppchain::endpoint_health("https://rpc.mirror.org").successes, 1);
+	});
+}
+
+#[test]
+fn test_observing_unavailable_mainchain() {
+	const PHRASE: &str =
+		"news slush supreme milk chapter athlete soap sausage put clutch what kitten";
+	let (offchain, _) = testing::TestOffchainExt::new();
+	let (pool, pool_state) = testing::TestTransactionPoolExt::new();
+
+	let keystore = KeyStore::new();
+	SyncCryptoStore::sr25519_generate_new(
+		&keystore,
+		crate::crypto::Public::ID,
+		Some(&format!("{}/hunter1", PHRASE)),
+	)
+	.unwrap();
+	let public_key = SyncCryptoStore::sr25519_public_keys(&keystore, crate::crypto::Public::ID)
+		.get(0)
+		.unwrap()
+		.clone();
+
+	let mut t = new_tester();
+	t.register_extension(OffchainWorkerExt::new(offchain));
+	t.register_extension(TransactionPoolExt::new(pool));
+	t.register_extension(KeystoreExt(Arc::new(keystore)));
+
+	MockMainchainClient::mutate(|mainchain| {
+		mainchain.validator_sets.push(expected_validator_set());
+		mainchain.unavailable = true;
+	});
+
+	let public = <Test as SigningTypes>::Public::from(public_key);
+	let account = public.clone().into_account();
+
+	t.execute_with(|| {
+		assert_ok!(OctopusAppchain::force_set_next_set_id(Origin::root(), 1));
+		assert_eq!(
+			OctopusAppchain::observing_mainchain(
+				2,
+				&["https://rpc.testnet.near.org".to_string()],
+				b"oct-test.testnet".to_vec(),
+				public,
+				account,
+			),
+			Err("Mainchain unavailable")
+		);
+		assert!(pool_state.read().transactions.is_empty());
+	});
+}
@@ -0,0 +1,185 @@
+//! Volume limits of the transfers crossing the bridge.
+//!
+//! A limit caps the amount of a token, or of all the tokens, transferred in a direction within a
+//! rolling window of blocks. The amount of the rolling window is estimated from the amounts of
+//! the current and the previous fixed windows. Amounts are counted in the units of the tokens on
+//! NEAR.
+//!
+//! A transfer exceeding a limit pauses the transfers of its direction until governance resumes
+//! them. The outbound transfer is reverted, while the notification of the inbound transfer waits
+//! in the retry queue.
+
+use super::*;
+use frame_support::{
+	dispatch::DispatchResultWithPostInfo,
+	storage::{with_transaction, TransactionOutcome},
+};
+use sp_runtime::{
+	helpers_128bit::multiply_by_rational,
+	traits::{AtLeast32BitUnsigned, Saturating, UniqueSaturatedInto, Zero},
+	DispatchError,
+};
+
+/// The direction of a transfer crossing the bridge.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub enum TransferDirection {
+	/// From NEAR to the appchain.
+	Inbound,
+	/// From the appchain to NEAR.
+	Outbound,
+}
+
+/// A token crossing the bridge.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub enum BridgedToken<AssetId> {
+	/// The native token of the appchain.
+	Native,
+	/// A bridged NEP-141 token.
+	Asset(AssetId),
+}
+
+/// The maximum amount transferred within a rolling window of blocks.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct TransferLimit<BlockNumber> {
+	pub max_amount: u128,
+	pub window: BlockNumber,
+}
+
+/// The amounts transferred in the current and the previous windows.
+#[derive(Encode, Decode, Clone, Default, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct TransferVolume<BlockNumber> {
+	pub window_start: BlockNumber,
+	pub current: u128,
+	pub previous: u128,
+}
+
+impl<BlockNumber: AtLeast32BitUnsigned + Copy> TransferVolume<BlockNumber> {
+	/// Move the windows forward to the block `now`.
+	pub fn roll(&mut self, now: BlockNumber, window: BlockNumber) {
+		let elapsed = now.saturating_sub(self.window_start);
+		if window.is_zero() || elapsed >= window.saturating_mul(2u32.into()) {
+			*self = Self { window_start: now, current: 0, previous: 0 };
+		} else if elapsed >= window {
+			self.previous = self.current;
+			self.current = 0;
+			self.window_start = self.window_start.saturating_add(window);
+		}
+	}
+
+	/// The amount transferred within the `window` blocks up to the block `now`.
+	///
+	/// The amount of the previous window is weighted by its overlap with the rolling window.
+	pub fn amount(&self, now: BlockNumber, window: BlockNumber) -> u128 {
+		let elapsed: u128 = now.saturating_sub(self.window_start).unique_saturated_into();
+		let window: u128 = window.unique_saturated_into();
+		if window.is_zero() {
+			return self.current;
+		}
+		let overlap = window.saturating_sub(elapsed);
+		multiply_by_rational(self.previous, overlap, window)
+			.unwrap_or(self.previous)
+			.saturating_add(self.current)
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Ensure that the transfer is within the limits of its direction, without recording it.
+	pub(crate) fn ensure_transfer_allowed(
+		direction: TransferDirection,
+		token: BridgedToken<AssetIdOf<T>>,
+		amount: u128,
+	) -> Result<(), Error<T>> {
+		if <TransfersPaused<T>>::get(direction) {
+			return Err(Error::<T>::TransfersPaused);
+		}
+
+		let now = <frame_system::Pallet<T>>::block_number();
+		for key in [Some(token), None] {
+			if let Some(limit) = <TransferLimits<T>>::get(direction, key) {
+				let mut volume = <TransferVolumes<T>>::get(direction, key);
+				volume.roll(now, limit.window);
+				if volume.amount(now, limit.window).saturating_add(amount) > limit.max_amount {
+					return Err(Error::<T>::TransferLimitExceeded);
+				}
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Record the transfer in the volumes of its direction.
+	pub(crate) fn record_transfer(
+		direction: TransferDirection,
+		token: BridgedToken<AssetIdOf<T>>,
+		amount: u128,
+	) {
+		let now = <frame_system::Pallet<T>>::block_number();
+		for key in [Some(token), None] {
+			if let Some(limit) = <TransferLimits<T>>::get(direction, key) {
+				<TransferVolumes<T>>::mutate(direction, key, |volume| {
+					volume.roll(now, limit.window);
+					volume.current = volume.current.saturating_add(amount);
+				});
+			}
+		}
+	}
+
+	/// Make an outbound transfer in its own transaction, pausing the outbound transfers if it
+	/// exceeds a limit.
+	///
+	/// The transfer checks the limits once it has been made, so only a transfer which would
+	/// succeed otherwise trips them. Such a transfer is reverted and the call succeeds, keeping
+	/// the pause; the `TransferLimitExceeded` event tells the sender that nothing was transferred.
+	pub(crate) fn transfer_outbound(
+		token: BridgedToken<AssetIdOf<T>>,
+		transfer: impl FnOnce() -> DispatchResultWithPostInfo,
+	) -> DispatchResultWithPostInfo {
+		ensure!(
+			!<TransfersPaused<T>>::get(TransferDirection::Outbound),
+			Error::<T>::TransfersPaused
+		);
+
+		let result = with_transaction(|| {
+			let result = transfer();
+			match result {
+				Ok(_) => TransactionOutcome::Commit(result),
+				Err(_) => TransactionOutcome::Rollback(result),
+			}
+		});
+
+		match result {
+			Err(error) if error.error == DispatchError::from(Error::<T>::TransferLimitExceeded) => {
+				<TransfersPaused<T>>::insert(TransferDirection::Outbound, true);
+				log!(warn, "outbound transfer limit of {:?} exceeded, pausing", token);
+				Self::deposit_event(Event::TransferLimitExceeded(
+					TransferDirection::Outbound,
+					token,
+				));
+				Ok(().into())
+			},
+			result => result,
+		}
+	}
+
+	/// Ensure that the inbound transfer is allowed, pausing the inbound transfers if it exceeds
+	/// a limit.
+	pub(crate) fn ensure_inbound_transfer_allowed(
+		token: BridgedToken<AssetIdOf<T>>,
+		amount: u128,
+	) -> Result<(), NotificationResult> {
+		match Self::ensure_transfer_allowed(TransferDirection::Inbound, token, amount) {
+			Ok(()) => Ok(()),
+			Err(error) => {
+				if matches!(error, Error::<T>::TransferLimitExceeded) {
+					<TransfersPaused<T>>::insert(TransferDirection::Inbound, true);
+					log!(warn, "inbound transfer limit of {:?} exceeded, pausing", token);
+					Self::deposit_event(Event::TransferLimitExceeded(
+						TransferDirection::Inbound,
+						token,
+					));
+				}
+				Err(NotificationResult::TransferLimited)
+			},
+		}
+	}
+}
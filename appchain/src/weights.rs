@@ -15,13 +15,17 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-//! Autogenerated weights for pallet_octopus_appchain
+//! Weights for pallet_octopus_appchain
 //!
-//! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI VERSION 4.0.0-dev
-//! DATE: 2021-12-07, STEPS: `20`, REPEAT: 10, LOW RANGE: `[]`, HIGH RANGE: `[]`
-//! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("dev"), DB CACHE: 128
+//! This file is maintained by hand, it is NOT the output of the benchmark CLI.
+//!
+//! The base weights of `force_set_is_activated`, `force_set_next_set_id`,
+//! `force_set_planned_validators` and `lock` come from a benchmark run of 2021-12-07
+//! (STEPS: `20`, REPEAT: 10, EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("dev"))
+//! with the command below. Their storage accesses, like all the weights of the other functions,
+//! are hand-written estimates, to be replaced by running that command against `benchmarking.rs`.
 
-// Executed Command:
+// Command to regenerate this file:
 // ./target/debug/node-template
 // benchmark
 // --chain
@@ -58,14 +62,39 @@ pub trait WeightInfo {
 	fn force_set_next_set_id(b: u32, ) -> Weight;
 	fn force_set_planned_validators(b: u32, ) -> Weight;
 	fn lock() -> Weight;
+	fn set_mainchain_rpc_endpoints(b: u32, ) -> Weight;
+	fn submit_observations(o: u32, v: u32, ) -> Weight;
+	fn mint_asset() -> Weight;
+	fn burn_asset() -> Weight;
+	fn register_asset() -> Weight;
+	fn update_asset() -> Weight;
+	fn deregister_asset() -> Weight;
+	fn set_asset_decimals() -> Weight;
+	fn set_native_token_decimals() -> Weight;
+	fn retry_notification() -> Weight;
+	fn redirect_notification() -> Weight;
+	fn refund_notification() -> Weight;
+	fn set_transfer_limit() -> Weight;
+	fn resume_transfers() -> Weight;
+	fn set_operation_paused() -> Weight;
+	fn set_asset_paused() -> Weight;
+	fn set_bridge_fee() -> Weight;
+	fn set_consensus_threshold() -> Weight;
+	fn lock_asset() -> Weight;
+	fn register_nft_class() -> Weight;
+	fn deregister_nft_class() -> Weight;
+	fn burn_nft() -> Weight;
+	fn lock_nft() -> Weight;
+	fn force_set_appchain_state() -> Weight;
 }
 
 /// Weights for pallet_octopus_appchain using the Substrate node and recommended hardware.
 pub struct SubstrateWeight<T>(PhantomData<T>);
 impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
-	// Storage: OctopusAppchain IsActivated (r:0 w:1)
+	// Storage: OctopusAppchain CurrentAppchainState (r:1 w:1)
 	fn force_set_is_activated() -> Weight {
 		(86_660_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
 			.saturating_add(T::DbWeight::get().writes(1 as Weight))
 	}
 	// Storage: OctopusAppchain NextSetId (r:0 w:1)
@@ -80,21 +109,274 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add((50_000 as Weight).saturating_mul(b as Weight))
 			.saturating_add(T::DbWeight::get().writes(1 as Weight))
 	}
-	// Storage: OctopusAppchain IsActivated (r:1 w:0)
+	// Storage: OctopusAppchain CurrentAppchainState (r:1 w:0)
+	// Storage: OctopusAppchain NativeTokenDecimals (r:1 w:0)
 	// Storage: OctopusUpwardMessages MessageQueue (r:1 w:1)
 	// Storage: OctopusUpwardMessages Nonce (r:1 w:1)
+	// Storage: OctopusAppchain TransfersPaused (r:1 w:0)
+	// Storage: OctopusAppchain TransferLimits (r:2 w:0)
+	// Storage: OctopusAppchain TransferVolumes (r:2 w:2)
+	// Storage: OctopusAppchain BridgeFees (r:1 w:0)
+	// Storage: System Account (r:1 w:1)
+	// Storage: OctopusAppchain PausedOperations (r:1 w:0)
 	fn lock() -> Weight {
 		(1_164_314_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(12 as Weight))
+			.saturating_add(T::DbWeight::get().writes(5 as Weight))
+	}
+	// Storage: OctopusAppchain MainchainRpcEndpoints (r:0 w:1)
+	fn set_mainchain_rpc_endpoints(b: u32, ) -> Weight {
+		(88_414_000 as Weight)
+			.saturating_add((9_000 as Weight).saturating_mul(b as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Session KeyOwner (r:1 w:0)
+	// Storage: Session Validators (r:1 w:0)
+	// Storage: OctopusAppchain NextNotificationId (r:1 w:1)
+	// Storage: OctopusAppchain Observations (r:1 w:1)
+	// Storage: OctopusAppchain Observing (r:1 w:1)
+	// Storage: OctopusAppchain ObservationTallies (r:1 w:1)
+	// Storage: OctopusAppchain NativeTokenDecimals (r:1 w:0)
+	// Storage: OctopusLpos ActiveEra (r:1 w:0)
+	// Storage: OctopusLpos ErasTotalStake (r:1 w:0)
+	// Storage: OctopusLpos ErasStakers (r:1 w:0)
+	// Storage: System Account (r:2 w:2)
+	// Storage: OctopusAppchain NotificationHistory (r:1 w:1)
+	// Storage: OctopusAppchain NotificationsByReceiver (r:1 w:1)
+	// Storage: OctopusAppchain TransfersPaused (r:1 w:0)
+	// Storage: OctopusAppchain TransferLimits (r:2 w:0)
+	// Storage: OctopusAppchain TransferVolumes (r:2 w:2)
+	// Storage: OctopusAppchain PausedOperations (r:1 w:0)
+	// Storage: OctopusAppchain ConsensusThreshold (r:1 w:0)
+	// Storage: OctopusAppchain EraObservations (r:1 w:1)
+	// Storage: OctopusAppchain ObservationParticipants (r:1 w:1)
+	// Storage: OctopusAppchain ConsensusReachedAt (r:1 w:1)
+	fn submit_observations(o: u32, v: u32, ) -> Weight {
+		(0 as Weight)
+			.saturating_add((262_418_000 as Weight).saturating_mul(o as Weight))
+			.saturating_add((9_874_000 as Weight).saturating_mul(v as Weight))
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().reads((21 as Weight).saturating_mul(o as Weight)))
+			.saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(v as Weight)))
+			.saturating_add(T::DbWeight::get().writes((12 as Weight).saturating_mul(o as Weight)))
+			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(v as Weight)))
+	}
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn mint_asset() -> Weight {
+		(182_605_000 as Weight)
 			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	// Storage: OctopusAppchain CurrentAppchainState (r:1 w:0)
+	// Storage: OctopusAppchain TokenIdByAssetId (r:1 w:0)
+	// Storage: OctopusAppchain AssetDecimals (r:1 w:0)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:1 w:1)
+	// Storage: OctopusUpwardMessages MessageQueue (r:1 w:1)
+	// Storage: OctopusUpwardMessages Nonce (r:1 w:1)
+	// Storage: OctopusAppchain TransfersPaused (r:1 w:0)
+	// Storage: OctopusAppchain TransferLimits (r:2 w:0)
+	// Storage: OctopusAppchain TransferVolumes (r:2 w:2)
+	// Storage: OctopusAppchain BridgeFees (r:1 w:0)
+	// Storage: OctopusAppchain PausedOperations (r:1 w:0)
+	// Storage: OctopusAppchain PausedAssets (r:1 w:0)
+	fn burn_asset() -> Weight {
+		(1_093_514_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(15 as Weight))
+			.saturating_add(T::DbWeight::get().writes(6 as Weight))
+	}
+	// Storage: OctopusAppchain AssetIdByName (r:1 w:1)
+	// Storage: OctopusAppchain TokenIdByAssetId (r:1 w:1)
+	fn register_asset() -> Weight {
+		(103_725_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	// Storage: OctopusAppchain AssetIdByName (r:1 w:1)
+	// Storage: OctopusAppchain TokenIdByAssetId (r:1 w:2)
+	// Storage: OctopusAppchain AssetDecimals (r:0 w:1)
+	fn update_asset() -> Weight {
+		(116_942_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+	// Storage: OctopusAppchain AssetIdByName (r:1 w:1)
+	// Storage: OctopusAppchain TokenIdByAssetId (r:0 w:1)
+	// Storage: OctopusAppchain AssetDecimals (r:0 w:1)
+	fn deregister_asset() -> Weight {
+		(99_207_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	// Storage: OctopusAppchain TokenIdByAssetId (r:1 w:0)
+	// Storage: OctopusAppchain AssetDecimals (r:0 w:1)
+	fn set_asset_decimals() -> Weight {
+		(87_126_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: OctopusAppchain NativeTokenDecimals (r:0 w:1)
+	fn set_native_token_decimals() -> Weight {
+		(80_341_000 as Weight)
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: OctopusAppchain FailedNotifications (r:1 w:1)
+	// Storage: OctopusAppchain NativeTokenDecimals (r:1 w:0)
+	// Storage: System Account (r:2 w:2)
+	// Storage: OctopusAppchain NotificationHistory (r:2 w:1)
+	// Storage: OctopusAppchain NotificationsByReceiver (r:1 w:1)
+	// Storage: OctopusAppchain TransfersPaused (r:1 w:0)
+	// Storage: OctopusAppchain TransferLimits (r:2 w:0)
+	// Storage: OctopusAppchain TransferVolumes (r:2 w:2)
+	// Storage: OctopusAppchain PausedOperations (r:1 w:0)
+	fn retry_notification() -> Weight {
+		(214_872_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(13 as Weight))
+			.saturating_add(T::DbWeight::get().writes(7 as Weight))
+	}
+	// Storage: OctopusAppchain FailedNotifications (r:1 w:1)
+	// Storage: OctopusAppchain NativeTokenDecimals (r:1 w:0)
+	// Storage: System Account (r:2 w:2)
+	// Storage: OctopusAppchain NotificationHistory (r:2 w:1)
+	// Storage: OctopusAppchain NotificationsByReceiver (r:2 w:2)
+	// Storage: OctopusAppchain TransfersPaused (r:1 w:0)
+	// Storage: OctopusAppchain TransferLimits (r:2 w:0)
+	// Storage: OctopusAppchain TransferVolumes (r:2 w:2)
+	// Storage: OctopusAppchain PausedOperations (r:1 w:0)
+	fn redirect_notification() -> Weight {
+		(221_530_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(14 as Weight))
+			.saturating_add(T::DbWeight::get().writes(8 as Weight))
+	}
+	// Storage: OctopusAppchain FailedNotifications (r:1 w:1)
+	// Storage: OctopusUpwardMessages MessageQueue (r:1 w:1)
+	// Storage: OctopusUpwardMessages Nonce (r:1 w:1)
+	// Storage: OctopusAppchain NotificationHistory (r:2 w:1)
+	// Storage: OctopusAppchain NotificationsByReceiver (r:1 w:1)
+	fn refund_notification() -> Weight {
+		(1_102_648_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().writes(5 as Weight))
+	}
+	// Storage: OctopusAppchain TransferLimits (r:0 w:1)
+	// Storage: OctopusAppchain TransferVolumes (r:0 w:1)
+	fn set_transfer_limit() -> Weight {
+		(84_196_000 as Weight)
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	// Storage: OctopusAppchain TransfersPaused (r:0 w:1)
+	// Storage: OctopusAppchain TransferVolumes (r:0 w:2)
+	fn resume_transfers() -> Weight {
+		(97_503_000 as Weight)
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	// Storage: OctopusAppchain PausedOperations (r:0 w:1)
+	// Storage: OctopusAppchain NextSetId (r:1 w:1)
+	// Storage: OctopusAppchain Observations (r:1 w:0)
+	// Storage: OctopusAppchain Observing (r:1 w:0)
+	// Storage: OctopusAppchain ObservationTallies (r:1 w:0)
+	// Storage: OctopusAppchain ConsensusThreshold (r:1 w:0)
+	// Storage: OctopusAppchain PlannedValidators (r:0 w:1)
+	// Storage: OctopusAppchain EraObservations (r:1 w:1)
+	fn set_operation_paused() -> Weight {
+		(79_812_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+	// Storage: OctopusAppchain PausedAssets (r:0 w:1)
+	fn set_asset_paused() -> Weight {
+		(80_127_000 as Weight)
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: OctopusAppchain BridgeFees (r:0 w:1)
+	fn set_bridge_fee() -> Weight {
+		(81_204_000 as Weight)
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: OctopusAppchain ConsensusThreshold (r:0 w:1)
+	fn set_consensus_threshold() -> Weight {
+		(78_530_000 as Weight)
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: OctopusAppchain CurrentAppchainState (r:1 w:0)
+	// Storage: OctopusAppchain TokenIdByAssetId (r:1 w:0)
+	// Storage: OctopusAppchain AssetDecimals (r:1 w:0)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	// Storage: System Account (r:1 w:1)
+	// Storage: OctopusUpwardMessages MessageQueue (r:1 w:1)
+	// Storage: OctopusUpwardMessages Nonce (r:1 w:1)
+	// Storage: OctopusAppchain TransfersPaused (r:1 w:0)
+	// Storage: OctopusAppchain TransferLimits (r:2 w:0)
+	// Storage: OctopusAppchain TransferVolumes (r:2 w:2)
+	// Storage: OctopusAppchain BridgeFees (r:1 w:0)
+	// Storage: OctopusAppchain PausedOperations (r:1 w:0)
+	// Storage: OctopusAppchain PausedAssets (r:1 w:0)
+	fn lock_asset() -> Weight {
+		(1_152_309_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(17 as Weight))
+			.saturating_add(T::DbWeight::get().writes(8 as Weight))
+	}
+	// Storage: OctopusAppchain ClassIdByContractId (r:1 w:1)
+	// Storage: OctopusAppchain ContractIdByClassId (r:1 w:1)
+	fn register_nft_class() -> Weight {
+		(104_382_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	// Storage: OctopusAppchain ClassIdByContractId (r:1 w:1)
+	// Storage: OctopusAppchain NftTokenIds (r:1 w:0)
+	// Storage: OctopusAppchain ContractIdByClassId (r:0 w:1)
+	fn deregister_nft_class() -> Weight {
+		(97_516_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
 			.saturating_add(T::DbWeight::get().writes(2 as Weight))
 	}
+	// Storage: OctopusAppchain CurrentAppchainState (r:1 w:0)
+	// Storage: OctopusAppchain PausedOperations (r:1 w:0)
+	// Storage: OctopusAppchain ContractIdByClassId (r:1 w:0)
+	// Storage: OctopusAppchain NftTokenIds (r:1 w:1)
+	// Storage: OctopusAppchain NftMetadata (r:0 w:1)
+	// Storage: Uniques Asset (r:1 w:1)
+	// Storage: Uniques Class (r:1 w:1)
+	// Storage: Uniques Account (r:0 w:1)
+	// Storage: OctopusUpwardMessages MessageQueue (r:1 w:1)
+	// Storage: OctopusUpwardMessages Nonce (r:1 w:1)
+	fn burn_nft() -> Weight {
+		(596_047_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(8 as Weight))
+			.saturating_add(T::DbWeight::get().writes(7 as Weight))
+	}
+	// Storage: OctopusAppchain CurrentAppchainState (r:1 w:0)
+	// Storage: OctopusAppchain PausedOperations (r:1 w:0)
+	// Storage: OctopusAppchain ContractIdByClassId (r:1 w:0)
+	// Storage: Uniques Asset (r:1 w:1)
+	// Storage: Uniques Class (r:1 w:0)
+	// Storage: Uniques Account (r:0 w:2)
+	// Storage: Uniques InstanceMetadataOf (r:1 w:0)
+	// Storage: OctopusUpwardMessages MessageQueue (r:1 w:1)
+	// Storage: OctopusUpwardMessages Nonce (r:1 w:1)
+	fn lock_nft() -> Weight {
+		(648_725_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(8 as Weight))
+			.saturating_add(T::DbWeight::get().writes(5 as Weight))
+	}
+	// Storage: OctopusAppchain CurrentAppchainState (r:1 w:1)
+	fn force_set_appchain_state() -> Weight {
+		(87_125_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
 }
 
 // For backwards compatibility and tests
 impl WeightInfo for () {
-	// Storage: OctopusAppchain IsActivated (r:0 w:1)
+	// Storage: OctopusAppchain CurrentAppchainState (r:1 w:1)
 	fn force_set_is_activated() -> Weight {
 		(86_660_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
 			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
 	}
 	// Storage: OctopusAppchain NextSetId (r:0 w:1)
@@ -109,12 +391,264 @@ impl WeightInfo for () {
 			.saturating_add((50_000 as Weight).saturating_mul(b as Weight))
 			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
 	}
-	// Storage: OctopusAppchain IsActivated (r:1 w:0)
+	// Storage: OctopusAppchain CurrentAppchainState (r:1 w:0)
+	// Storage: OctopusAppchain NativeTokenDecimals (r:1 w:0)
 	// Storage: OctopusUpwardMessages MessageQueue (r:1 w:1)
 	// Storage: OctopusUpwardMessages Nonce (r:1 w:1)
+	// Storage: OctopusAppchain TransfersPaused (r:1 w:0)
+	// Storage: OctopusAppchain TransferLimits (r:2 w:0)
+	// Storage: OctopusAppchain TransferVolumes (r:2 w:2)
+	// Storage: OctopusAppchain BridgeFees (r:1 w:0)
+	// Storage: System Account (r:1 w:1)
+	// Storage: OctopusAppchain PausedOperations (r:1 w:0)
 	fn lock() -> Weight {
 		(1_164_314_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(12 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(5 as Weight))
+	}
+	// Storage: OctopusAppchain MainchainRpcEndpoints (r:0 w:1)
+	fn set_mainchain_rpc_endpoints(b: u32, ) -> Weight {
+		(88_414_000 as Weight)
+			.saturating_add((9_000 as Weight).saturating_mul(b as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Session KeyOwner (r:1 w:0)
+	// Storage: Session Validators (r:1 w:0)
+	// Storage: OctopusAppchain NextNotificationId (r:1 w:1)
+	// Storage: OctopusAppchain Observations (r:1 w:1)
+	// Storage: OctopusAppchain Observing (r:1 w:1)
+	// Storage: OctopusAppchain ObservationTallies (r:1 w:1)
+	// Storage: OctopusAppchain NativeTokenDecimals (r:1 w:0)
+	// Storage: OctopusLpos ActiveEra (r:1 w:0)
+	// Storage: OctopusLpos ErasTotalStake (r:1 w:0)
+	// Storage: OctopusLpos ErasStakers (r:1 w:0)
+	// Storage: System Account (r:2 w:2)
+	// Storage: OctopusAppchain NotificationHistory (r:1 w:1)
+	// Storage: OctopusAppchain NotificationsByReceiver (r:1 w:1)
+	// Storage: OctopusAppchain TransfersPaused (r:1 w:0)
+	// Storage: OctopusAppchain TransferLimits (r:2 w:0)
+	// Storage: OctopusAppchain TransferVolumes (r:2 w:2)
+	// Storage: OctopusAppchain PausedOperations (r:1 w:0)
+	// Storage: OctopusAppchain ConsensusThreshold (r:1 w:0)
+	// Storage: OctopusAppchain EraObservations (r:1 w:1)
+	// Storage: OctopusAppchain ObservationParticipants (r:1 w:1)
+	// Storage: OctopusAppchain ConsensusReachedAt (r:1 w:1)
+	fn submit_observations(o: u32, v: u32, ) -> Weight {
+		(0 as Weight)
+			.saturating_add((262_418_000 as Weight).saturating_mul(o as Weight))
+			.saturating_add((9_874_000 as Weight).saturating_mul(v as Weight))
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((21 as Weight).saturating_mul(o as Weight)))
+			.saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(v as Weight)))
+			.saturating_add(RocksDbWeight::get().writes((12 as Weight).saturating_mul(o as Weight)))
+			.saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(v as Weight)))
+	}
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn mint_asset() -> Weight {
+		(182_605_000 as Weight)
 			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	// Storage: OctopusAppchain CurrentAppchainState (r:1 w:0)
+	// Storage: OctopusAppchain TokenIdByAssetId (r:1 w:0)
+	// Storage: OctopusAppchain AssetDecimals (r:1 w:0)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:1 w:1)
+	// Storage: OctopusUpwardMessages MessageQueue (r:1 w:1)
+	// Storage: OctopusUpwardMessages Nonce (r:1 w:1)
+	// Storage: OctopusAppchain TransfersPaused (r:1 w:0)
+	// Storage: OctopusAppchain TransferLimits (r:2 w:0)
+	// Storage: OctopusAppchain TransferVolumes (r:2 w:2)
+	// Storage: OctopusAppchain BridgeFees (r:1 w:0)
+	// Storage: OctopusAppchain PausedOperations (r:1 w:0)
+	// Storage: OctopusAppchain PausedAssets (r:1 w:0)
+	fn burn_asset() -> Weight {
+		(1_093_514_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(15 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(6 as Weight))
+	}
+	// Storage: OctopusAppchain AssetIdByName (r:1 w:1)
+	// Storage: OctopusAppchain TokenIdByAssetId (r:1 w:1)
+	fn register_asset() -> Weight {
+		(103_725_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
 			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
 	}
+	// Storage: OctopusAppchain AssetIdByName (r:1 w:1)
+	// Storage: OctopusAppchain TokenIdByAssetId (r:1 w:2)
+	// Storage: OctopusAppchain AssetDecimals (r:0 w:1)
+	fn update_asset() -> Weight {
+		(116_942_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+	}
+	// Storage: OctopusAppchain AssetIdByName (r:1 w:1)
+	// Storage: OctopusAppchain TokenIdByAssetId (r:0 w:1)
+	// Storage: OctopusAppchain AssetDecimals (r:0 w:1)
+	fn deregister_asset() -> Weight {
+		(99_207_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	// Storage: OctopusAppchain TokenIdByAssetId (r:1 w:0)
+	// Storage: OctopusAppchain AssetDecimals (r:0 w:1)
+	fn set_asset_decimals() -> Weight {
+		(87_126_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: OctopusAppchain NativeTokenDecimals (r:0 w:1)
+	fn set_native_token_decimals() -> Weight {
+		(80_341_000 as Weight)
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: OctopusAppchain FailedNotifications (r:1 w:1)
+	// Storage: OctopusAppchain NativeTokenDecimals (r:1 w:0)
+	// Storage: System Account (r:2 w:2)
+	// Storage: OctopusAppchain NotificationHistory (r:2 w:1)
+	// Storage: OctopusAppchain NotificationsByReceiver (r:1 w:1)
+	// Storage: OctopusAppchain TransfersPaused (r:1 w:0)
+	// Storage: OctopusAppchain TransferLimits (r:2 w:0)
+	// Storage: OctopusAppchain TransferVolumes (r:2 w:2)
+	// Storage: OctopusAppchain PausedOperations (r:1 w:0)
+	fn retry_notification() -> Weight {
+		(214_872_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(13 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(7 as Weight))
+	}
+	// Storage: OctopusAppchain FailedNotifications (r:1 w:1)
+	// Storage: OctopusAppchain NativeTokenDecimals (r:1 w:0)
+	// Storage: System Account (r:2 w:2)
+	// Storage: OctopusAppchain NotificationHistory (r:2 w:1)
+	// Storage: OctopusAppchain NotificationsByReceiver (r:2 w:2)
+	// Storage: OctopusAppchain TransfersPaused (r:1 w:0)
+	// Storage: OctopusAppchain TransferLimits (r:2 w:0)
+	// Storage: OctopusAppchain TransferVolumes (r:2 w:2)
+	// Storage: OctopusAppchain PausedOperations (r:1 w:0)
+	fn redirect_notification() -> Weight {
+		(221_530_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(14 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(8 as Weight))
+	}
+	// Storage: OctopusAppchain FailedNotifications (r:1 w:1)
+	// Storage: OctopusUpwardMessages MessageQueue (r:1 w:1)
+	// Storage: OctopusUpwardMessages Nonce (r:1 w:1)
+	// Storage: OctopusAppchain NotificationHistory (r:2 w:1)
+	// Storage: OctopusAppchain NotificationsByReceiver (r:1 w:1)
+	fn refund_notification() -> Weight {
+		(1_102_648_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(6 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(5 as Weight))
+	}
+	// Storage: OctopusAppchain TransferLimits (r:0 w:1)
+	// Storage: OctopusAppchain TransferVolumes (r:0 w:1)
+	fn set_transfer_limit() -> Weight {
+		(84_196_000 as Weight)
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	// Storage: OctopusAppchain TransfersPaused (r:0 w:1)
+	// Storage: OctopusAppchain TransferVolumes (r:0 w:2)
+	fn resume_transfers() -> Weight {
+		(97_503_000 as Weight)
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	// Storage: OctopusAppchain PausedOperations (r:0 w:1)
+	// Storage: OctopusAppchain NextSetId (r:1 w:1)
+	// Storage: OctopusAppchain Observations (r:1 w:0)
+	// Storage: OctopusAppchain Observing (r:1 w:0)
+	// Storage: OctopusAppchain ObservationTallies (r:1 w:0)
+	// Storage: OctopusAppchain ConsensusThreshold (r:1 w:0)
+	// Storage: OctopusAppchain PlannedValidators (r:0 w:1)
+	// Storage: OctopusAppchain EraObservations (r:1 w:1)
+	fn set_operation_paused() -> Weight {
+		(79_812_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(6 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+	}
+	// Storage: OctopusAppchain PausedAssets (r:0 w:1)
+	fn set_asset_paused() -> Weight {
+		(80_127_000 as Weight)
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: OctopusAppchain BridgeFees (r:0 w:1)
+	fn set_bridge_fee() -> Weight {
+		(81_204_000 as Weight)
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: OctopusAppchain ConsensusThreshold (r:0 w:1)
+	fn set_consensus_threshold() -> Weight {
+		(78_530_000 as Weight)
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: OctopusAppchain CurrentAppchainState (r:1 w:0)
+	// Storage: OctopusAppchain TokenIdByAssetId (r:1 w:0)
+	// Storage: OctopusAppchain AssetDecimals (r:1 w:0)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	// Storage: System Account (r:1 w:1)
+	// Storage: OctopusUpwardMessages MessageQueue (r:1 w:1)
+	// Storage: OctopusUpwardMessages Nonce (r:1 w:1)
+	// Storage: OctopusAppchain TransfersPaused (r:1 w:0)
+	// Storage: OctopusAppchain TransferLimits (r:2 w:0)
+	// Storage: OctopusAppchain TransferVolumes (r:2 w:2)
+	// Storage: OctopusAppchain BridgeFees (r:1 w:0)
+	// Storage: OctopusAppchain PausedOperations (r:1 w:0)
+	// Storage: OctopusAppchain PausedAssets (r:1 w:0)
+	fn lock_asset() -> Weight {
+		(1_152_309_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(17 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(8 as Weight))
+	}
+	// Storage: OctopusAppchain ClassIdByContractId (r:1 w:1)
+	// Storage: OctopusAppchain ContractIdByClassId (r:1 w:1)
+	fn register_nft_class() -> Weight {
+		(104_382_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	// Storage: OctopusAppchain ClassIdByContractId (r:1 w:1)
+	// Storage: OctopusAppchain NftTokenIds (r:1 w:0)
+	// Storage: OctopusAppchain ContractIdByClassId (r:0 w:1)
+	fn deregister_nft_class() -> Weight {
+		(97_516_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	// Storage: OctopusAppchain CurrentAppchainState (r:1 w:0)
+	// Storage: OctopusAppchain PausedOperations (r:1 w:0)
+	// Storage: OctopusAppchain ContractIdByClassId (r:1 w:0)
+	// Storage: OctopusAppchain NftTokenIds (r:1 w:1)
+	// Storage: OctopusAppchain NftMetadata (r:0 w:1)
+	// Storage: Uniques Asset (r:1 w:1)
+	// Storage: Uniques Class (r:1 w:1)
+	// Storage: Uniques Account (r:0 w:1)
+	// Storage: OctopusUpwardMessages MessageQueue (r:1 w:1)
+	// Storage: OctopusUpwardMessages Nonce (r:1 w:1)
+	fn burn_nft() -> Weight {
+		(596_047_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(8 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(7 as Weight))
+	}
+	// Storage: OctopusAppchain CurrentAppchainState (r:1 w:0)
+	// Storage: OctopusAppchain PausedOperations (r:1 w:0)
+	// Storage: OctopusAppchain ContractIdByClassId (r:1 w:0)
+	// Storage: Uniques Asset (r:1 w:1)
+	// Storage: Uniques Class (r:1 w:0)
+	// Storage: Uniques Account (r:0 w:2)
+	// Storage: Uniques InstanceMetadataOf (r:1 w:0)
+	// Storage: OctopusUpwardMessages MessageQueue (r:1 w:1)
+	// Storage: OctopusUpwardMessages Nonce (r:1 w:1)
+	fn lock_nft() -> Weight {
+		(648_725_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(8 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(5 as Weight))
+	}
+	// Storage: OctopusAppchain CurrentAppchainState (r:1 w:1)
+	fn force_set_appchain_state() -> Weight {
+		(87_125_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
 }
@@ -28,7 +28,10 @@ use frame_support::{
 use frame_system::{ensure_root, offchain::SendTransactionTypes, pallet_prelude::*};
 use pallet_octopus_support::{
 	log,
-	traits::{AppchainInterface, LposInterface, UpwardMessagesInterface, ValidatorsProvider},
+	traits::{
+		AppchainInterface, LposInterface, ObserversProvider, UpwardMessagesInterface,
+		ValidatorsProvider,
+	},
 	types::{EraPayoutPayload, PayloadType, PlanNewEraPayload},
 };
 use pallet_session::historical;
@@ -166,6 +169,10 @@ impl<T: Config> LposInterface<<T as frame_system::Config>::AccountId> for Pallet
 	fn active_total_stake() -> Option<u128> {
 		Self::active_era().map(|active_era| Self::eras_total_stake(active_era.index))
 	}
+
+	fn active_era_index() -> Option<u32> {
+		Self::active_era().map(|active_era| active_era.index)
+	}
 }
 
 /// The current storage version.
@@ -194,6 +201,9 @@ pub mod pallet {
 		/// Something that provides the next validators.
 		type ValidatorsProvider: ValidatorsProvider<Self::AccountId>;
 
+		/// Something that provides the validators voting for the observations of an era.
+		type ObserversProvider: ObserversProvider<Self::AccountId>;
+
 		/// The overarching event type.
 		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
 
@@ -207,6 +217,10 @@ pub mod pallet {
 		#[pallet::constant]
 		type BlocksPerEra: Get<u32>;
 
+		/// Number of reward points for each observation voted for, which reached consensus.
+		#[pallet::constant]
+		type ObservationRewardPoints: Get<u32>;
+
 		/// Number of eras that staked funds must remain bonded for.
 		#[pallet::constant]
 		type BondingDuration: Get<EraIndex>;
@@ -214,6 +228,9 @@ pub mod pallet {
 		/// Interface for interacting with a session pallet.
 		type SessionInterface: self::SessionInterface<Self::AccountId>;
 
+		/// The origin which may update the era payout.
+		type GovernanceOrigin: EnsureOrigin<Self::Origin>;
+
 		/// Weight information for extrinsics in this pallet.
 		type WeightInfo: WeightInfo;
 
@@ -484,10 +501,10 @@ pub mod pallet {
 			Ok(())
 		}
 
-		// Force set era rewards with sudo permissions.
+		// Force set era rewards with governance permissions.
 		#[pallet::weight(<T as Config>::WeightInfo::force_set_era_payout())]
 		pub fn force_set_era_payout(origin: OriginFor<T>, era_payout: u128) -> DispatchResult {
-			ensure_root(origin)?;
+			T::GovernanceOrigin::ensure_origin(origin)?;
 			<EraPayout<T>>::put(era_payout);
 			log!(debug, "force set EraPayout: {:?}", era_payout);
 			Ok(())
@@ -616,7 +633,16 @@ impl<T: Config> Pallet<T> {
 			.collect::<Vec<T::AccountId>>();
 
 		log!(debug, "All validators: {:?}", validators.clone());
-		let expect_points = T::BlocksPerEra::get() / validators.len() as u32 * 80 / 100;
+		let (_, observers) = T::ObserversProvider::observers(index);
+		let observation_points = observers
+			.iter()
+			.fold(0u32, |points, (_, count)| points.saturating_add(*count))
+			.saturating_mul(T::ObservationRewardPoints::get());
+		// A validator is expected to earn 80% of the average points, from the blocks it authors
+		// and the observations it votes for.
+		let average_points =
+			T::BlocksPerEra::get().saturating_add(observation_points) / validators.len() as u32;
+		let expect_points = average_points * 80 / 100;
 		let era_reward_points = <ErasRewardPoints<T>>::get(index);
 		let qualified_validators = era_reward_points
 			.individual
@@ -669,6 +695,7 @@ impl<T: Config> Pallet<T> {
 			// Set ending era reward.
 			<ErasValidatorReward<T>>::insert(&active_era.index, validator_payout);
 
+			Self::reward_observers(active_era.index);
 			let excluded_validators = Self::get_exclude_validators(active_era.index);
 			log!(debug, "exclude validators: {:?}", excluded_validators.clone());
 
@@ -762,6 +789,7 @@ impl<T: Config> Pallet<T> {
 		<ErasRewardPoints<T>>::remove(era_index);
 		<ErasTotalStake<T>>::remove(era_index);
 		ErasStartSessionIndex::<T>::remove(era_index);
+		T::ObserversProvider::clear_observers(era_index);
 	}
 
 	/// Add reward points to validators.
@@ -780,6 +808,17 @@ impl<T: Config> Pallet<T> {
 			});
 		}
 	}
+
+	/// Add reward points to the validators voting for the observations of the era.
+	fn reward_observers(era_index: EraIndex) {
+		let points = T::ObservationRewardPoints::get();
+		let (_, observers) = T::ObserversProvider::observers(era_index);
+		Self::reward_by_ids(
+			observers
+				.into_iter()
+				.map(|(validator, count)| (validator, count.saturating_mul(points))),
+		);
+	}
 }
 
 /// In this implementation `new_session(session)` must be called before `end_session(session-1)`
@@ -188,6 +188,32 @@ impl pallet_assets::Config for Test {
 	type WeightInfo = pallet_assets::weights::SubstrateWeight<Test>;
 }
 
+parameter_types! {
+	pub const ClassDeposit: Balance = 100 * DOLLARS;
+	pub const InstanceDeposit: Balance = 1 * DOLLARS;
+	pub const AttributeDepositBase: Balance = 10 * DOLLARS;
+	pub const DepositPerByte: Balance = 1 * DOLLARS;
+	pub const KeyLimit: u32 = 32;
+	pub const ValueLimit: u32 = 256;
+}
+
+impl pallet_uniques::Config for Test {
+	type Event = Event;
+	type ClassId = u128;
+	type InstanceId = u128;
+	type Currency = Balances;
+	type ForceOrigin = EnsureRoot<AccountId>;
+	type ClassDeposit = ClassDeposit;
+	type InstanceDeposit = InstanceDeposit;
+	type MetadataDepositBase = MetadataDepositBase;
+	type AttributeDepositBase = AttributeDepositBase;
+	type DepositPerByte = DepositPerByte;
+	type StringLimit = StringLimit;
+	type KeyLimit = KeyLimit;
+	type ValueLimit = ValueLimit;
+	type WeightInfo = pallet_uniques::weights::SubstrateWeight<Test>;
+}
+
 pub struct OctopusAppCrypto;
 
 impl frame_system::offchain::AppCrypto<<Signature as Verify>::Signer, Signature>
@@ -216,11 +242,13 @@ construct_runtime!(
 		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
 		Timestamp: pallet_timestamp::{Pallet, Call, Storage, Inherent},
 		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
-		OctopusAppchain: pallet_octopus_appchain::{Pallet, Call, Storage, Config<T>, Event<T>, ValidateUnsigned}, // must before session
+		OctopusAppchain: pallet_octopus_appchain::{Pallet, Call, Storage, Config<T>, Event<T>, Origin, ValidateUnsigned}, // must before session
 		OctopusLpos: pallet_octopus_lpos::{Pallet, Call, Config, Storage, Event<T>},
 		OctopusUpwardMessages: pallet_octopus_upward_messages::{Pallet, Call, Storage, Event<T>},
 		Session: pallet_session::{Pallet, Call, Storage, Event, Config<T>},
+		Historical: pallet_session::historical::{Pallet},
 		Assets: pallet_assets::{Pallet, Call, Storage, Event<T>, Config<T>},
+		Uniques: pallet_uniques::{Pallet, Call, Storage, Event<T>},
 	}
 );
 
@@ -229,21 +257,53 @@ parameter_types! {
 	   pub const GracePeriod: u32 = 10;
 	   pub const UnsignedPriority: u64 = 1 << 21;
 	   pub const RequestEventLimit: u32 = 10;
+	   pub const NotificationHistoryDepth: u32 = 10;
+	   pub const ObservationHistoryDepth: u32 = 10;
+	   pub const LateVotePeriod: BlockNumber = 10;
+	   pub const MaxNotificationsPerReceiver: u32 = 5;
+	   pub const MaxPrunedPerBlock: u32 = 5;
+	   pub const MaxRpcEndpoints: u32 = 3;
+	   pub const MaxRpcEndpointLength: u32 = 128;
+	   pub const MaxValidators: u32 = 100;
+	   pub const MaxObservationsPerId: u32 = 100;
+	   pub const MaxNotificationRetries: u32 = 3;
+	   pub const MaxMainchainCallWeight: Weight = 500_000_000;
 	   pub const UpwardMessagesLimit: u32 = 10;
 }
 
 impl pallet_octopus_appchain::Config for Test {
 	type AuthorityId = OctopusAppCrypto;
 	type Event = Event;
+	type Origin = Origin;
 	type Call = Call;
 	type PalletId = OctopusAppchainPalletId;
 	type LposInterface = OctopusLpos;
 	type UpwardMessagesInterface = OctopusUpwardMessages;
+	type GovernanceOrigin = EnsureRoot<AccountId>;
+	type PauseOrigin = EnsureRoot<AccountId>;
+	type MainchainCallFilter = frame_support::traits::Everything;
+	type ValidatorSet = Historical;
+	type ReportConflictingObservation = ();
+	type OnBridgeFee = ();
+	type OnAssetBridgeFee = ();
+	type MainchainClient = pallet_octopus_appchain::NearMainchainClient<Test>;
 	type Currency = Balances;
 	type Assets = Assets;
+	type Uniques = Uniques;
 	type GracePeriod = GracePeriod;
 	type UnsignedPriority = UnsignedPriority;
 	type RequestEventLimit = RequestEventLimit;
+	type NotificationHistoryDepth = NotificationHistoryDepth;
+	type ObservationHistoryDepth = ObservationHistoryDepth;
+	type LateVotePeriod = LateVotePeriod;
+	type MaxNotificationsPerReceiver = MaxNotificationsPerReceiver;
+	type MaxPrunedPerBlock = MaxPrunedPerBlock;
+	type MaxRpcEndpoints = MaxRpcEndpoints;
+	type MaxRpcEndpointLength = MaxRpcEndpointLength;
+	type MaxValidators = MaxValidators;
+	type MaxObservationsPerId = MaxObservationsPerId;
+	type MaxNotificationRetries = MaxNotificationRetries;
+	type MaxMainchainCallWeight = MaxMainchainCallWeight;
 	type WeightInfo = ();
 }
 
@@ -251,6 +311,7 @@ parameter_types! {
 	pub const SessionsPerEra: sp_staking::SessionIndex = 6;
 	pub const BondingDuration: pallet_octopus_lpos::EraIndex = 24 * 28;
 	pub const BlocksPerEra: u32 = EPOCH_DURATION_IN_BLOCKS * 6 / (SECS_PER_BLOCK as u32);
+	pub const ObservationRewardPoints: u32 = 1;
 }
 
 impl Config for Test {
@@ -261,11 +322,14 @@ impl Config for Test {
 	type SessionsPerEra = SessionsPerEra;
 	type BondingDuration = BondingDuration;
 	type BlocksPerEra = BlocksPerEra;
+	type ObservationRewardPoints = ObservationRewardPoints;
 	type SessionInterface = Self;
 	type AppchainInterface = OctopusAppchain;
 	type UpwardMessagesInterface = OctopusUpwardMessages;
 	type PalletId = OctopusAppchainPalletId;
 	type ValidatorsProvider = OctopusAppchain;
+	type ObserversProvider = OctopusAppchain;
+	type GovernanceOrigin = EnsureRoot<AccountId>;
 	type WeightInfo = ();
 }
 
@@ -3,6 +3,9 @@
 pub mod traits;
 pub mod types;
 
+#[cfg(test)]
+mod tests;
+
 pub(crate) const LOG_TARGET: &'static str = "runtime::octopus-support";
 
 // syntactic sugar for logging.
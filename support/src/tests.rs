@@ -0,0 +1,180 @@
+use crate::types::{NearAccountId, NearAccountIdError};
+use sp_std::{convert::TryFrom, str::FromStr};
+
+/// A xorshift generator, so that the property tests are reproducible.
+struct Rng(u64);
+
+impl Rng {
+	fn next(&mut self) -> u64 {
+		self.0 ^= self.0 << 13;
+		self.0 ^= self.0 >> 7;
+		self.0 ^= self.0 << 17;
+		self.0
+	}
+
+	fn below(&mut self, n: usize) -> usize {
+		(self.next() % n as u64) as usize
+	}
+
+	fn string_of(&mut self, alphabet: &[u8], max_len: usize) -> String {
+		let len = self.below(max_len + 1);
+		(0..len).map(|_| alphabet[self.below(alphabet.len())] as char).collect()
+	}
+}
+
+/// The rules of NEAR account ids, written after their definition rather than as a scan.
+fn is_valid_by_definition(account_id: &str) -> bool {
+	(NearAccountId::MIN_LEN..=NearAccountId::MAX_LEN).contains(&account_id.len())
+		&& account_id.split('.').all(|part| {
+			part.split(|c| c == '-' || c == '_').all(|word| {
+				!word.is_empty()
+					&& word.bytes().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+			})
+		})
+}
+
+#[test]
+fn test_valid_near_account_ids() {
+	for account_id in [
+		"aa",
+		"a-b",
+		"a_b",
+		"near",
+		"test-account.testnet",
+		"octopus_network.near",
+		"wrap.testnet",
+		"0xabc.near",
+		"a.b.c.d",
+		"98793cd91a3f870fb126f66285808c7e094afcfc4eda8a970f6648cdf0dbd6de",
+		"a2345678901234567890123456789012345678901234567890123456789.near",
+	] {
+		assert_eq!(NearAccountId::validate(account_id), Ok(()), "{}", account_id);
+	}
+}
+
+#[test]
+fn test_invalid_near_account_ids() {
+	for (account_id, error) in [
+		("", NearAccountIdError::TooShort),
+		("a", NearAccountIdError::TooShort),
+		(
+			"a234567890123456789012345678901234567890123456789012345678901.near",
+			NearAccountIdError::TooLong,
+		),
+		("Alice.near", NearAccountIdError::InvalidCharacter),
+		("alice near", NearAccountIdError::InvalidCharacter),
+		("alice@near", NearAccountIdError::InvalidCharacter),
+		("alice.néar", NearAccountIdError::InvalidCharacter),
+		(".near", NearAccountIdError::RedundantSeparator),
+		("near.", NearAccountIdError::RedundantSeparator),
+		("-near", NearAccountIdError::RedundantSeparator),
+		("near_", NearAccountIdError::RedundantSeparator),
+		("alice..near", NearAccountIdError::RedundantSeparator),
+		("alice-.near", NearAccountIdError::RedundantSeparator),
+		("alice._near", NearAccountIdError::RedundantSeparator),
+		("a__b", NearAccountIdError::RedundantSeparator),
+	] {
+		assert_eq!(NearAccountId::validate(account_id), Err(error), "{}", account_id);
+	}
+}
+
+#[test]
+fn test_implicit_near_account_ids() {
+	let implicit = "98793cd91a3f870fb126f66285808c7e094afcfc4eda8a970f6648cdf0dbd6de";
+	assert!(NearAccountId::from_str(implicit).unwrap().is_implicit());
+	assert!(!NearAccountId::from_str(&implicit[1..]).unwrap().is_implicit());
+	assert!(!NearAccountId::from_str("test-account.testnet").unwrap().is_implicit());
+	assert_eq!(
+		NearAccountId::from_str(&implicit.to_uppercase()),
+		Err(NearAccountIdError::InvalidCharacter)
+	);
+}
+
+#[test]
+fn test_near_account_id_conversions() {
+	let account_id = NearAccountId::try_from("wrap.testnet".to_string()).unwrap();
+	assert_eq!(account_id.as_str(), "wrap.testnet");
+	assert_eq!(String::from(account_id), "wrap.testnet".to_string());
+	assert_eq!(
+		NearAccountId::try_from("Wrap.testnet".to_string()),
+		Err(NearAccountIdError::InvalidCharacter)
+	);
+}
+
+#[test]
+fn test_near_account_id_matches_definition() {
+	let mut rng = Rng(0x5eed_0f_0c70_9005);
+	for _ in 0..100_000 {
+		let account_id = rng.string_of(b"ab09.-_A ", 70);
+		assert_eq!(
+			NearAccountId::validate(&account_id).is_ok(),
+			is_valid_by_definition(&account_id),
+			"{:?}",
+			account_id
+		);
+	}
+}
+
+#[test]
+fn test_near_account_id_properties() {
+	let mut rng = Rng(0x0c70_9005_5eed);
+	for _ in 0..10_000 {
+		// Build a valid account id from random words and separators.
+		let mut account_id = String::new();
+		for i in 0..=rng.below(4) {
+			if i > 0 {
+				account_id.push(b".-_"[rng.below(3)] as char);
+			}
+			let word = rng.string_of(b"abcdefghijklmnopqrstuvwxyz0123456789", 12);
+			account_id.push_str(if word.is_empty() { "a" } else { &word });
+		}
+		if account_id.len() < NearAccountId::MIN_LEN {
+			account_id.push('0');
+		}
+		if account_id.len() > NearAccountId::MAX_LEN {
+			continue;
+		}
+		assert_eq!(NearAccountId::validate(&account_id), Ok(()), "{:?}", account_id);
+
+		// An uppercase letter makes it invalid.
+		let upper = account_id.to_uppercase();
+		if upper != account_id {
+			assert_eq!(
+				NearAccountId::validate(&upper),
+				Err(NearAccountIdError::InvalidCharacter),
+				"{:?}",
+				upper
+			);
+		}
+
+		// A separator at either end makes it invalid.
+		let separator = b".-_"[rng.below(3)] as char;
+		let prefixed = format!("{}{}", separator, account_id);
+		let suffixed = format!("{}{}", account_id, separator);
+		for account_id in [prefixed, suffixed] {
+			if account_id.len() <= NearAccountId::MAX_LEN {
+				assert_eq!(
+					NearAccountId::validate(&account_id),
+					Err(NearAccountIdError::RedundantSeparator),
+					"{:?}",
+					account_id
+				);
+			}
+		}
+	}
+}
+
+#[test]
+fn test_near_account_id_fuzz() {
+	let mut rng = Rng(0xdead_beef);
+	for _ in 0..100_000 {
+		let len = rng.below(80);
+		let bytes: Vec<u8> = (0..len).map(|_| rng.next() as u8).collect();
+		let account_id = String::from_utf8_lossy(&bytes);
+		if let Ok(account_id) = NearAccountId::from_str(&account_id) {
+			let len = account_id.as_str().len();
+			assert!((NearAccountId::MIN_LEN..=NearAccountId::MAX_LEN).contains(&len));
+			assert!(is_valid_by_definition(account_id.as_str()));
+		}
+	}
+}
@@ -20,6 +20,18 @@ pub trait LposInterface<AccountId> {
 	fn active_stake_of(who: &AccountId) -> u128;
 
 	fn active_total_stake() -> Option<u128>;
+
+	fn active_era_index() -> Option<u32>;
+}
+
+/// Something that records the validators voting for the observations which reached consensus.
+pub trait ObserversProvider<AccountId> {
+	/// The number of observations which reached consensus in the era, and the number of them
+	/// each validator voted for.
+	fn observers(era_index: u32) -> (u32, Vec<(AccountId, u32)>);
+
+	/// Clear the records of the era.
+	fn clear_observers(era_index: u32);
 }
 
 pub trait UpwardMessagesInterface<AccountId> {
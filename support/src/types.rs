@@ -7,6 +7,9 @@ use alloc::string::{String, ToString};
 use borsh::{BorshDeserialize, BorshSerialize};
 use codec::{Decode, Encode};
 use scale_info::TypeInfo;
+use serde::Deserialize;
+#[cfg(feature = "std")]
+use serde::Serialize;
 use sp_runtime::RuntimeDebug;
 use sp_std::prelude::*;
 
@@ -16,6 +19,9 @@ pub enum PayloadType {
 	BurnAsset,
 	PlanNewEra,
 	EraPayout,
+	LockAsset,
+	BurnNft,
+	LockNft,
 }
 
 #[derive(BorshSerialize, BorshDeserialize, Clone, PartialEq, Eq, RuntimeDebug)]
@@ -33,6 +39,67 @@ pub struct BurnAssetPayload {
 	pub amount: u128,
 }
 
+/// An appchain-native asset locked in escrow, to be minted as a wrapped token on NEAR.
+#[derive(BorshSerialize, BorshDeserialize, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct LockAssetPayload {
+	pub asset_id: u32,
+	pub sender: String,
+	pub receiver_id: String,
+	pub amount: u128,
+}
+
+/// An NFT bridged from a NEP-171 contract burnt, to be unlocked on NEAR.
+#[derive(BorshSerialize, BorshDeserialize, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct BurnNftPayload {
+	pub contract_id: String,
+	pub token_id: String,
+	pub sender: String,
+	pub receiver_id: String,
+}
+
+/// An appchain-native NFT locked in escrow, to be minted as a wrapped NFT on NEAR.
+#[derive(BorshSerialize, BorshDeserialize, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct LockNftPayload {
+	pub class: u128,
+	pub instance: u128,
+	pub sender: String,
+	pub receiver_id: String,
+	pub metadata: TokenMetadata,
+}
+
+/// The metadata of an NFT, as defined by NEP-177.
+///
+/// The hashes are base64 encoded and the timestamps are unix epoch in milliseconds, as in the
+/// JSON of the NEAR contracts.
+#[derive(
+	BorshSerialize,
+	BorshDeserialize,
+	Deserialize,
+	Encode,
+	Decode,
+	Clone,
+	Default,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	TypeInfo,
+)]
+#[cfg_attr(feature = "std", derive(Serialize))]
+pub struct TokenMetadata {
+	pub title: Option<String>,
+	pub description: Option<String>,
+	pub media: Option<String>,
+	pub media_hash: Option<String>,
+	pub copies: Option<u64>,
+	pub issued_at: Option<String>,
+	pub expires_at: Option<String>,
+	pub starts_at: Option<String>,
+	pub updated_at: Option<String>,
+	pub extra: Option<String>,
+	pub reference: Option<String>,
+	pub reference_hash: Option<String>,
+}
+
 #[derive(BorshSerialize, BorshDeserialize, Clone, PartialEq, Eq, RuntimeDebug)]
 pub struct PlanNewEraPayload {
 	pub new_era: u32,
@@ -43,3 +110,92 @@ pub struct EraPayoutPayload {
 	pub end_era: u32,
 	pub excluded_validators: Vec<String>,
 }
+
+/// The reason why an account id is not a valid NEAR account id.
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum NearAccountIdError {
+	/// The account id is shorter than `NearAccountId::MIN_LEN`.
+	TooShort,
+	/// The account id is longer than `NearAccountId::MAX_LEN`.
+	TooLong,
+	/// The account id contains a character other than a lowercase letter, a digit, or one of
+	/// the separators `.`, `-` and `_`.
+	InvalidCharacter,
+	/// The account id starts or ends with a separator, or has two consecutive separators.
+	RedundantSeparator,
+}
+
+/// An account id on NEAR.
+///
+/// An account id consists of parts separated by `.`, each part consisting of lowercase
+/// alphanumeric characters separated by either `-` or `_`. An implicit account id, the hex
+/// encoding of a 32 bytes public key, is also a valid named account id.
+#[derive(Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct NearAccountId(String);
+
+impl NearAccountId {
+	pub const MIN_LEN: usize = 2;
+	pub const MAX_LEN: usize = 64;
+
+	/// Check an account id against the rules of NEAR.
+	pub fn validate(account_id: &str) -> Result<(), NearAccountIdError> {
+		if account_id.len() < Self::MIN_LEN {
+			return Err(NearAccountIdError::TooShort);
+		}
+		if account_id.len() > Self::MAX_LEN {
+			return Err(NearAccountIdError::TooLong);
+		}
+
+		// Whether the previous character is a separator, true at the start of the account id.
+		let mut after_separator = true;
+		for c in account_id.bytes() {
+			match c {
+				b'a'..=b'z' | b'0'..=b'9' => after_separator = false,
+				b'.' | b'-' | b'_' => {
+					if after_separator {
+						return Err(NearAccountIdError::RedundantSeparator);
+					}
+					after_separator = true;
+				},
+				_ => return Err(NearAccountIdError::InvalidCharacter),
+			}
+		}
+		if after_separator {
+			return Err(NearAccountIdError::RedundantSeparator);
+		}
+		Ok(())
+	}
+
+	/// Whether this is an implicit account id, i.e. 64 lowercase hex characters.
+	pub fn is_implicit(&self) -> bool {
+		self.0.len() == 64 && self.0.bytes().all(|c| matches!(c, b'a'..=b'f' | b'0'..=b'9'))
+	}
+
+	pub fn as_str(&self) -> &str {
+		&self.0
+	}
+}
+
+impl sp_std::str::FromStr for NearAccountId {
+	type Err = NearAccountIdError;
+
+	fn from_str(account_id: &str) -> Result<Self, Self::Err> {
+		Self::validate(account_id)?;
+		Ok(Self(account_id.to_string()))
+	}
+}
+
+impl sp_std::convert::TryFrom<String> for NearAccountId {
+	type Error = NearAccountIdError;
+
+	fn try_from(account_id: String) -> Result<Self, Self::Error> {
+		Self::validate(&account_id)?;
+		Ok(Self(account_id))
+	}
+}
+
+impl From<NearAccountId> for String {
+	fn from(account_id: NearAccountId) -> Self {
+		account_id.0
+	}
+}
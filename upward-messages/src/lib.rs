@@ -148,7 +148,11 @@ pub mod pallet {
 impl<T: Config> UpwardMessagesInterface<<T as frame_system::Config>::AccountId> for Pallet<T> {
 	fn submit(_who: &T::AccountId, payload_type: PayloadType, payload: &[u8]) -> DispatchResult {
 		match payload_type {
-			PayloadType::Lock | PayloadType::BurnAsset => {
+			PayloadType::Lock
+			| PayloadType::BurnAsset
+			| PayloadType::LockAsset
+			| PayloadType::BurnNft
+			| PayloadType::LockNft => {
 				ensure!(
 					MessageQueue::<T>::get().len() < T::UpwardMessagesLimit::get() as usize,
 					Error::<T>::QueueSizeLimitReached,
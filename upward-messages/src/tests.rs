@@ -103,6 +103,10 @@ fn test_submit_exceeds_queue_limit() {
 			OctopusUpwardMessages::submit(&who, PayloadType::BurnAsset, &vec![0, 1, 2]),
 			Error::<Test>::QueueSizeLimitReached,
 		);
+		assert_noop!(
+			OctopusUpwardMessages::submit(&who, PayloadType::LockAsset, &vec![0, 1, 2]),
+			Error::<Test>::QueueSizeLimitReached,
+		);
 	})
 }
 